@@ -1,4 +1,6 @@
 use actix_web::{web, App, HttpServer, HttpResponse, middleware};
+use cybersomething_core::math::defensible::DefensibleZonePlan;
+use cybersomething_core::math::risk_index::{RiskCalculator, RiskWeights, RiskZone};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -11,16 +13,34 @@ pub struct RiskQueryRequest {
 #[derive(Serialize)]
 pub struct RiskResponse {
     pub risk_index: f64,
-    pub defensible_zone_m: (u32, u32, u32),
+    pub defensible_zone: DefensibleZonePlan,
     pub recommendation: String,
 }
 
 async fn compute_risk(req: web::Json<RiskQueryRequest>) -> HttpResponse {
-    // Use cybersomething::core::math::risk_index::RiskCalculator
+    let calculator = RiskCalculator::new(RiskWeights::default());
+    let risk_index = calculator.compute_risk(
+        req.vegetation_density,
+        req.invasive_grass_percent,
+        req.slope_degrees,
+    );
+    let plan = calculator.defensible_zone(risk_index);
+
+    let level = match plan.risk_zone {
+        RiskZone::Low => "Low",
+        RiskZone::Medium => "Medium",
+        RiskZone::High => "High",
+    };
+    let recommendation = format!(
+        "{} risk: implement 0-{}m defensible space",
+        level,
+        plan.outer_radius_m()
+    );
+
     HttpResponse::Ok().json(RiskResponse {
-        risk_index: 0.65,
-        defensible_zone_m: (100, 200, 20),
-        recommendation: "Medium risk: implement 10-30m defensible space".to_string(),
+        risk_index,
+        defensible_zone: plan,
+        recommendation,
     })
 }
 
@@ -0,0 +1,314 @@
+//! Defensible-space grass-height rules
+//! Maximum grass height by distance d (m) from the nearest structure, either as
+//! discrete bands (0–1.5 m: 6 cm, 1.5–10 m: 10 cm, 10–30 m: 20 cm) or the
+//! linear rule h_d = 6 + 1.4·d over 0–10 m, plus risk-zone ring plans.
+
+use crate::math::risk_index::{RiskThresholds, RiskZone};
+use crate::utils::constants::defensible_zones::*;
+use serde::{Deserialize, Serialize};
+
+/// Single distance band of a grass-height rule set
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GrassHeightRule {
+    /// Constant cap over [inner_m, outer_m)
+    Band { inner_m: f64, outer_m: f64, max_height_cm: f64 },
+    /// h_d = base_cm + cm_per_m·d over [inner_m, outer_m)
+    Linear { inner_m: f64, outer_m: f64, base_cm: f64, cm_per_m: f64 },
+}
+
+impl GrassHeightRule {
+    pub fn inner_m(&self) -> f64 {
+        match self {
+            Self::Band { inner_m, .. } | Self::Linear { inner_m, .. } => *inner_m,
+        }
+    }
+
+    pub fn outer_m(&self) -> f64 {
+        match self {
+            Self::Band { outer_m, .. } | Self::Linear { outer_m, .. } => *outer_m,
+        }
+    }
+
+    /// Whether this rule governs distance d
+    pub fn covers(&self, distance_m: f64) -> bool {
+        distance_m >= self.inner_m() && distance_m < self.outer_m()
+    }
+
+    /// Maximum allowed grass height (cm) at distance d
+    pub fn max_height_cm(&self, distance_m: f64) -> f64 {
+        match self {
+            Self::Band { max_height_cm, .. } => *max_height_cm,
+            Self::Linear { base_cm, cm_per_m, .. } => base_cm + cm_per_m * distance_m,
+        }
+    }
+
+    /// Human-readable rule, e.g. "1.5-10 m: 10 cm"
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Band { inner_m, outer_m, max_height_cm } => {
+                format!("{}-{} m: {} cm", inner_m, outer_m, max_height_cm)
+            }
+            Self::Linear { inner_m, outer_m, base_cm, cm_per_m } => {
+                format!("{}-{} m: h = {} + {}·d cm", inner_m, outer_m, base_cm, cm_per_m)
+            }
+        }
+    }
+}
+
+/// Ordered grass-height rules for a jurisdiction or HOA
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrassHeightRuleSet {
+    pub name: String,
+    pub rules: Vec<GrassHeightRule>,
+}
+
+impl GrassHeightRuleSet {
+    /// README discrete bands: 0–1.5 m 6 cm, 1.5–10 m 10 cm, 10–30 m 20 cm
+    pub fn banded_default() -> Self {
+        Self {
+            name: "banded".to_string(),
+            rules: vec![
+                GrassHeightRule::Band { inner_m: 0.0, outer_m: 1.5, max_height_cm: 6.0 },
+                GrassHeightRule::Band { inner_m: 1.5, outer_m: 10.0, max_height_cm: 10.0 },
+                GrassHeightRule::Band { inner_m: 10.0, outer_m: 30.0, max_height_cm: 20.0 },
+            ],
+        }
+    }
+
+    /// README linear rule h_d = 6 + 1.4·d (0–10 m), then 20 cm to 30 m
+    pub fn linear_default() -> Self {
+        Self {
+            name: "linear".to_string(),
+            rules: vec![
+                GrassHeightRule::Linear { inner_m: 0.0, outer_m: 10.0, base_cm: 6.0, cm_per_m: 1.4 },
+                GrassHeightRule::Band { inner_m: 10.0, outer_m: 30.0, max_height_cm: 20.0 },
+            ],
+        }
+    }
+
+    /// First rule covering distance d, with its height cap (cm)
+    pub fn limit_at(&self, distance_m: f64) -> Option<(usize, f64)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.covers(distance_m))
+            .map(|(idx, rule)| (idx, rule.max_height_cm(distance_m)))
+    }
+
+    /// Outermost distance regulated by any rule
+    pub fn max_distance_m(&self) -> f64 {
+        self.rules.iter().map(|r| r.outer_m()).fold(0.0, f64::max)
+    }
+}
+
+/// Ring around a structure with its grass-height cap
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DefensibleRing {
+    pub inner_radius_m: f64,
+    pub outer_radius_m: f64,
+    pub max_grass_height_cm: f64,
+}
+
+impl DefensibleRing {
+    pub fn new(inner_radius_m: f64, outer_radius_m: f64, max_grass_height_cm: f64) -> Self {
+        Self {
+            inner_radius_m,
+            outer_radius_m,
+            max_grass_height_cm,
+        }
+    }
+}
+
+/// Jurisdiction rule table: rings required for each risk zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefensibleRuleTable {
+    pub jurisdiction: String,
+    pub low: Vec<DefensibleRing>,
+    pub medium: Vec<DefensibleRing>,
+    pub high: Vec<DefensibleRing>,
+}
+
+impl DefensibleRuleTable {
+    /// County weed-ordinance table (docs/MATH.md): Low 0-1 m bare, 1-10 m 10 cm,
+    /// 10 m+ 20 cm; Medium 0-10 m 10 cm, 10 m+ 20 cm; High full 0-30 m
+    /// defensible space. Open-ended "10 m+" rings stop at the extended zone.
+    pub fn county_ordinance() -> Self {
+        let zero = ZONE_ZERO_M as f64;
+        let reduction = ZONE_REDUCTION_M as f64;
+        let extended = ZONE_EXTENDED_M as f64;
+        let full = vec![
+            DefensibleRing::new(0.0, zero, GRASS_HEIGHT_ZERO_CM as f64),
+            DefensibleRing::new(zero, reduction, GRASS_HEIGHT_REDUCTION_CM as f64),
+            DefensibleRing::new(reduction, extended, GRASS_HEIGHT_EXTENDED_CM as f64),
+        ];
+
+        Self {
+            jurisdiction: "county_ordinance".to_string(),
+            low: full.clone(),
+            medium: vec![
+                DefensibleRing::new(0.0, reduction, GRASS_HEIGHT_REDUCTION_CM as f64),
+                DefensibleRing::new(reduction, extended, GRASS_HEIGHT_EXTENDED_CM as f64),
+            ],
+            high: full,
+        }
+    }
+
+    /// Stricter HOA landscaping table (lawns kept short to 30 m)
+    pub fn hoa() -> Self {
+        Self {
+            jurisdiction: "hoa".to_string(),
+            low: vec![DefensibleRing::new(0.0, 30.0, 10.0)],
+            medium: vec![
+                DefensibleRing::new(0.0, 1.5, 6.0),
+                DefensibleRing::new(1.5, 30.0, 10.0),
+            ],
+            high: vec![
+                DefensibleRing::new(0.0, 1.5, 0.0),
+                DefensibleRing::new(1.5, 30.0, 6.0),
+            ],
+        }
+    }
+
+    pub fn rings_for(&self, zone: RiskZone) -> &[DefensibleRing] {
+        match zone {
+            RiskZone::Low => &self.low,
+            RiskZone::Medium => &self.medium,
+            RiskZone::High => &self.high,
+        }
+    }
+
+    /// Plan for a parcel with the given risk index under a jurisdiction's thresholds
+    pub fn plan(&self, risk_index: f64, thresholds: &RiskThresholds) -> DefensibleZonePlan {
+        self.plan_for_zone(RiskZone::classify(risk_index, thresholds))
+    }
+
+    /// Plan for an already-classified risk zone
+    pub fn plan_for_zone(&self, risk_zone: RiskZone) -> DefensibleZonePlan {
+        DefensibleZonePlan {
+            jurisdiction: self.jurisdiction.clone(),
+            risk_zone,
+            rings: self.rings_for(risk_zone).to_vec(),
+        }
+    }
+}
+
+impl Default for DefensibleRuleTable {
+    fn default() -> Self {
+        Self::county_ordinance()
+    }
+}
+
+/// Defensible-space plan returned to clients and stored per parcel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefensibleZonePlan {
+    pub jurisdiction: String,
+    pub risk_zone: RiskZone,
+    pub rings: Vec<DefensibleRing>,
+}
+
+impl DefensibleZonePlan {
+    /// Outer edge of the managed area (m)
+    pub fn outer_radius_m(&self) -> f64 {
+        self.rings.iter().map(|r| r.outer_radius_m).fold(0.0, f64::max)
+    }
+
+    /// Grass-height cap at distance d, if regulated
+    pub fn max_grass_height_cm(&self, distance_m: f64) -> Option<f64> {
+        self.rings
+            .iter()
+            .find(|r| distance_m >= r.inner_radius_m && distance_m < r.outer_radius_m)
+            .map(|r| r.max_grass_height_cm)
+    }
+}
+
+impl From<&DefensibleZonePlan> for GrassHeightRuleSet {
+    fn from(plan: &DefensibleZonePlan) -> Self {
+        Self {
+            name: plan.jurisdiction.clone(),
+            rules: plan
+                .rings
+                .iter()
+                .map(|r| GrassHeightRule::Band {
+                    inner_m: r.inner_radius_m,
+                    outer_m: r.outer_radius_m,
+                    max_height_cm: r.max_grass_height_cm,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banded_limits() {
+        let rules = GrassHeightRuleSet::banded_default();
+        assert_eq!(rules.limit_at(1.0), Some((0, 6.0)));
+        assert_eq!(rules.limit_at(5.0), Some((1, 10.0)));
+        assert_eq!(rules.limit_at(25.0), Some((2, 20.0)));
+        assert_eq!(rules.limit_at(35.0), None);
+    }
+
+    #[test]
+    fn test_linear_rule() {
+        let rules = GrassHeightRuleSet::linear_default();
+        let (idx, cap) = rules.limit_at(5.0).unwrap();
+        assert_eq!(idx, 0);
+        assert!((cap - 13.0).abs() < 1e-9); // 6 + 1.4·5
+        assert_eq!(rules.max_distance_m(), 30.0);
+    }
+
+    #[test]
+    fn test_rule_set_serde() {
+        let rules = GrassHeightRuleSet::linear_default();
+        let json = serde_json::to_string(&rules).unwrap();
+        assert!(json.contains("\"kind\":\"linear\""));
+        assert_eq!(serde_json::from_str::<GrassHeightRuleSet>(&json).unwrap(), rules);
+    }
+
+    #[test]
+    fn test_plan_by_risk_zone() {
+        let table = DefensibleRuleTable::county_ordinance();
+        let high = table.plan(0.8, &RiskThresholds::default());
+        assert_eq!(high.risk_zone, RiskZone::High);
+        assert_eq!(high.rings[0], DefensibleRing::new(0.0, 1.0, 0.0));
+        assert_eq!(high.outer_radius_m(), 30.0);
+        assert_eq!(high.max_grass_height_cm(5.0), Some(10.0));
+
+        let low = table.plan(0.1, &RiskThresholds::default());
+        assert_eq!(low.max_grass_height_cm(0.5), Some(0.0));
+        assert_eq!(low.max_grass_height_cm(5.0), Some(10.0));
+        assert_eq!(low.max_grass_height_cm(40.0), None);
+    }
+
+    #[test]
+    fn test_plan_uses_thresholds() {
+        let table = DefensibleRuleTable::county_ordinance();
+        let strict = RiskThresholds { low: 0.2, medium: 0.4 };
+        assert_eq!(table.plan(0.5, &RiskThresholds::default()).risk_zone, RiskZone::Medium);
+        assert_eq!(table.plan(0.5, &strict).risk_zone, RiskZone::High);
+    }
+
+    #[test]
+    fn test_jurisdiction_tables_differ() {
+        let county = DefensibleRuleTable::county_ordinance().plan(0.5, &RiskThresholds::default());
+        let hoa = DefensibleRuleTable::hoa().plan(0.5, &RiskThresholds::default());
+        assert_eq!(hoa.jurisdiction, "hoa");
+        assert!(hoa.max_grass_height_cm(20.0) < county.max_grass_height_cm(20.0));
+    }
+
+    #[test]
+    fn test_plan_serde_and_rule_set() {
+        let plan = DefensibleRuleTable::default().plan(0.5, &RiskThresholds::default());
+        let json = serde_json::to_string(&plan).unwrap();
+        assert!(json.contains("\"risk_zone\":\"Medium\""));
+        assert_eq!(serde_json::from_str::<DefensibleZonePlan>(&json).unwrap(), plan);
+
+        let rules = GrassHeightRuleSet::from(&plan);
+        assert_eq!(rules.limit_at(1.0), Some((0, 10.0)));
+        assert_eq!(rules.limit_at(15.0), Some((1, 20.0)));
+    }
+}
@@ -0,0 +1,261 @@
+//! Swarm mission energy budgets (docs/MATH.md)
+//! Formulas work in joules; conversions to Wh (drones, PowerBudget) and
+//! mJ (nanobots) are explicit at the edges.
+
+use crate::models::{Drone, NanoBot, NanoswarmDose, NanoswarmLimits, PowerBudget};
+use crate::utils::constants::energy::*;
+use crate::utils::errors::Result;
+use serde::{Deserialize, Serialize};
+
+/// Joules per watt-hour
+pub const J_PER_WH: f64 = 3600.0;
+
+/// Millijoules per joule
+pub const MJ_PER_J: f64 = 1000.0;
+
+pub fn joules_to_wh(joules: f64) -> f64 {
+    joules / J_PER_WH
+}
+
+pub fn wh_to_joules(wh: f64) -> f64 {
+    wh * J_PER_WH
+}
+
+pub fn joules_to_mj(joules: f64) -> f64 {
+    joules * MJ_PER_J
+}
+
+pub fn mj_to_joules(mj: f64) -> f64 {
+    mj / MJ_PER_J
+}
+
+pub fn wh_to_mj(wh: f64) -> f64 {
+    joules_to_mj(wh_to_joules(wh))
+}
+
+pub fn mj_to_wh(mj: f64) -> f64 {
+    joules_to_wh(mj_to_joules(mj))
+}
+
+/// Drone mission coefficients: E = a·d + b·drops + c·t_min (J)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DroneEnergyModel {
+    pub flight_j_per_m: f64,
+    pub payload_j: f64,
+    pub airborne_j_per_min: f64,
+}
+
+impl Default for DroneEnergyModel {
+    fn default() -> Self {
+        Self {
+            flight_j_per_m: DRONE_FLIGHT_J_PER_M,
+            payload_j: DRONE_PAYLOAD_J,
+            airborne_j_per_min: DRONE_AIRBORNE_J_PER_MIN,
+        }
+    }
+}
+
+impl DroneEnergyModel {
+    /// E_total (J) for a mission
+    pub fn mission_energy_j(&self, distance_m: f64, payload_drops: u32, airborne_minutes: f64) -> f64 {
+        self.flight_j_per_m * distance_m
+            + self.payload_j * payload_drops as f64
+            + self.airborne_j_per_min * airborne_minutes
+    }
+
+    /// Mission cost for a drone flying at cruise speed
+    pub fn cost(&self, drone: &Drone, distance_m: f64, payload_drops: u32) -> DroneMissionCost {
+        let airborne_minutes = distance_m / drone.drone_type.cruise_speed_mps() / 60.0;
+        DroneMissionCost {
+            distance_m,
+            payload_drops,
+            airborne_minutes,
+            energy_j: self.mission_energy_j(distance_m, payload_drops, airborne_minutes),
+        }
+    }
+}
+
+/// Costed drone mission
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DroneMissionCost {
+    pub distance_m: f64,
+    pub payload_drops: u32,
+    pub airborne_minutes: f64,
+    pub energy_j: f64,
+}
+
+impl DroneMissionCost {
+    pub fn energy_wh(&self) -> f64 {
+        joules_to_wh(self.energy_j)
+    }
+}
+
+/// Nanobot mission coefficients: E = a·d + b·injections (J), recharging every interval
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NanobotEnergyModel {
+    pub travel_j_per_m: f64,
+    pub injection_j: f64,
+    pub recharge_interval_h: f64,
+}
+
+impl Default for NanobotEnergyModel {
+    fn default() -> Self {
+        Self {
+            travel_j_per_m: NANOBOT_TRAVEL_J_PER_M,
+            injection_j: NANOBOT_INJECTION_J,
+            recharge_interval_h: NANOBOT_RECHARGE_INTERVAL_H,
+        }
+    }
+}
+
+impl NanobotEnergyModel {
+    pub fn mission_energy_j(&self, distance_m: f64, injections: u32) -> f64 {
+        self.travel_j_per_m * distance_m + self.injection_j * injections as f64
+    }
+
+    /// Recharge pauses falling inside a mission of this length
+    pub fn recharges_needed(&self, duration_h: f64) -> u32 {
+        if duration_h <= 0.0 {
+            return 0;
+        }
+        ((duration_h / self.recharge_interval_h).ceil() as u32).saturating_sub(1)
+    }
+
+    pub fn cost(&self, distance_m: f64, injections: u32, duration_h: f64) -> NanobotMissionCost {
+        NanobotMissionCost {
+            distance_m,
+            injections,
+            energy_j: self.mission_energy_j(distance_m, injections),
+            recharges: self.recharges_needed(duration_h),
+        }
+    }
+
+    /// Per-bot cost of a swarm release, once the dose clears the jurisdiction's limits
+    pub fn plan_swarm(
+        &self,
+        dose: &NanoswarmDose,
+        limits: &NanoswarmLimits,
+        distance_m: f64,
+        injections: u32,
+    ) -> Result<NanobotMissionCost> {
+        limits.check(dose)?;
+        Ok(self.cost(distance_m, injections, dose.mission_hours))
+    }
+}
+
+/// Costed nanobot mission
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NanobotMissionCost {
+    pub distance_m: f64,
+    pub injections: u32,
+    pub energy_j: f64,
+    pub recharges: u32,
+}
+
+impl NanobotMissionCost {
+    pub fn energy_mj(&self) -> f64 {
+        joules_to_mj(self.energy_j)
+    }
+}
+
+impl Drone {
+    /// Battery energy above the flight SoC floor (Wh)
+    pub fn usable_energy_wh(&self) -> f64 {
+        ((self.battery_percent - DRONE_MIN_SOC_PERCENT) / 100.0 * self.battery_capacity_wh()).max(0.0)
+    }
+
+    /// Mission cost under the default drone model
+    pub fn mission_cost(&self, distance_m: f64, payload_drops: u32) -> DroneMissionCost {
+        DroneEnergyModel::default().cost(self, distance_m, payload_drops)
+    }
+
+    /// Can the mission be flown without dropping below the SoC floor?
+    pub fn can_complete_mission(&self, distance_m: f64, payload_drops: u32) -> bool {
+        self.mission_cost(distance_m, payload_drops).energy_wh() <= self.usable_energy_wh()
+    }
+}
+
+impl NanoBot {
+    /// Mission energy under the default nanobot model (mJ)
+    pub fn mission_energy_mj(&self, distance_m: f64, injections: u32) -> f64 {
+        joules_to_mj(NanobotEnergyModel::default().mission_energy_j(distance_m, injections))
+    }
+
+    /// Can the mission run on stored energy alone?
+    pub fn can_complete_mission(&self, distance_m: f64, injections: u32) -> bool {
+        self.active && self.mission_energy_mj(distance_m, injections) <= self.energy_mj
+    }
+}
+
+impl PowerBudget {
+    /// Can a mission of this many joules fit the remaining budget?
+    pub fn can_afford_j(&self, joules: f64) -> bool {
+        joules_to_wh(joules) <= self.remaining_wh()
+    }
+
+    /// Charge a costed drone mission against the budget
+    pub fn draw_drone_mission(&mut self, cost: &DroneMissionCost) -> bool {
+        self.draw_energy(cost.energy_wh())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DroneType;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_unit_conversions() {
+        assert_eq!(wh_to_joules(1.0), 3600.0);
+        assert_eq!(joules_to_mj(0.05), 50.0);
+        assert_abs_diff_eq!(mj_to_wh(wh_to_mj(2.5)), 2.5, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_drone_formula() {
+        // 0.5·1000 + 1000 + 500·10 = 6500 J
+        let model = DroneEnergyModel::default();
+        assert_eq!(model.mission_energy_j(1000.0, 1, 10.0), 6500.0);
+
+        let drone = Drone::new(1, DroneType::Quadcopter);
+        let cost = drone.mission_cost(7200.0, 1); // 10 min at 12 m/s
+        assert_abs_diff_eq!(cost.airborne_minutes, 10.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(cost.energy_j, 3600.0 + 1000.0 + 5000.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_nanobot_formula_and_recharges() {
+        let model = NanobotEnergyModel::default();
+        assert_abs_diff_eq!(model.mission_energy_j(100.0, 2), 110.0, epsilon = 1e-12);
+        assert_eq!(model.recharges_needed(4.0), 0);
+        assert_eq!(model.recharges_needed(9.0), 2);
+
+        let bot = NanoBot::new(1);
+        assert!(!bot.can_complete_mission(100.0, 2)); // 110 J far exceeds 100 mJ storage
+        assert!(bot.can_complete_mission(0.5, 0));
+    }
+
+    #[test]
+    fn test_swarm_plan_respects_limits() {
+        let model = NanobotEnergyModel::default();
+        let limits = NanoswarmLimits::default();
+
+        let cost = model.plan_swarm(&NanoswarmDose::new(1000, 1.0, 9.0, 0.5), &limits, 100.0, 2).unwrap();
+        assert_eq!(cost.recharges, 2);
+
+        // 10 000 bots × 0.01 g = 100 g/ha, twice the default 50 g/ha
+        assert!(model.plan_swarm(&NanoswarmDose::new(10_000, 1.0, 9.0, 0.5), &limits, 100.0, 2).is_err());
+    }
+
+    #[test]
+    fn test_budget_integration() {
+        let drone = Drone::new(1, DroneType::Quadcopter);
+        let cost = drone.mission_cost(10_000.0, 2);
+        let mut budget = PowerBudget::new(1, "drone".to_string(), 500.0);
+        assert!(budget.can_afford_j(cost.energy_j));
+        assert!(budget.draw_drone_mission(&cost));
+        assert_abs_diff_eq!(budget.energy_consumed_wh, cost.energy_wh(), epsilon = 1e-12);
+        assert!(drone.can_complete_mission(10_000.0, 2));
+    }
+}
@@ -0,0 +1,313 @@
+//! Terrain- and wind-aware drone flight energy
+//! Cruise power scaled by air density and all-up mass, headwind-adjusted ground
+//! speed, potential energy for terrain climbs and hover time at delivery points.
+
+use crate::models::{DroneType, LatLon};
+use crate::utils::constants::sonoran::{PEAK_TEMP_C, PHOENIX_ELEVATION_M};
+use crate::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+
+/// ISA sea-level air density (kg/m³)
+pub const SEA_LEVEL_DENSITY_KG_M3: f64 = 1.225;
+
+const GRAVITY_MPS2: f64 = 9.80665;
+const DRY_AIR_J_PER_KG_K: f64 = 287.05;
+const SEA_LEVEL_PA: f64 = 101_325.0;
+
+/// Ground elevation lookup (DEM, constant plane, ...)
+pub trait ElevationSource {
+    /// Elevation in meters, None outside coverage
+    fn elevation_m(&self, location: &LatLon) -> Option<f64>;
+}
+
+/// Constant-elevation terrain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlatTerrain(pub f64);
+
+impl ElevationSource for FlatTerrain {
+    fn elevation_m(&self, _location: &LatLon) -> Option<f64> {
+        Some(self.0)
+    }
+}
+
+/// Air temperature and altitude
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Atmosphere {
+    pub temperature_c: f64,
+    pub elevation_m: f64,
+}
+
+impl Default for Atmosphere {
+    /// ISA sea level, 15 °C
+    fn default() -> Self {
+        Self {
+            temperature_c: 15.0,
+            elevation_m: 0.0,
+        }
+    }
+}
+
+impl Atmosphere {
+    pub fn new(temperature_c: f64, elevation_m: f64) -> Self {
+        Self { temperature_c, elevation_m }
+    }
+
+    /// Phoenix summer peak (45 °C at 345 m)
+    pub fn sonoran_peak() -> Self {
+        Self::new(PEAK_TEMP_C, PHOENIX_ELEVATION_M)
+    }
+
+    /// Air density from barometric pressure and temperature (kg/m³)
+    pub fn density_kg_m3(&self) -> f64 {
+        let pressure_pa = SEA_LEVEL_PA * (1.0 - 2.25577e-5 * self.elevation_m).powf(5.25588);
+        pressure_pa / (DRY_AIR_J_PER_KG_K * (self.temperature_c + 273.15))
+    }
+
+    /// Power multiplier vs. sea level: thinner air needs more induced power (√(ρ0/ρ))
+    pub fn power_factor(&self) -> f64 {
+        (SEA_LEVEL_DENSITY_KG_M3 / self.density_kg_m3()).sqrt()
+    }
+}
+
+/// Steady wind, meteorological convention
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Wind {
+    pub speed_mps: f64,
+    pub from_deg: f64, // Direction the wind blows from (0 = north)
+}
+
+impl Wind {
+    pub fn new(speed_mps: f64, from_deg: f64) -> Self {
+        Self { speed_mps, from_deg }
+    }
+
+    /// Headwind component along a course (negative = tailwind)
+    pub fn headwind_mps(&self, course_deg: f64) -> f64 {
+        self.speed_mps * (self.from_deg - course_deg).to_radians().cos()
+    }
+}
+
+/// Weather for a flight
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FlightConditions {
+    pub wind: Wind,
+    pub atmosphere: Atmosphere,
+}
+
+/// One flight leg (terrain-following)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FlightLeg {
+    pub from: LatLon,
+    pub to: LatLon,
+    pub payload_kg: f64,
+    pub hover_s: f64, // Hover at the destination (delivery, survey)
+}
+
+impl FlightLeg {
+    pub fn new(from: LatLon, to: LatLon) -> Self {
+        Self {
+            from,
+            to,
+            payload_kg: 0.0,
+            hover_s: 0.0,
+        }
+    }
+
+    pub fn with_payload(mut self, payload_kg: f64) -> Self {
+        self.payload_kg = payload_kg;
+        self
+    }
+
+    pub fn with_hover(mut self, hover_s: f64) -> Self {
+        self.hover_s = hover_s;
+        self
+    }
+}
+
+/// Leg energy breakdown (Wh)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LegEnergy {
+    pub distance_m: f64,
+    pub ground_speed_mps: f64,
+    pub duration_s: f64,     // Cruise + hover
+    pub cruise_wh: f64,
+    pub climb_wh: f64,       // Net of descent recovery
+    pub hover_wh: f64,
+    pub total_wh: f64,
+}
+
+/// Physical drone energy model
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FlightEnergyModel {
+    pub empty_mass_kg: f64,
+    pub battery_wh: f64,
+    pub airspeed_mps: f64,
+    pub cruise_power_w: f64,     // Sea level, no payload, still air
+    pub hover_power_w: f64,      // Sea level, no payload
+    pub climb_efficiency: f64,   // Electrical -> potential energy
+    pub descent_recovery: f64,   // Fraction of descent energy saved (0-1)
+    pub sample_spacing_m: f64,   // DEM sampling along legs
+}
+
+impl FlightEnergyModel {
+    /// Presets: cruise power = battery / rated flight time
+    pub fn for_drone_type(drone_type: DroneType) -> Self {
+        let cruise_power_w = drone_type.cruise_power_w();
+        let (empty_mass_kg, hover_power_w, descent_recovery) = match drone_type {
+            DroneType::Quadcopter => (6.0, cruise_power_w * 1.1, 0.1),
+            DroneType::FixedWing => (8.0, cruise_power_w, 0.4), // Loiter instead of hover
+            DroneType::HybridVTOL => (10.0, cruise_power_w * 2.5, 0.3),
+        };
+        Self {
+            empty_mass_kg,
+            battery_wh: drone_type.battery_capacity_wh(),
+            airspeed_mps: drone_type.cruise_speed_mps(),
+            cruise_power_w,
+            hover_power_w,
+            climb_efficiency: 0.7,
+            descent_recovery,
+            sample_spacing_m: 30.0,
+        }
+    }
+
+    /// Net elevation gain and loss along a leg (m)
+    fn climb_profile(&self, leg: &FlightLeg, terrain: &dyn ElevationSource) -> (f64, f64) {
+        let distance_m = leg.from.distance_to(&leg.to);
+        let steps = ((distance_m / self.sample_spacing_m).ceil() as usize).max(1);
+        let mut previous: Option<f64> = None;
+        let (mut gain, mut loss) = (0.0, 0.0);
+
+        for i in 0..=steps {
+            let t = i as f64 / steps as f64;
+            let point = LatLon {
+                latitude: leg.from.latitude + t * (leg.to.latitude - leg.from.latitude),
+                longitude: leg.from.longitude + t * (leg.to.longitude - leg.from.longitude),
+            };
+            let Some(z) = terrain.elevation_m(&point) else { continue };
+            if let Some(p) = previous {
+                if z > p {
+                    gain += z - p;
+                } else {
+                    loss += p - z;
+                }
+            }
+            previous = Some(z);
+        }
+        (gain, loss)
+    }
+
+    /// Energy for one leg under the given conditions and terrain
+    pub fn leg_energy(&self, leg: &FlightLeg, conditions: &FlightConditions, terrain: &dyn ElevationSource) -> Result<LegEnergy> {
+        if !self.sample_spacing_m.is_finite() || self.sample_spacing_m <= 0.0 {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("DEM sample spacing must be positive and finite (got {} m)", self.sample_spacing_m),
+            });
+        }
+        let distance_m = leg.from.distance_to(&leg.to);
+        let course = leg.from.bearing_to(&leg.to);
+        let ground_speed_mps = if distance_m > 0.0 {
+            self.airspeed_mps - conditions.wind.headwind_mps(course)
+        } else {
+            self.airspeed_mps
+        };
+        if ground_speed_mps <= 0.5 {
+            return Err(CybersomethingError::MissionPlanningFailed {
+                reason: format!("headwind {:.1} m/s exceeds airspeed {:.1} m/s", conditions.wind.headwind_mps(course), self.airspeed_mps),
+            });
+        }
+
+        let all_up_kg = self.empty_mass_kg + leg.payload_kg;
+        let mass_ratio = all_up_kg / self.empty_mass_kg;
+        let density = conditions.atmosphere.power_factor();
+
+        let cruise_s = distance_m / ground_speed_mps;
+        let cruise_wh = self.cruise_power_w * density * mass_ratio * cruise_s / 3600.0;
+        let hover_wh = self.hover_power_w * density * mass_ratio.powf(1.5) * leg.hover_s / 3600.0;
+
+        let (gain, loss) = self.climb_profile(leg, terrain);
+        let potential_j = all_up_kg * GRAVITY_MPS2;
+        let climb_wh = ((potential_j * gain / self.climb_efficiency) - (potential_j * loss * self.descent_recovery)) / 3600.0;
+        let climb_wh = climb_wh.max(-cruise_wh * 0.5); // Descents never fly for free
+
+        Ok(LegEnergy {
+            distance_m,
+            ground_speed_mps,
+            duration_s: cruise_s + leg.hover_s,
+            cruise_wh,
+            climb_wh,
+            hover_wh,
+            total_wh: cruise_wh + climb_wh + hover_wh,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg() -> FlightLeg {
+        // ~11 km due north
+        FlightLeg::new(LatLon::new(33.40, -112.00), LatLon::new(33.50, -112.00))
+    }
+
+    /// Plane rising 100 m per 0.1° of latitude
+    struct Ramp;
+
+    impl ElevationSource for Ramp {
+        fn elevation_m(&self, location: &LatLon) -> Option<f64> {
+            Some((location.latitude - 33.40) * 1000.0)
+        }
+    }
+
+    #[test]
+    fn test_hot_air_costs_more() {
+        let density = Atmosphere::sonoran_peak().density_kg_m3();
+        assert!(density < 1.12 && density > 1.05);
+        assert!((Atmosphere::default().density_kg_m3() - SEA_LEVEL_DENSITY_KG_M3).abs() < 0.01);
+
+        let model = FlightEnergyModel::for_drone_type(DroneType::Quadcopter);
+        let cool = model.leg_energy(&leg(), &FlightConditions::default(), &FlatTerrain(0.0)).unwrap();
+        let hot = FlightConditions { atmosphere: Atmosphere::sonoran_peak(), ..Default::default() };
+        assert!(model.leg_energy(&leg(), &hot, &FlatTerrain(0.0)).unwrap().total_wh > cool.total_wh);
+    }
+
+    #[test]
+    fn test_headwind_and_tailwind() {
+        let model = FlightEnergyModel::for_drone_type(DroneType::Quadcopter);
+        let still = model.leg_energy(&leg(), &FlightConditions::default(), &FlatTerrain(0.0)).unwrap();
+        let head = FlightConditions { wind: Wind::new(5.0, 0.0), ..Default::default() };
+        let tail = FlightConditions { wind: Wind::new(5.0, 180.0), ..Default::default() };
+
+        let head = model.leg_energy(&leg(), &head, &FlatTerrain(0.0)).unwrap();
+        let tail = model.leg_energy(&leg(), &tail, &FlatTerrain(0.0)).unwrap();
+        assert!((head.ground_speed_mps - 7.0).abs() < 1e-6);
+        assert!(head.total_wh > still.total_wh && still.total_wh > tail.total_wh);
+
+        let gale = FlightConditions { wind: Wind::new(20.0, 0.0), ..Default::default() };
+        assert!(model.leg_energy(&leg(), &gale, &FlatTerrain(0.0)).is_err());
+    }
+
+    #[test]
+    fn test_climb_payload_and_hover() {
+        let model = FlightEnergyModel::for_drone_type(DroneType::Quadcopter);
+        let flat = model.leg_energy(&leg(), &FlightConditions::default(), &FlatTerrain(0.0)).unwrap();
+        let up = model.leg_energy(&leg(), &FlightConditions::default(), &Ramp).unwrap();
+        // 6 kg × g × 100 m / 0.7 ≈ 0.23 Wh
+        assert!((up.climb_wh - 6.0 * GRAVITY_MPS2 * 100.0 / 0.7 / 3600.0).abs() < 1e-3);
+        assert!(up.total_wh > flat.total_wh);
+
+        let loaded = model
+            .leg_energy(&leg().with_payload(2.0).with_hover(60.0), &FlightConditions::default(), &FlatTerrain(0.0))
+            .unwrap();
+        assert!(loaded.cruise_wh > flat.cruise_wh);
+        assert!(loaded.hover_wh > 0.0);
+    }
+
+    #[test]
+    fn test_rejects_bad_sample_spacing() {
+        for spacing in [0.0, -30.0, f64::NAN, f64::INFINITY] {
+            let model = FlightEnergyModel { sample_spacing_m: spacing, ..FlightEnergyModel::for_drone_type(DroneType::Quadcopter) };
+            assert!(model.leg_energy(&leg(), &FlightConditions::default(), &Ramp).is_err());
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Solar and RF energy harvesting by time of day and weather
+//! Solar position (NOAA approximation) → Haurwitz clear-sky irradiance →
+//! Kasten–Czeplak cloud attenuation → panel output; RF via Friis free-space falloff.
+
+use crate::models::{LatLon, PowerBudget};
+use crate::utils::constants::energy::{RF_EFFICIENCY_PERCENT, SOLAR_EFFICIENCY_PERCENT};
+use crate::utils::constants::sonoran::UTC_OFFSET_HOURS;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+const SPEED_OF_LIGHT_MPS: f64 = 299_792_458.0;
+
+/// Irradiance at which panel power is rated (W/m², STC)
+pub const STANDARD_IRRADIANCE_W_M2: f64 = 1000.0;
+
+/// Sun angles in degrees
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SolarPosition {
+    pub elevation_deg: f64,
+    pub azimuth_deg: f64, // Clockwise from north
+}
+
+/// Solar position for a place, day of year (1-366) and UTC hour; hours outside
+/// 0-24 roll into the neighbouring UTC day
+pub fn solar_position(latitude: f64, longitude: f64, day_of_year: u32, utc_hour: f64) -> SolarPosition {
+    let n = (day_of_year as f64 - 1.0 + utc_hour.div_euclid(24.0)).rem_euclid(365.0) + 1.0;
+    let utc_hour = utc_hour.rem_euclid(24.0);
+    let declination = (23.45 * (2.0 * PI * (284.0 + n) / 365.0).sin()).to_radians();
+
+    let b = 2.0 * PI * (n - 81.0) / 365.0;
+    let equation_of_time_min = 9.87 * (2.0 * b).sin() - 7.53 * b.cos() - 1.5 * b.sin();
+    let solar_minutes = utc_hour * 60.0 + 4.0 * longitude + equation_of_time_min;
+    let hour_angle = ((15.0 * (solar_minutes / 60.0 - 12.0) + 180.0).rem_euclid(360.0) - 180.0).to_radians();
+
+    let phi = latitude.to_radians();
+    let sin_elevation = phi.sin() * declination.sin() + phi.cos() * declination.cos() * hour_angle.cos();
+    let elevation = sin_elevation.clamp(-1.0, 1.0).asin();
+
+    let cos_azimuth = ((declination.sin() - elevation.sin() * phi.sin()) / (elevation.cos() * phi.cos())).clamp(-1.0, 1.0);
+    let mut azimuth_deg = cos_azimuth.acos().to_degrees();
+    if hour_angle > 0.0 {
+        azimuth_deg = 360.0 - azimuth_deg; // Afternoon: sun in the west
+    }
+
+    SolarPosition {
+        elevation_deg: elevation.to_degrees(),
+        azimuth_deg,
+    }
+}
+
+/// Clear-sky global horizontal irradiance (W/m², Haurwitz)
+pub fn clear_sky_ghi_w_m2(elevation_deg: f64) -> f64 {
+    let cos_zenith = elevation_deg.to_radians().sin();
+    if cos_zenith <= 0.0 {
+        return 0.0;
+    }
+    1098.0 * cos_zenith * (-0.059 / cos_zenith).exp()
+}
+
+/// Fraction of clear-sky irradiance reaching the ground for cloud cover 0-1 (Kasten–Czeplak)
+pub fn cloud_transmittance(cloud_cover: f64) -> f64 {
+    1.0 - 0.75 * cloud_cover.clamp(0.0, 1.0).powf(3.4)
+}
+
+/// Horizontal PV panel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SolarPanel {
+    pub area_m2: f64,
+    pub efficiency: f64, // 0-1
+}
+
+impl SolarPanel {
+    /// Panel at the platform's rated efficiency
+    pub fn new(area_m2: f64) -> Self {
+        Self {
+            area_m2,
+            efficiency: SOLAR_EFFICIENCY_PERCENT / 100.0,
+        }
+    }
+
+    /// Panel sized to deliver `power_w` at standard irradiance
+    pub fn rated(power_w: f64) -> Self {
+        let efficiency = SOLAR_EFFICIENCY_PERCENT / 100.0;
+        Self {
+            area_m2: power_w / (STANDARD_IRRADIANCE_W_M2 * efficiency),
+            efficiency,
+        }
+    }
+
+    pub fn output_w(&self, irradiance_w_m2: f64) -> f64 {
+        irradiance_w_m2 * self.area_m2 * self.efficiency
+    }
+}
+
+/// RF power beacon
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RfTransmitter {
+    pub location: LatLon,
+    pub power_w: f64,
+    pub frequency_hz: f64,
+    pub gain_dbi: f64,
+}
+
+/// Rectenna on the harvesting device
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RfHarvester {
+    pub gain_dbi: f64,
+    pub efficiency: f64, // RF -> DC, 0-1
+}
+
+impl Default for RfHarvester {
+    fn default() -> Self {
+        Self {
+            gain_dbi: 2.0,
+            efficiency: RF_EFFICIENCY_PERCENT / 100.0,
+        }
+    }
+}
+
+impl RfHarvester {
+    /// Harvested DC power from one transmitter (W): Friis P_r = P_t·G_t·G_r·(λ/4πd)²
+    pub fn harvested_w(&self, tx: &RfTransmitter, at: &LatLon) -> f64 {
+        let wavelength = SPEED_OF_LIGHT_MPS / tx.frequency_hz;
+        let distance = at.distance_to(&tx.location).max(wavelength); // Far-field floor
+        let gains = 10f64.powf((tx.gain_dbi + self.gain_dbi) / 10.0);
+        let received = tx.power_w * gains * (wavelength / (4.0 * PI * distance)).powi(2);
+        received * self.efficiency
+    }
+}
+
+/// Harvested power at an instant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HarvestPower {
+    pub irradiance_w_m2: f64,
+    pub solar_w: f64,
+    pub rf_w: f64,
+}
+
+/// Energy harvested over one local clock hour
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HourlyHarvest {
+    pub hour: u32, // Local time, 0-23
+    pub mean_irradiance_w_m2: f64,
+    pub solar_wh: f64,
+    pub rf_wh: f64,
+}
+
+impl HourlyHarvest {
+    pub fn total_wh(&self) -> f64 {
+        self.solar_wh + self.rf_wh
+    }
+}
+
+/// Harvesting device or charging pad at a fixed location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarvestingModel {
+    pub location: LatLon,
+    pub utc_offset_hours: f64,
+    pub panel: Option<SolarPanel>,
+    pub harvester: Option<RfHarvester>,
+    pub transmitters: Vec<RfTransmitter>,
+}
+
+impl HarvestingModel {
+    /// Model in Arizona time (UTC-7, no daylight saving)
+    pub fn new(location: LatLon) -> Self {
+        Self {
+            location,
+            utc_offset_hours: UTC_OFFSET_HOURS,
+            panel: None,
+            harvester: None,
+            transmitters: Vec::new(),
+        }
+    }
+
+    pub fn with_panel(mut self, panel: SolarPanel) -> Self {
+        self.panel = Some(panel);
+        self
+    }
+
+    pub fn with_rf(mut self, harvester: RfHarvester, transmitters: Vec<RfTransmitter>) -> Self {
+        self.harvester = Some(harvester);
+        self.transmitters = transmitters;
+        self
+    }
+
+    /// Harvested power at a local time
+    pub fn power_at(&self, day_of_year: u32, local_hour: f64, cloud_cover: f64) -> HarvestPower {
+        let utc_hour = local_hour - self.utc_offset_hours;
+        let sun = solar_position(self.location.latitude, self.location.longitude, day_of_year, utc_hour);
+        let irradiance = clear_sky_ghi_w_m2(sun.elevation_deg) * cloud_transmittance(cloud_cover);
+
+        let solar_w = self.panel.map_or(0.0, |p| p.output_w(irradiance));
+        let rf_w = self.harvester.map_or(0.0, |h| {
+            self.transmitters.iter().map(|tx| h.harvested_w(tx, &self.location)).sum()
+        });
+
+        HarvestPower {
+            irradiance_w_m2: irradiance,
+            solar_w,
+            rf_w,
+        }
+    }
+
+    /// 24 local hours of harvest; `cloud_cover` is per hour (missing hours use the last value, or clear sky)
+    pub fn hourly(&self, day_of_year: u32, cloud_cover: &[f64]) -> Vec<HourlyHarvest> {
+        const SUBSTEPS: u32 = 6;
+
+        (0..24)
+            .map(|hour| {
+                let clouds = cloud_cover
+                    .get(hour as usize)
+                    .or(cloud_cover.last())
+                    .copied()
+                    .unwrap_or(0.0);
+                let samples: Vec<HarvestPower> = (0..SUBSTEPS)
+                    .map(|i| self.power_at(day_of_year, hour as f64 + (i as f64 + 0.5) / SUBSTEPS as f64, clouds))
+                    .collect();
+                let mean = |f: fn(&HarvestPower) -> f64| samples.iter().map(f).sum::<f64>() / SUBSTEPS as f64;
+
+                HourlyHarvest {
+                    hour,
+                    mean_irradiance_w_m2: mean(|p| p.irradiance_w_m2),
+                    solar_wh: mean(|p| p.solar_w),
+                    rf_wh: mean(|p| p.rf_w),
+                }
+            })
+            .collect()
+    }
+
+    /// Clear-sky harvest (Wh/day) averaged over the 15th of each month
+    pub fn mean_daily_clear_sky_wh(&self) -> f64 {
+        const MID_MONTH_DAYS: [u32; 12] = [15, 46, 74, 105, 135, 166, 196, 227, 258, 288, 319, 349];
+        MID_MONTH_DAYS
+            .iter()
+            .map(|&day| self.hourly(day, &[0.0]).iter().map(|h| h.total_wh()).sum::<f64>())
+            .sum::<f64>()
+            / MID_MONTH_DAYS.len() as f64
+    }
+
+    /// Contiguous local-hour windows [start, end) where harvest meets a minimum (Wh per hour)
+    pub fn recharge_windows(&self, day_of_year: u32, cloud_cover: &[f64], min_wh: f64) -> Vec<(u32, u32)> {
+        let mut windows = Vec::new();
+        let mut start = None;
+        for h in self.hourly(day_of_year, cloud_cover) {
+            match (h.total_wh() >= min_wh, start) {
+                (true, None) => start = Some(h.hour),
+                (false, Some(s)) => {
+                    windows.push((s, h.hour));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            windows.push((s, 24));
+        }
+        windows
+    }
+}
+
+impl PowerBudget {
+    /// Share of the daily budget recovered by a harvest profile (%, capped at 100)
+    pub fn harvest_recovery_percent(&self, harvest: &[HourlyHarvest]) -> f64 {
+        let harvested: f64 = harvest.iter().map(|h| h.total_wh()).sum();
+        (harvested / self.total_energy_budget_wh * 100.0).min(100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EnergySource;
+    use crate::utils::constants::sonoran::{PHOENIX_LAT, PHOENIX_LON};
+
+    fn phoenix() -> LatLon {
+        LatLon::new(PHOENIX_LAT, PHOENIX_LON)
+    }
+
+    #[test]
+    fn test_solar_noon_elevation() {
+        // June solstice, local solar noon in Phoenix ≈ 12:30 MST = 19:30 UTC
+        let sun = solar_position(PHOENIX_LAT, PHOENIX_LON, 172, 19.5);
+        assert!((sun.elevation_deg - (90.0 - PHOENIX_LAT + 23.45)).abs() < 1.5);
+        assert!(solar_position(PHOENIX_LAT, PHOENIX_LON, 172, 7.0).elevation_deg < 0.0); // Midnight MST
+        assert!(solar_position(PHOENIX_LAT, PHOENIX_LON, 172, 15.0).azimuth_deg < 180.0); // Morning: east
+    }
+
+    #[test]
+    fn test_solar_position_across_midnight() {
+        // Local solar midnight in Phoenix ≈ 07:30 UTC: just before it the sun is in the west
+        assert!(solar_position(PHOENIX_LAT, PHOENIX_LON, 172, 6.5).azimuth_deg > 180.0);
+        assert!(solar_position(PHOENIX_LAT, PHOENIX_LON, 172, 8.5).azimuth_deg < 180.0);
+
+        // 20:00 MST is 03:00 UTC the next day
+        assert_eq!(
+            solar_position(PHOENIX_LAT, PHOENIX_LON, 172, 27.0),
+            solar_position(PHOENIX_LAT, PHOENIX_LON, 173, 3.0)
+        );
+        assert_eq!(
+            solar_position(PHOENIX_LAT, PHOENIX_LON, 1, -2.0),
+            solar_position(PHOENIX_LAT, PHOENIX_LON, 365, 22.0)
+        );
+    }
+
+    #[test]
+    fn test_clear_sky_and_clouds() {
+        let overhead = clear_sky_ghi_w_m2(90.0);
+        assert!(overhead > 1000.0 && overhead < 1098.0);
+        assert_eq!(clear_sky_ghi_w_m2(-5.0), 0.0);
+        assert_eq!(cloud_transmittance(0.0), 1.0);
+        assert!((cloud_transmittance(1.0) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_hourly_solar_profile() {
+        let model = HarvestingModel::new(phoenix()).with_panel(SolarPanel::new(1.0));
+        let clear = model.hourly(172, &[0.0]);
+        assert_eq!(clear.len(), 24);
+        assert_eq!(clear[2].solar_wh, 0.0);
+        let noon = clear[12].solar_wh;
+        assert!(noon > 150.0 && noon < 200.0); // ~1000 W/m² × 18 %
+        let daily: f64 = clear.iter().map(|h| h.total_wh()).sum();
+        assert!(daily > 1000.0);
+
+        let budget = PowerBudget::new(1, "drone".to_string(), 5000.0);
+        assert!((budget.harvest_recovery_percent(&clear) - daily / 50.0).abs() < 1e-9);
+
+        let overcast = model.hourly(172, &[1.0]);
+        assert!(overcast[12].solar_wh < noon * 0.3);
+
+        let windows = model.recharge_windows(172, &[0.0], 100.0);
+        assert_eq!(windows.len(), 1);
+        assert!(windows[0].0 >= 7 && windows[0].1 <= 18);
+    }
+
+    #[test]
+    fn test_daily_recovery_uses_solar_model() {
+        // A 100 W panel sees ~6 peak sun hours a day, not 24
+        let solar_wh = EnergySource::Solar.expected_daily_wh();
+        assert!(solar_wh > 400.0 && solar_wh < 700.0);
+
+        let mut budget = PowerBudget::new(1, "base".to_string(), 5000.0);
+        budget.energy_sources.push(EnergySource::Solar);
+        assert!((budget.daily_recovery_percent() - solar_wh / 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rf_friis_falloff() {
+        let tx = RfTransmitter {
+            location: phoenix(),
+            power_w: 3.0,
+            frequency_hz: 915e6,
+            gain_dbi: 6.0,
+        };
+        let harvester = RfHarvester::default();
+        let near = harvester.harvested_w(&tx, &LatLon::new(PHOENIX_LAT + 0.00009, PHOENIX_LON)); // ~10 m
+        let far = harvester.harvested_w(&tx, &LatLon::new(PHOENIX_LAT + 0.00018, PHOENIX_LON)); // ~20 m
+        assert!((near / far - 4.0).abs() < 0.05); // Inverse square
+        assert!(near < 1e-3);
+    }
+}
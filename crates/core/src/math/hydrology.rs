@@ -1,7 +1,7 @@
 //! Water delivery routing for ecological recovery
 //! "Water-bottle method": Calculate water bottle deployment to deforested zones
 
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
 use std::cmp::Ordering;
 
 #[derive(Debug, Clone, Copy)]
@@ -14,6 +14,13 @@ pub struct HydroZone {
     pub recovery_stage: f64,       // 0.0 = bare, 1.0 = recovered
 }
 
+impl HydroZone {
+    /// Liters needed to cover the deficit over an area (1 mm over 1 m² = 1 L)
+    pub fn deficit_liters(&self, area_m2: f64) -> f64 {
+        self.deficit_mm * area_m2
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct WaterBottle {
     pub id: u32,
@@ -40,6 +47,43 @@ impl PartialOrd for RouteNode {
     }
 }
 
+/// One tank trip from a bottle's source to a zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeliveryTrip {
+    pub bottle_id: u32,
+    pub zone_id: u32,
+    pub liters: f64,
+    pub distance_m: f64,  // One-way
+}
+
+/// Result of capacity-aware delivery assignment
+#[derive(Debug, Clone, Default)]
+pub struct DeliveryAssignment {
+    pub trips: Vec<DeliveryTrip>,
+    pub unmet_liters: HashMap<u32, f64>, // zone_id -> remaining need
+}
+
+impl DeliveryAssignment {
+    /// Liters assigned to a zone
+    pub fn delivered_liters(&self, zone_id: u32) -> f64 {
+        self.trips
+            .iter()
+            .filter(|t| t.zone_id == zone_id)
+            .map(|t| t.liters)
+            .sum()
+    }
+
+    /// Trips assigned to a bottle
+    pub fn trip_count(&self, bottle_id: u32) -> u32 {
+        self.trips.iter().filter(|t| t.bottle_id == bottle_id).count() as u32
+    }
+
+    /// Round-trip distance over all trips (m)
+    pub fn total_distance_m(&self) -> f64 {
+        self.trips.iter().map(|t| 2.0 * t.distance_m).sum()
+    }
+}
+
 pub struct HydroRouter;
 
 impl HydroRouter {
@@ -56,45 +100,222 @@ impl HydroRouter {
         R * c
     }
 
-    /// Greedy water bottle routing: visit highest priority zones first
-    pub fn route_bottles(
+    /// Priority = water need + ecosystem urgency - distance penalty (docs/MATH.md Pz)
+    pub fn zone_priority(zone: &HydroZone, deficit_mm: f64, distance_m: f64) -> f64 {
+        (deficit_mm / 100.0)
+            + (zone.native_species_count as f64 / 100.0)
+            - (zone.recovery_stage * 2.0)
+            - (distance_m / 10000.0)
+    }
+
+    /// Capacity-aware assignment of tank trips to zone demand (min-cost flow).
+    ///
+    /// Liters flow source → bottle (trips left · C) → zone (remaining need) → sink.
+    /// Each bottle→zone liter costs -Pz / C, so a full trip is worth its Pz bid.
+    /// Successive shortest paths deliver as many liters as possible and, among
+    /// those plans, the one with the highest total Pz. Flows are split into
+    /// trips of at most C; if partial loads push a bottle past its trip limit its
+    /// smallest loads go back to the zones and are re-solved over the trips the
+    /// other bottles have left. Leftover need is reported per zone.
+    pub fn assign_deliveries(
         bottles: &[WaterBottle],
         zones: &[HydroZone],
-    ) -> Vec<(u32, u32, f64)> {
-        // (bottle_id, zone_id, distance_m)
-        let mut routes = Vec::new();
-
-        for bottle in bottles {
-            // Find zone with highest recovery need
-            let mut best_zone: Option<(u32, f64)> = None;
-            let mut best_priority = f64::NEG_INFINITY;
-
-            for zone in zones {
-                let dist = Self::distance(
-                    bottle.source_lat,
-                    bottle.source_lon,
-                    zone.center_lat,
-                    zone.center_lon,
-                );
-
-                // Priority = water need + ecosystem urgency - distance penalty
-                let priority = (zone.deficit_mm as f64 / 100.0)
-                    + (zone.native_species_count as f64 / 100.0)
-                    - (zone.recovery_stage * 2.0)
-                    - (dist / 10000.0);
-
-                if priority > best_priority {
-                    best_priority = priority;
-                    best_zone = Some((zone.zone_id, dist));
+        demand_liters: &HashMap<u32, f64>,
+        max_trips_per_bottle: u32,
+    ) -> DeliveryAssignment {
+        let demand: Vec<f64> = zones
+            .iter()
+            .map(|z| demand_liters.get(&z.zone_id).copied().unwrap_or(0.0).max(0.0))
+            .collect();
+
+        let mut need = demand.clone();
+        let mut trips_left = vec![max_trips_per_bottle as usize; bottles.len()];
+        let mut assignment = DeliveryAssignment::default();
+        loop {
+            // Every non-empty round uses at least one trip, so this terminates
+            let round = Self::assign_round(bottles, zones, &need, &trips_left);
+            if round.is_empty() {
+                break;
+            }
+            for (bi, zi, trip) in round {
+                need[zi] -= trip.liters;
+                trips_left[bi] -= 1;
+                assignment.trips.push(trip);
+            }
+        }
+
+        assignment.unmet_liters = zones
+            .iter()
+            .zip(&demand)
+            .filter(|(_, need)| **need > 0.0)
+            .map(|(zone, need)| {
+                let unmet = need - assignment.delivered_liters(zone.zone_id);
+                (zone.zone_id, if unmet > FLOW_EPS { unmet } else { 0.0 })
+            })
+            .collect();
+        assignment
+    }
+
+    /// One min-cost flow pass; returns (bottle idx, zone idx, trip) within each bottle's trips left
+    fn assign_round(
+        bottles: &[WaterBottle],
+        zones: &[HydroZone],
+        need: &[f64],
+        trips_left: &[usize],
+    ) -> Vec<(usize, usize, DeliveryTrip)> {
+        // Nodes: 0 = source, 1..=B bottles, B+1..=B+Z zones, B+Z+1 = sink
+        let sink = bottles.len() + zones.len() + 1;
+        let mut graph = FlowGraph::new(sink + 1);
+        let mut links = Vec::new(); // (bottle idx, zone idx, edge, one-way distance)
+
+        for (bi, bottle) in bottles.iter().enumerate() {
+            if bottle.capacity_liters <= 0.0 || trips_left[bi] == 0 {
+                continue;
+            }
+            graph.add_edge(0, 1 + bi, bottle.capacity_liters * trips_left[bi] as f64, 0.0);
+            for (zi, zone) in zones.iter().enumerate() {
+                if need[zi] <= FLOW_EPS {
+                    continue;
                 }
+                let dist = Self::distance(bottle.source_lat, bottle.source_lon, zone.center_lat, zone.center_lon);
+                let cost = -Self::zone_priority(zone, zone.deficit_mm, dist) / bottle.capacity_liters;
+                let edge = graph.add_edge(1 + bi, 1 + bottles.len() + zi, need[zi], cost);
+                links.push((bi, zi, edge, dist));
             }
+        }
+        for (zi, need) in need.iter().enumerate() {
+            if *need > FLOW_EPS {
+                graph.add_edge(1 + bottles.len() + zi, sink, *need, 0.0);
+            }
+        }
+        graph.min_cost_max_flow(0, sink);
 
-            if let Some((zone_id, dist)) = best_zone {
-                routes.push((bottle.id, zone_id, dist));
+        let mut trips = Vec::new();
+        for (bi, bottle) in bottles.iter().enumerate() {
+            let capacity = bottle.capacity_liters;
+            let mut loads: Vec<(usize, usize, DeliveryTrip)> = Vec::new();
+            for &(_, zi, edge, dist) in links.iter().filter(|(b, ..)| *b == bi) {
+                let mut liters = graph.flow(edge);
+                while liters > FLOW_EPS {
+                    let load = capacity.min(liters);
+                    liters -= load;
+                    let trip = DeliveryTrip {
+                        bottle_id: bottle.id,
+                        zone_id: zones[zi].zone_id,
+                        liters: load,
+                        distance_m: dist,
+                    };
+                    loads.push((bi, zi, trip));
+                }
             }
+            loads.sort_by(|a, b| b.2.liters.total_cmp(&a.2.liters));
+            loads.truncate(trips_left[bi]);
+            trips.extend(loads);
         }
+        trips
+    }
+
+    /// Each bottle's single trip to its highest-Pz zone. Without per-zone demand
+    /// every zone can absorb any number of tanks; use `route_bottles_to_demand`
+    /// to fill zones only up to their need.
+    /// Returns (bottle_id, zone_id, distance_m) for each bottle that is sent out.
+    pub fn route_bottles(bottles: &[WaterBottle], zones: &[HydroZone]) -> Vec<(u32, u32, f64)> {
+        let unbounded: f64 = bottles.iter().map(|b| b.capacity_liters.max(0.0)).sum();
+        let demand = zones.iter().map(|z| (z.zone_id, unbounded)).collect();
+        Self::route_bottles_to_demand(bottles, zones, &demand)
+    }
+
+    /// One trip per bottle, zones filled up to their demand (see `assign_deliveries`).
+    /// Returns (bottle_id, zone_id, distance_m) for each bottle that is sent out.
+    pub fn route_bottles_to_demand(
+        bottles: &[WaterBottle],
+        zones: &[HydroZone],
+        demand_liters: &HashMap<u32, f64>,
+    ) -> Vec<(u32, u32, f64)> {
+        Self::assign_deliveries(bottles, zones, demand_liters, 1)
+            .trips
+            .iter()
+            .map(|t| (t.bottle_id, t.zone_id, t.distance_m))
+            .collect()
+    }
+}
+
+/// Flow below this many liters is treated as zero
+const FLOW_EPS: f64 = 1e-9;
+
+/// Residual edge; edges are stored in pairs so `e ^ 1` is the reverse edge
+#[derive(Debug, Clone, Copy)]
+struct FlowEdge {
+    from: usize,
+    to: usize,
+    cap: f64,
+    cost: f64,
+}
+
+/// Residual graph for successive-shortest-path min-cost max-flow
+struct FlowGraph {
+    nodes: usize,
+    edges: Vec<FlowEdge>,
+}
+
+impl FlowGraph {
+    fn new(nodes: usize) -> Self {
+        Self {
+            nodes,
+            edges: Vec::new(),
+        }
+    }
 
-        routes
+    /// Add an edge and its zero-capacity reverse; returns the forward edge index
+    fn add_edge(&mut self, from: usize, to: usize, cap: f64, cost: f64) -> usize {
+        self.edges.push(FlowEdge { from, to, cap, cost });
+        self.edges.push(FlowEdge { from: to, to: from, cap: 0.0, cost: -cost });
+        self.edges.len() - 2
+    }
+
+    /// Flow currently carried by a forward edge
+    fn flow(&self, edge: usize) -> f64 {
+        self.edges[edge ^ 1].cap
+    }
+
+    /// Augment along cheapest paths (Bellman-Ford, costs may be negative) until
+    /// the sink is unreachable
+    fn min_cost_max_flow(&mut self, source: usize, sink: usize) {
+        loop {
+            let mut dist = vec![f64::INFINITY; self.nodes];
+            let mut via: Vec<Option<usize>> = vec![None; self.nodes];
+            dist[source] = 0.0;
+
+            for _ in 0..self.nodes {
+                let mut changed = false;
+                for (e, edge) in self.edges.iter().enumerate() {
+                    if edge.cap > FLOW_EPS && dist[edge.from] + edge.cost < dist[edge.to] - 1e-12 {
+                        dist[edge.to] = dist[edge.from] + edge.cost;
+                        via[edge.to] = Some(e);
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+            if dist[sink].is_infinite() {
+                break;
+            }
+
+            let mut push = f64::INFINITY;
+            let mut node = sink;
+            while let Some(e) = via[node] {
+                push = push.min(self.edges[e].cap);
+                node = self.edges[e].from;
+            }
+            let mut node = sink;
+            while let Some(e) = via[node] {
+                self.edges[e].cap -= push;
+                self.edges[e ^ 1].cap += push;
+                node = self.edges[e].from;
+            }
+        }
     }
 }
 
@@ -140,4 +361,117 @@ mod tests {
         let routes = HydroRouter::route_bottles(&bottles, &zones);
         assert_eq!(routes.len(), 1);
     }
+
+    #[test]
+    fn test_bottles_spread_over_zones() {
+        let zones = vec![zone(101, 33.45, -112.07, 100.0), zone(102, 33.46, -112.06, 100.0)];
+        let bottles = vec![bottle(1, 1000.0), bottle(2, 1000.0)];
+        let demand = HashMap::from([(101, 1000.0), (102, 1000.0)]);
+
+        let routes = HydroRouter::route_bottles_to_demand(&bottles, &zones, &demand);
+        assert_eq!(routes.len(), 2);
+        assert_ne!(routes[0].1, routes[1].1);
+    }
+
+    fn zone(zone_id: u32, lat: f64, lon: f64, deficit_mm: f64) -> HydroZone {
+        HydroZone {
+            zone_id,
+            center_lat: lat,
+            center_lon: lon,
+            deficit_mm,
+            native_species_count: 20,
+            recovery_stage: 0.1,
+        }
+    }
+
+    fn bottle(id: u32, capacity_liters: f64) -> WaterBottle {
+        WaterBottle {
+            id,
+            capacity_liters,
+            source_lat: 33.4484,
+            source_lon: -112.0742,
+        }
+    }
+
+    fn bottle_at(id: u32, lat: f64, lon: f64) -> WaterBottle {
+        WaterBottle {
+            id,
+            capacity_liters: 1000.0,
+            source_lat: lat,
+            source_lon: lon,
+        }
+    }
+
+    #[test]
+    fn test_assignment_splits_deficit_across_tanks() {
+        let zones = vec![zone(101, 33.45, -112.07, 100.0)];
+        let bottles = vec![bottle(1, 1000.0), bottle(2, 1000.0)];
+        let demand = HashMap::from([(101, 1500.0)]);
+
+        let result = HydroRouter::assign_deliveries(&bottles, &zones, &demand, 5);
+        assert_eq!(result.trips.len(), 2);
+        assert!((result.delivered_liters(101) - 1500.0).abs() < 1e-9);
+        assert_eq!(result.unmet_liters[&101], 0.0);
+        assert!(result.trips.iter().all(|t| t.liters <= 1000.0));
+    }
+
+    #[test]
+    fn test_assignment_respects_trip_limits() {
+        let zones = vec![zone(101, 33.45, -112.07, 100.0), zone(102, 33.46, -112.06, 80.0)];
+        let bottles = vec![bottle(1, 500.0)];
+        let demand = HashMap::from([(101, 1000.0), (102, 1000.0)]);
+
+        let result = HydroRouter::assign_deliveries(&bottles, &zones, &demand, 3);
+        assert_eq!(result.trip_count(1), 3);
+        let unmet: f64 = result.unmet_liters.values().sum();
+        assert!((unmet - 500.0).abs() < 1e-9);
+        // Zone 101 is filled first (higher Pz), the last trip goes to zone 102
+        assert!((result.delivered_liters(101) - 1000.0).abs() < 1e-9);
+        assert!(result.delivered_liters(101) > 0.0 && result.delivered_liters(102) > 0.0);
+    }
+
+    #[test]
+    fn test_trip_limit_overflow_moves_to_other_bottles() {
+        // The near bottle's flow splits into three partial loads but it has two
+        // trips; the dropped load goes to the far bottle instead of going unmet
+        let zones = vec![
+            zone(101, 33.45, -112.07, 100.0),
+            zone(102, 33.46, -112.07, 100.0),
+            zone(103, 33.45, -112.06, 100.0),
+        ];
+        let bottles = vec![bottle_at(1, 33.455, -112.065), bottle_at(2, 33.60, -112.07)];
+        let demand = HashMap::from([(101, 600.0), (102, 600.0), (103, 600.0)]);
+
+        let result = HydroRouter::assign_deliveries(&bottles, &zones, &demand, 2);
+        assert_eq!(result.trip_count(1), 2);
+        assert_eq!(result.trip_count(2), 1);
+        assert!(result.unmet_liters.values().all(|l| *l == 0.0));
+    }
+
+    #[test]
+    fn test_assignment_beats_greedy() {
+        // Zone 101 is slightly drier, so a greedy pick sends the central bottle
+        // there and leaves the southern bottle an 11 km haul to zone 102. The
+        // flow solution swaps them for a shorter total distance.
+        let zones = vec![zone(101, 33.45, -112.07, 110.0), zone(102, 33.50, -112.07, 100.0)];
+        let bottles = vec![bottle_at(1, 33.475, -112.07), bottle_at(2, 33.40, -112.07)];
+        let demand = HashMap::from([(101, 1000.0), (102, 1000.0)]);
+
+        let result = HydroRouter::assign_deliveries(&bottles, &zones, &demand, 1);
+        assert_eq!(result.trips.len(), 2);
+        let zone_of = |bottle_id| result.trips.iter().find(|t| t.bottle_id == bottle_id).unwrap().zone_id;
+        assert_eq!(zone_of(1), 102);
+        assert_eq!(zone_of(2), 101);
+
+        let greedy_m = 2.0
+            * (HydroRouter::distance(33.475, -112.07, 33.45, -112.07)
+                + HydroRouter::distance(33.40, -112.07, 33.50, -112.07));
+        assert!(result.total_distance_m() < greedy_m - 5000.0);
+        assert!(result.unmet_liters.values().all(|l| *l == 0.0));
+    }
+
+    #[test]
+    fn test_deficit_liters() {
+        assert_eq!(zone(1, 33.0, -112.0, 20.0).deficit_liters(50.0), 1000.0);
+    }
 }
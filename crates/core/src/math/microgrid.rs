@@ -0,0 +1,742 @@
+//! Field-base microgrid dispatch (hourly)
+//! Solar + wind generation, battery storage with charge/discharge losses,
+//! priority-based load shedding, and drone charging-slot scheduling (greedy and branch and bound).
+
+use super::harvesting::HourlyHarvest;
+use crate::models::PowerGrid;
+use crate::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Drone charging loads use ids from here up (base + drone id)
+pub const DRONE_LOAD_ID_BASE: u64 = 1 << 63;
+
+/// Search nodes `Microgrid::schedule_charging` explores before settling for its incumbent
+pub const CHARGING_SEARCH_NODE_LIMIT: usize = 200_000;
+
+/// Load shedding priority (shed from the bottom up)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LoadPriority {
+    Critical,   // Comms, sensors, safety
+    Essential,  // Base operations
+    Deferrable, // Drone charging, pumps
+}
+
+/// Scheduled device demand over local hours [start_hour, end_hour), wrapping past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoadDemand {
+    pub load_id: u64,
+    pub priority: LoadPriority,
+    pub power_w: f64,
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl LoadDemand {
+    pub fn new(load_id: u64, priority: LoadPriority, power_w: f64, start_hour: u32, end_hour: u32) -> Self {
+        Self { load_id, priority, power_w, start_hour, end_hour }
+    }
+
+    /// Always-on load
+    pub fn continuous(load_id: u64, priority: LoadPriority, power_w: f64) -> Self {
+        Self::new(load_id, priority, power_w, 0, 24)
+    }
+
+    pub fn is_active(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// Battery bank with round-trip losses
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StorageBank {
+    pub capacity_wh: f64,
+    pub stored_wh: f64,
+    pub charge_efficiency: f64,    // Input -> stored
+    pub discharge_efficiency: f64, // Stored -> output
+    pub max_charge_w: f64,
+    pub max_discharge_w: f64,
+    pub reserve_fraction: f64,     // Never discharged below this
+}
+
+impl StorageBank {
+    pub fn new(capacity_wh: f64, stored_wh: f64) -> Self {
+        Self {
+            capacity_wh,
+            stored_wh: stored_wh.clamp(0.0, capacity_wh),
+            charge_efficiency: 0.95,
+            discharge_efficiency: 0.95,
+            max_charge_w: capacity_wh / 2.0, // 0.5C
+            max_discharge_w: capacity_wh / 2.0,
+            reserve_fraction: 0.1,
+        }
+    }
+
+    /// Output power available for one hour (W)
+    pub fn deliverable_w(&self) -> f64 {
+        let above_reserve = (self.stored_wh - self.capacity_wh * self.reserve_fraction).max(0.0);
+        (above_reserve * self.discharge_efficiency).min(self.max_discharge_w)
+    }
+
+    /// Input power accepted for one hour (W)
+    pub fn absorbable_w(&self) -> f64 {
+        ((self.capacity_wh - self.stored_wh) / self.charge_efficiency).min(self.max_charge_w)
+    }
+
+    /// Charge with input energy; returns the input actually accepted (Wh)
+    pub fn charge(&mut self, input_wh: f64) -> f64 {
+        let accepted = input_wh.clamp(0.0, self.absorbable_w());
+        self.stored_wh = (self.stored_wh + accepted * self.charge_efficiency).min(self.capacity_wh);
+        accepted
+    }
+
+    /// Discharge to supply output energy; returns the output actually delivered (Wh)
+    pub fn discharge(&mut self, output_wh: f64) -> f64 {
+        let delivered = output_wh.clamp(0.0, self.deliverable_w());
+        self.stored_wh -= delivered / self.discharge_efficiency;
+        delivered
+    }
+
+    pub fn state_of_charge(&self) -> f64 {
+        self.stored_wh / self.capacity_wh
+    }
+}
+
+/// One simulated hour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HourDispatch {
+    pub hour: u32,
+    pub generation_wh: f64,
+    pub served_wh: f64,          // At the loads
+    pub served_loads: Vec<u64>,
+    pub shed_loads: Vec<u64>,
+    pub shed_wh: f64,
+    pub battery_charge_wh: f64,    // Input accepted
+    pub battery_discharge_wh: f64, // Output delivered
+    pub curtailed_wh: f64,
+    pub stored_wh: f64,            // End of hour
+}
+
+/// 24-hour dispatch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DispatchResult {
+    pub hours: Vec<HourDispatch>,
+}
+
+impl DispatchResult {
+    pub fn served_wh(&self) -> f64 {
+        self.hours.iter().map(|h| h.served_wh).sum()
+    }
+
+    pub fn shed_wh(&self) -> f64 {
+        self.hours.iter().map(|h| h.shed_wh).sum()
+    }
+
+    pub fn curtailed_wh(&self) -> f64 {
+        self.hours.iter().map(|h| h.curtailed_wh).sum()
+    }
+}
+
+/// Drone waiting on the base charger
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DroneChargeRequest {
+    pub drone_id: u64,
+    pub charge_wh: f64,       // Energy per full charge (at the pack)
+    pub charger_power_w: f64,
+    pub mission_hours: u32,   // Away time per mission
+    pub initial_wh: f64,      // Charge already in the pack at hour 0
+}
+
+/// Charger allocation for one drone-hour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChargingSlot {
+    pub drone_id: u64,
+    pub hour: u32,
+}
+
+/// Mission launch after a completed charge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissionLaunch {
+    pub drone_id: u64,
+    pub hour: u32, // Launch at the start of this hour
+}
+
+/// Charging plan and the resulting grid dispatch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChargingSchedule {
+    pub slots: Vec<ChargingSlot>,
+    pub launches: Vec<MissionLaunch>,
+    pub dispatch: DispatchResult,
+    pub proven_optimal: bool, // Launches match the best achievable
+}
+
+impl ChargingSchedule {
+    pub fn missions_launched(&self) -> usize {
+        self.launches.len()
+    }
+}
+
+/// Field-base microgrid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Microgrid {
+    pub storage: StorageBank,
+    pub solar_profile_w: Vec<f64>,      // Mean output per local hour (24 values)
+    pub wind_w: f64,                    // Mean wind output
+    pub distribution_efficiency: f64,   // Bus -> loads
+    pub loads: Vec<LoadDemand>,
+}
+
+impl Microgrid {
+    pub fn new(storage: StorageBank, solar_profile_w: Vec<f64>) -> Self {
+        Self {
+            storage,
+            solar_profile_w,
+            wind_w: 0.0,
+            distribution_efficiency: 1.0,
+            loads: Vec::new(),
+        }
+    }
+
+    /// Grid battery, wind (Sonoran availability) and distribution losses from a PowerGrid
+    pub fn from_grid(grid: &PowerGrid, solar_profile_w: Vec<f64>) -> Result<Self> {
+        let storage = StorageBank::new(grid.battery_capacity_wh, grid.battery_capacity_wh * grid.battery_soc_percent / 100.0);
+        let microgrid = Self {
+            wind_w: grid.wind_capacity_w * 0.3,
+            distribution_efficiency: grid.efficiency_percent / 100.0,
+            ..Self::new(storage, solar_profile_w)
+        };
+        microgrid.validate()?;
+        Ok(microgrid)
+    }
+
+    /// Efficiencies are divisors in dispatch and must lie in (0, 1]
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("distribution_efficiency", self.distribution_efficiency),
+            ("storage charge_efficiency", self.storage.charge_efficiency),
+            ("storage discharge_efficiency", self.storage.discharge_efficiency),
+        ] {
+            if !(value > 0.0 && value <= 1.0) {
+                return Err(CybersomethingError::DataValidationError {
+                    reason: format!("{} must be in (0, 1], got {}", name, value),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Solar profile for an array rated at `capacity_w` under 1000 W/m²
+    pub fn solar_profile(capacity_w: f64, harvest: &[HourlyHarvest]) -> Vec<f64> {
+        harvest.iter().map(|h| capacity_w * h.mean_irradiance_w_m2 / 1000.0).collect()
+    }
+
+    pub fn add_load(&mut self, load: LoadDemand) {
+        self.loads.push(load);
+    }
+
+    fn generation_w(&self, hour: u32) -> f64 {
+        self.solar_profile_w.get(hour as usize).copied().unwrap_or(0.0) + self.wind_w
+    }
+
+    /// Battery output (Wh) needed after `hour` to carry Critical and Essential loads through the day
+    fn firm_shortfall_wh(&self, hour: u32) -> f64 {
+        (hour + 1..24)
+            .map(|h| {
+                let firm_w: f64 = self
+                    .loads
+                    .iter()
+                    .filter(|l| l.priority < LoadPriority::Deferrable && l.is_active(h))
+                    .map(|l| l.power_w / self.distribution_efficiency)
+                    .sum();
+                (firm_w - self.generation_w(h)).max(0.0)
+            })
+            .sum()
+    }
+
+    /// Dispatch one hour: serve loads by priority, shed what cannot be met, store surplus
+    fn dispatch_hour(&self, storage: &mut StorageBank, hour: u32, loads: &[LoadDemand]) -> HourDispatch {
+        let generation = self.generation_w(hour);
+        let mut active: Vec<&LoadDemand> = loads.iter().filter(|l| l.is_active(hour)).collect();
+        active.sort_by_key(|l| (l.priority, l.load_id));
+
+        let mut supply = generation + storage.deliverable_w(); // At the bus
+        let (mut served, mut served_loads, mut shed_loads, mut shed_wh) = (0.0, Vec::new(), Vec::new(), 0.0);
+        for load in active {
+            let bus_w = load.power_w / self.distribution_efficiency;
+            if bus_w <= supply + 1e-9 {
+                supply -= bus_w;
+                served += load.power_w;
+                served_loads.push(load.load_id);
+            } else {
+                shed_wh += load.power_w;
+                shed_loads.push(load.load_id);
+            }
+        }
+
+        let bus_demand = served / self.distribution_efficiency;
+        let (mut charge, mut discharge, mut curtailed) = (0.0, 0.0, 0.0);
+        if generation >= bus_demand {
+            charge = storage.charge(generation - bus_demand);
+            curtailed = generation - bus_demand - charge;
+        } else {
+            discharge = storage.discharge(bus_demand - generation);
+        }
+
+        HourDispatch {
+            hour,
+            generation_wh: generation,
+            served_wh: served,
+            served_loads,
+            shed_loads,
+            shed_wh,
+            battery_charge_wh: charge,
+            battery_discharge_wh: discharge,
+            curtailed_wh: curtailed,
+            stored_wh: storage.stored_wh,
+        }
+    }
+
+    /// Simulate 24 hours of the scheduled loads
+    pub fn simulate(&self) -> Result<DispatchResult> {
+        self.validate()?;
+        let mut storage = self.storage;
+        Ok(DispatchResult {
+            hours: (0..24).map(|h| self.dispatch_hour(&mut storage, h, &self.loads)).collect(),
+        })
+    }
+
+    /// Reject load ids that would collide with drone charging loads
+    fn validate_charging(&self, drones: &[DroneChargeRequest]) -> Result<()> {
+        self.validate()?;
+        let clash = self
+            .loads
+            .iter()
+            .map(|l| l.load_id)
+            .chain(drones.iter().map(|d| d.drone_id))
+            .find(|&id| id >= DRONE_LOAD_ID_BASE);
+        match clash {
+            Some(id) => Err(CybersomethingError::DataValidationError {
+                reason: format!("load and drone ids must be below {}, got {}", DRONE_LOAD_ID_BASE, id),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn fleet_state(&self, drones: &[DroneChargeRequest]) -> FleetState {
+        FleetState {
+            storage: self.storage,
+            pack: drones.iter().map(|d| d.initial_wh.min(d.charge_wh)).collect(),
+            away_until: vec![0; drones.len()],
+            launched: 0,
+        }
+    }
+
+    /// Launch full drones that are on the ground at the start of `hour`
+    fn launch_ready(drones: &[DroneChargeRequest], state: &mut FleetState, hour: u32) -> Vec<MissionLaunch> {
+        let mut launches = Vec::new();
+        for (i, d) in drones.iter().enumerate() {
+            if state.away_until[i] <= hour && state.pack[i] >= d.charge_wh - 1e-9 {
+                launches.push(MissionLaunch { drone_id: d.drone_id, hour });
+                state.away_until[i] = hour + d.mission_hours.max(1);
+                state.pack[i] = 0.0;
+            }
+        }
+        state.launched += launches.len();
+        launches
+    }
+
+    /// Bus power left for chargers after scheduled loads, keeping battery for later firm loads
+    fn spare_charging_w(&self, storage: &StorageBank, hour: u32) -> f64 {
+        let mut probe = *storage;
+        let base = self.dispatch_hour(&mut probe, hour, &self.loads);
+        let mut reserved = *storage;
+        reserved.stored_wh -= self.firm_shortfall_wh(hour) / storage.discharge_efficiency;
+        self.generation_w(hour) + storage.deliverable_w().min(reserved.deliverable_w())
+            - base.served_wh / self.distribution_efficiency
+    }
+
+    /// Idle drones that can still take charge this hour
+    fn waiting(drones: &[DroneChargeRequest], state: &FleetState, hour: u32) -> Vec<usize> {
+        (0..drones.len())
+            .filter(|&i| state.away_until[i] <= hour && state.pack[i] < drones[i].charge_wh && drones[i].charger_power_w > 0.0)
+            .collect()
+    }
+
+    /// Charger draw for one hour (W at the pack)
+    fn draw_w(d: &DroneChargeRequest, pack_wh: f64) -> f64 {
+        d.charger_power_w.min(d.charge_wh - pack_wh)
+    }
+
+    /// Dispatch `hour` with the `charging` drones on chargers
+    fn charge_hour(&self, drones: &[DroneChargeRequest], state: &mut FleetState, hour: u32, charging: &[usize]) -> HourDispatch {
+        let mut loads = self.loads.clone();
+        for &i in charging {
+            let d = &drones[i];
+            let draw_w = Self::draw_w(d, state.pack[i]);
+            state.pack[i] += draw_w;
+            loads.push(LoadDemand::new(DRONE_LOAD_ID_BASE + d.drone_id, LoadPriority::Deferrable, draw_w, hour, hour + 1));
+        }
+        self.dispatch_hour(&mut state.storage, hour, &loads)
+    }
+
+    /// Run the day, asking `choose` which drones charge each hour
+    fn run_charging<F>(&self, drones: &[DroneChargeRequest], mut choose: F) -> ChargingSchedule
+    where
+        F: FnMut(&FleetState, u32) -> Vec<usize>,
+    {
+        let mut state = self.fleet_state(drones);
+        let (mut slots, mut launches, mut hours) = (Vec::new(), Vec::new(), Vec::new());
+        for hour in 0..24 {
+            launches.extend(Self::launch_ready(drones, &mut state, hour));
+            let charging = choose(&state, hour);
+            slots.extend(charging.iter().map(|&i| ChargingSlot { drone_id: drones[i].drone_id, hour }));
+            hours.push(self.charge_hour(drones, &mut state, hour, &charging));
+        }
+
+        let proven_optimal = launches.len() >= self.launch_upper_bound(drones);
+        ChargingSchedule {
+            slots,
+            launches,
+            dispatch: DispatchResult { hours },
+            proven_optimal,
+        }
+    }
+
+    /// Greedy pick: waiting drones nearest to full first, while spare power lasts
+    fn greedy_charging(&self, drones: &[DroneChargeRequest], state: &FleetState, hour: u32) -> Vec<usize> {
+        let mut waiting = Self::waiting(drones, state, hour);
+        waiting.sort_by(|&a, &b| {
+            let left = |i: usize| drones[i].charge_wh - state.pack[i];
+            left(a).total_cmp(&left(b)).then(drones[a].drone_id.cmp(&drones[b].drone_id))
+        });
+
+        let mut spare = self.spare_charging_w(&state.storage, hour);
+        let mut charging = Vec::new();
+        for i in waiting {
+            let bus_w = Self::draw_w(&drones[i], state.pack[i]) / self.distribution_efficiency;
+            if bus_w > spare + 1e-9 {
+                continue;
+            }
+            spare -= bus_w;
+            charging.push(i);
+        }
+        charging
+    }
+
+    /// Greedy charger scheduling heuristic for mission launches per day.
+    ///
+    /// Each hour the scheduled loads are served first; leftover bus power
+    /// (keeping enough battery to carry firm loads later in the day) is
+    /// offered to idle drones, nearest-to-full first, so charges complete (and
+    /// missions launch) as early as possible. A drone launches the hour after
+    /// its pack is full and returns empty after `mission_hours`.
+    ///
+    /// The result is not guaranteed optimal: charging early can spend energy
+    /// that a later, shorter charge cycle would turn into more launches.
+    /// `schedule_charging` searches for the best schedule.
+    pub fn schedule_charging_greedy(&self, drones: &[DroneChargeRequest]) -> Result<ChargingSchedule> {
+        self.validate_charging(drones)?;
+        Ok(self.run_charging(drones, |state, hour| self.greedy_charging(drones, state, hour)))
+    }
+
+    /// Charger schedule maximizing mission launches per day.
+    ///
+    /// Branch and bound over which idle drones charge each hour, under the
+    /// same launch and spare-power rules as `schedule_charging_greedy`. The
+    /// greedy schedule seeds the incumbent; a branch is cut when its launches
+    /// plus the relaxed bound of `launch_upper_bound` from its state cannot
+    /// beat it. The search stops after `CHARGING_SEARCH_NODE_LIMIT` nodes;
+    /// `proven_optimal` is set when it finished or the bound was reached.
+    pub fn schedule_charging(&self, drones: &[DroneChargeRequest]) -> Result<ChargingSchedule> {
+        self.validate_charging(drones)?;
+        let mut greedy_plan = Vec::new();
+        let greedy = self.run_charging(drones, |state, hour| {
+            let charging = self.greedy_charging(drones, state, hour);
+            greedy_plan.push(charging.clone());
+            charging
+        });
+        if greedy.proven_optimal {
+            return Ok(greedy);
+        }
+
+        let mut search = ChargingSearch {
+            best: greedy.missions_launched(),
+            best_plan: greedy_plan,
+            plan: Vec::new(),
+            nodes: 0,
+            exhausted: false,
+        };
+        self.branch(drones, &mut search, self.fleet_state(drones), 0);
+
+        let mut schedule = self.run_charging(drones, |_, hour| search.best_plan[hour as usize].clone());
+        schedule.proven_optimal |= !search.exhausted;
+        Ok(schedule)
+    }
+
+    fn branch(&self, drones: &[DroneChargeRequest], search: &mut ChargingSearch, mut state: FleetState, hour: u32) {
+        if hour == 24 {
+            if state.launched > search.best {
+                search.best = state.launched;
+                search.best_plan = search.plan.clone();
+            }
+            return;
+        }
+        if search.nodes >= CHARGING_SEARCH_NODE_LIMIT {
+            search.exhausted = true;
+            return;
+        }
+        search.nodes += 1;
+
+        Self::launch_ready(drones, &mut state, hour);
+        if state.launched + self.launch_bound_from(drones, &state, hour) <= search.best {
+            return;
+        }
+
+        // Feasible charger subsets, most drones first
+        let spare = self.spare_charging_w(&state.storage, hour);
+        let bus_w: Vec<(usize, f64)> = Self::waiting(drones, &state, hour)
+            .into_iter()
+            .map(|i| (i, Self::draw_w(&drones[i], state.pack[i]) / self.distribution_efficiency))
+            .collect();
+        let mut subsets = Vec::new();
+        Self::charger_subsets(&bus_w, spare, &mut Vec::new(), &mut subsets);
+        subsets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+
+        for charging in subsets {
+            let mut next = state.clone();
+            self.charge_hour(drones, &mut next, hour, &charging);
+            search.plan.push(charging);
+            self.branch(drones, search, next, hour + 1);
+            search.plan.pop();
+        }
+    }
+
+    /// Every subset of `bus_w` drawing at most `spare` watts
+    fn charger_subsets(bus_w: &[(usize, f64)], spare: f64, current: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        let Some((&(i, w), rest)) = bus_w.split_first() else {
+            out.push(current.clone());
+            return;
+        };
+        if w <= spare + 1e-9 {
+            current.push(i);
+            Self::charger_subsets(rest, spare - w, current, out);
+            current.pop();
+        }
+        Self::charger_subsets(rest, spare, current, out);
+    }
+
+    /// Upper bound on launches per day for any charging schedule.
+    ///
+    /// Relaxes the day to two budgets: each drone's charge/mission turnaround
+    /// limits its own launches, and all charging shares the day's generation
+    /// plus battery output above reserve (storage losses and scheduled loads
+    /// ignored). Launches are counted cheapest-charge first within both.
+    pub fn launch_upper_bound(&self, drones: &[DroneChargeRequest]) -> usize {
+        self.launch_bound_from(drones, &self.fleet_state(drones), 0)
+    }
+
+    /// `launch_upper_bound` for the rest of the day from `state` at `hour`
+    fn launch_bound_from(&self, drones: &[DroneChargeRequest], state: &FleetState, hour: u32) -> usize {
+        let hours_to_charge = |d: &DroneChargeRequest, wh: f64| {
+            if wh <= 1e-9 {
+                0
+            } else if d.charger_power_w <= 0.0 {
+                u32::MAX
+            } else {
+                (wh / d.charger_power_w).ceil() as u32
+            }
+        };
+
+        let mut costs = Vec::new();
+        for (i, d) in drones.iter().enumerate() {
+            let first_wh = (d.charge_wh - state.pack[i]).max(0.0);
+            let mut launch = state.away_until[i].max(hour).saturating_add(hours_to_charge(d, first_wh));
+            let mut cost = first_wh;
+            while launch < 24 {
+                costs.push(cost);
+                cost = d.charge_wh;
+                launch = launch
+                    .saturating_add(d.mission_hours.max(1))
+                    .saturating_add(hours_to_charge(d, d.charge_wh));
+            }
+        }
+        costs.sort_by(f64::total_cmp);
+
+        let storage = &state.storage;
+        let reserve_wh = storage.capacity_wh * storage.reserve_fraction;
+        let stored = (storage.stored_wh - reserve_wh).max(0.0) * storage.discharge_efficiency;
+        let mut budget = ((hour..24).map(|h| self.generation_w(h)).sum::<f64>() + stored) * self.distribution_efficiency;
+        costs
+            .into_iter()
+            .take_while(|&cost| {
+                budget -= cost;
+                budget >= -1e-9
+            })
+            .count()
+    }
+}
+
+/// Storage and drone packs while a charging schedule is built
+#[derive(Debug, Clone)]
+struct FleetState {
+    storage: StorageBank,
+    pack: Vec<f64>,
+    away_until: Vec<u32>,
+    launched: usize,
+}
+
+/// Branch-and-bound incumbent and node budget
+struct ChargingSearch {
+    best: usize,
+    best_plan: Vec<Vec<usize>>, // Drone indices charging per hour
+    plan: Vec<Vec<usize>>,
+    nodes: usize,
+    exhausted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat solar output from 07:00 to 17:00
+    fn daylight(peak_w: f64) -> Vec<f64> {
+        (0..24).map(|h| if (7..17).contains(&h) { peak_w } else { 0.0 }).collect()
+    }
+
+    #[test]
+    fn test_storage_round_trip_losses() {
+        let mut bank = StorageBank::new(1000.0, 500.0);
+        assert_eq!(bank.charge(100.0), 100.0);
+        assert!((bank.stored_wh - 595.0).abs() < 1e-9);
+        assert_eq!(bank.discharge(95.0), 95.0);
+        assert!((bank.stored_wh - 495.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_night_shedding_by_priority() {
+        let mut grid = Microgrid::new(StorageBank::new(1000.0, 300.0), daylight(0.0));
+        grid.add_load(LoadDemand::continuous(1, LoadPriority::Critical, 50.0));
+        grid.add_load(LoadDemand::continuous(2, LoadPriority::Deferrable, 150.0));
+
+        let result = grid.simulate().unwrap();
+        let first = &result.hours[0];
+        assert_eq!(first.served_loads, vec![1]); // 180 Wh above reserve cannot cover both
+        assert_eq!(first.shed_loads, vec![2]);
+        assert!(result.hours.iter().any(|h| h.served_loads.is_empty())); // Battery eventually hits reserve
+        assert!(result.hours.iter().all(|h| h.stored_wh >= 100.0 - 1e-9));
+    }
+
+    #[test]
+    fn test_surplus_charges_then_curtails() {
+        let grid = Microgrid::new(StorageBank::new(1000.0, 900.0), daylight(1000.0));
+        let result = grid.simulate().unwrap();
+        assert!((result.hours[7].battery_charge_wh - 100.0 / 0.95).abs() < 1e-9);
+        assert!(result.curtailed_wh() > 0.0);
+        assert_eq!(result.shed_wh(), 0.0);
+    }
+
+    #[test]
+    fn test_charging_schedule_respects_supply() {
+        let drones: Vec<DroneChargeRequest> = (1..=4)
+            .map(|id| DroneChargeRequest {
+                drone_id: id,
+                charge_wh: 400.0,
+                charger_power_w: 400.0,
+                mission_hours: 1,
+                initial_wh: 0.0,
+            })
+            .collect();
+
+        let mut grid = Microgrid::new(StorageBank::new(2000.0, 1000.0), daylight(1000.0));
+        grid.add_load(LoadDemand::continuous(100, LoadPriority::Critical, 40.0));
+        let schedule = grid.schedule_charging_greedy(&drones).unwrap();
+
+        assert!(schedule.missions_launched() > 0);
+        assert!(schedule.missions_launched() <= grid.launch_upper_bound(&drones));
+        assert_eq!(schedule.dispatch.shed_wh(), 0.0);
+        for hour in &schedule.dispatch.hours {
+            let chargers = schedule.slots.iter().filter(|s| s.hour == hour.hour).count();
+            assert!(chargers as f64 * 400.0 <= hour.generation_wh + 2000.0);
+        }
+
+        let sunnier = Microgrid::new(StorageBank::new(2000.0, 200.0), daylight(2000.0));
+        assert!(sunnier.schedule_charging_greedy(&drones).unwrap().missions_launched() >= schedule.missions_launched());
+    }
+
+    #[test]
+    fn test_launch_bound_is_tight_without_energy() {
+        // Packs start full and nothing can recharge them: one launch each
+        let drones: Vec<DroneChargeRequest> = (1..=3)
+            .map(|id| DroneChargeRequest {
+                drone_id: id,
+                charge_wh: 400.0,
+                charger_power_w: 400.0,
+                mission_hours: 2,
+                initial_wh: 400.0,
+            })
+            .collect();
+        let grid = Microgrid::new(StorageBank::new(1000.0, 100.0), daylight(0.0));
+        assert_eq!(grid.launch_upper_bound(&drones), 3);
+        assert_eq!(grid.schedule_charging_greedy(&drones).unwrap().missions_launched(), 3);
+    }
+
+    #[test]
+    fn test_search_beats_greedy() {
+        // Nearest-to-full, as-early-as-possible charging leaves launches on the table
+        let drones = [
+            DroneChargeRequest { drone_id: 1, charge_wh: 200.0, charger_power_w: 300.0, mission_hours: 1, initial_wh: 0.0 },
+            DroneChargeRequest { drone_id: 2, charge_wh: 400.0, charger_power_w: 100.0, mission_hours: 1, initial_wh: 200.0 },
+        ];
+        let grid = Microgrid::new(StorageBank::new(1000.0, 100.0), daylight(200.0));
+        let greedy = grid.schedule_charging_greedy(&drones).unwrap();
+        let best = grid.schedule_charging(&drones).unwrap();
+
+        assert_eq!(greedy.missions_launched(), 7);
+        assert_eq!(best.missions_launched(), 9);
+        assert!(best.proven_optimal && !greedy.proven_optimal);
+        assert!(best.missions_launched() <= grid.launch_upper_bound(&drones));
+        assert_eq!(best.dispatch.shed_wh(), 0.0);
+        let charged = &best.dispatch.hours[best.slots[0].hour as usize].served_loads;
+        assert!(charged.contains(&(DRONE_LOAD_ID_BASE + best.slots[0].drone_id)));
+    }
+
+    #[test]
+    fn test_rejects_ids_in_drone_load_space() {
+        let mut grid = Microgrid::new(StorageBank::new(1000.0, 500.0), daylight(100.0));
+        let drone = DroneChargeRequest { drone_id: DRONE_LOAD_ID_BASE, charge_wh: 100.0, charger_power_w: 100.0, mission_hours: 1, initial_wh: 0.0 };
+        assert!(grid.schedule_charging(&[drone]).is_err());
+
+        grid.add_load(LoadDemand::continuous(DRONE_LOAD_ID_BASE + 1, LoadPriority::Critical, 10.0));
+        assert!(grid.schedule_charging_greedy(&[]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_efficiency() {
+        let mut grid = Microgrid::new(StorageBank::new(1000.0, 500.0), daylight(100.0));
+        grid.distribution_efficiency = 0.0;
+        assert!(grid.simulate().is_err());
+        assert!(grid.schedule_charging_greedy(&[]).is_err());
+
+        let mut power = PowerGrid::new(1);
+        power.efficiency_percent = 0.0;
+        assert!(Microgrid::from_grid(&power, daylight(100.0)).is_err());
+        power.efficiency_percent = 120.0;
+        assert!(Microgrid::from_grid(&power, daylight(100.0)).is_err());
+    }
+
+    #[test]
+    fn test_profile_from_harvest_and_grid() {
+        let harvest: Vec<HourlyHarvest> = (0..24)
+            .map(|hour| HourlyHarvest { hour, mean_irradiance_w_m2: if hour == 12 { 1000.0 } else { 0.0 }, solar_wh: 0.0, rf_wh: 0.0 })
+            .collect();
+        let profile = Microgrid::solar_profile(500.0, &harvest);
+        assert_eq!(profile[12], 500.0);
+
+        let grid = Microgrid::from_grid(&PowerGrid::new(1), profile).unwrap();
+        assert_eq!(grid.storage.stored_wh, 8000.0);
+        assert!((grid.wind_w - 30.0).abs() < 1e-9);
+    }
+}
@@ -1,11 +1,29 @@
 //! Mathematical calculations and algorithms
 
 pub mod risk_index;
+pub mod normalization;
+pub mod defensible;
 pub mod routing;
+pub mod road_network;
+pub mod vehicle_routing;
+pub mod recharge_planner;
 pub mod hydrology;
+pub mod water_demand;
 pub mod energy_calc;
+pub mod flight_energy;
+pub mod harvesting;
+pub mod microgrid;
 
 pub use risk_index::*;
+pub use normalization::*;
+pub use defensible::*;
 pub use routing::*;
+pub use road_network::*;
+pub use vehicle_routing::*;
+pub use recharge_planner::*;
 pub use hydrology::*;
+pub use water_demand::*;
 pub use energy_calc::*;
+pub use flight_energy::*;
+pub use harvesting::*;
+pub use microgrid::*;
@@ -0,0 +1,270 @@
+//! Normalization profiles for risk index inputs
+//! Maps raw Vi (trees/ha), Gi (% cover or cm), Si (degrees) onto [0, 1]
+//! so regional calibrations (Phoenix, Amazon, ...) can be swapped as data.
+
+use crate::math::risk_index::RiskTerms;
+use crate::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Score assigned at and above a raw-value threshold
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdBand {
+    pub threshold: f64,
+    pub score: f64,
+}
+
+/// Curve shape applied after scaling raw input to t = (x - floor) / (reference - floor)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum Curve {
+    Linear,
+    Log,                                       // ln(1 + 9t) / ln(10)
+    Sigmoid { midpoint: f64, steepness: f64 }, // Midpoint in t units; rescaled so t = 0 -> 0, t = 1 -> 1
+    Piecewise { bands: Vec<ThresholdBand> },   // Step function on raw (unclamped) values
+}
+
+/// Normalization rule for a single term
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TermNormalization {
+    pub floor: f64,     // Raw value scoring 0 (inputs below are clamped)
+    pub reference: f64, // Raw value scoring 1
+    pub ceiling: f64,   // Raw inputs above are clamped
+    pub curve: Curve,
+}
+
+impl TermNormalization {
+    pub fn linear(floor: f64, reference: f64) -> Self {
+        Self {
+            floor,
+            reference,
+            ceiling: reference,
+            curve: Curve::Linear,
+        }
+    }
+
+    /// Step bands, e.g. 0/10/20 cm grass height thresholds
+    pub fn piecewise(bands: Vec<ThresholdBand>) -> Self {
+        let floor = bands.first().map(|b| b.threshold).unwrap_or(0.0);
+        let reference = bands.last().map(|b| b.threshold).unwrap_or(1.0);
+        Self {
+            floor,
+            reference,
+            ceiling: reference,
+            curve: Curve::Piecewise { bands },
+        }
+    }
+
+    /// Normalize a raw value to [0, 1]
+    pub fn apply(&self, raw: f64) -> f64 {
+        if raw.is_nan() {
+            return 0.0;
+        }
+        let x = raw.clamp(self.floor, self.ceiling);
+        let t = (x - self.floor) / (self.reference - self.floor);
+
+        let score = match &self.curve {
+            Curve::Linear => t,
+            Curve::Log => (1.0 + 9.0 * t).ln() / 10f64.ln(),
+            Curve::Sigmoid { midpoint, steepness } => {
+                let logistic = |t: f64| 1.0 / (1.0 + (-steepness * (t - midpoint)).exp());
+                let (low, high) = (logistic(0.0), logistic(1.0));
+                (logistic(t) - low) / (high - low)
+            }
+            Curve::Piecewise { bands } => bands
+                .iter()
+                .rev()
+                .find(|b| raw >= b.threshold)
+                .map(|b| b.score)
+                .unwrap_or(0.0),
+        };
+
+        score.clamp(0.0, 1.0)
+    }
+
+    /// Validate bounds and curve parameters
+    pub fn validate(&self, term: &str) -> Result<()> {
+        let invalid = |reason: String| CybersomethingError::DataValidationError {
+            reason: format!("{} normalization: {}", term, reason),
+        };
+
+        if !(self.floor.is_finite() && self.reference.is_finite() && self.ceiling.is_finite()) {
+            return Err(invalid("floor, reference and ceiling must be finite".into()));
+        }
+        // Step curves read raw values, so a single band may pin reference to floor
+        let piecewise = matches!(self.curve, Curve::Piecewise { .. });
+        if self.reference < self.floor || (self.reference == self.floor && !piecewise) {
+            return Err(invalid(format!(
+                "reference {} must exceed floor {}",
+                self.reference, self.floor
+            )));
+        }
+        if self.ceiling < self.reference {
+            return Err(invalid(format!(
+                "ceiling {} is below reference {}",
+                self.ceiling, self.reference
+            )));
+        }
+
+        match &self.curve {
+            Curve::Linear | Curve::Log => {}
+            Curve::Sigmoid { midpoint, steepness } => {
+                if !midpoint.is_finite() || !steepness.is_finite() || *steepness <= 0.0 {
+                    return Err(invalid("sigmoid needs finite midpoint and positive steepness".into()));
+                }
+            }
+            Curve::Piecewise { bands } => {
+                if bands.is_empty() {
+                    return Err(invalid("piecewise curve has no bands".into()));
+                }
+                if bands.windows(2).any(|w| w[1].threshold <= w[0].threshold) {
+                    return Err(invalid("piecewise thresholds must be strictly ascending".into()));
+                }
+                if bands.iter().any(|b| !(0.0..=1.0).contains(&b.score)) {
+                    return Err(invalid("piecewise scores must lie in [0, 1]".into()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Per-term normalization for the risk index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NormalizationProfile {
+    pub name: String,
+    pub vegetation: TermNormalization,
+    pub grass: TermNormalization,
+    pub slope: TermNormalization,
+}
+
+impl NormalizationProfile {
+    /// Sonoran Desert calibration: 1000 trees/ha, 100 % cover, 60° slope
+    pub fn sonoran() -> Self {
+        Self {
+            name: "sonoran".to_string(),
+            vegetation: TermNormalization::linear(0.0, 1000.0),
+            grass: TermNormalization::linear(0.0, 100.0),
+            slope: TermNormalization::linear(0.0, 60.0),
+        }
+    }
+
+    /// Parse and validate a JSON profile
+    pub fn from_json(json: &str) -> Result<Self> {
+        let profile: Self = serde_json::from_str(json)
+            .map_err(|e| CybersomethingError::SerializationError(e.to_string()))?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| CybersomethingError::SerializationError(e.to_string()))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.vegetation.validate("vegetation")?;
+        self.grass.validate("grass")?;
+        self.slope.validate("slope")
+    }
+
+    /// Normalize raw inputs to [0, 1] terms
+    pub fn apply(&self, vi: f64, gi: f64, si: f64) -> RiskTerms {
+        RiskTerms {
+            vi: self.vegetation.apply(vi),
+            gi: self.grass.apply(gi),
+            si: self.slope.apply(si),
+        }
+    }
+}
+
+impl Default for NormalizationProfile {
+    fn default() -> Self {
+        Self::sonoran()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_sonoran_clamps_all_terms() {
+        let profile = NormalizationProfile::sonoran();
+        let terms = profile.apply(500.0, 140.0, -5.0);
+        assert_abs_diff_eq!(terms.vi, 0.5);
+        assert_eq!(terms.gi, 1.0);
+        assert_eq!(terms.si, 0.0);
+    }
+
+    #[test]
+    fn test_grass_height_bands() {
+        let grass = TermNormalization::piecewise(vec![
+            ThresholdBand { threshold: 0.0, score: 0.0 },
+            ThresholdBand { threshold: 10.0, score: 0.5 },
+            ThresholdBand { threshold: 20.0, score: 1.0 },
+        ]);
+        assert_eq!(grass.apply(5.0), 0.0);
+        assert_eq!(grass.apply(12.0), 0.5);
+        assert_eq!(grass.apply(35.0), 1.0);
+    }
+
+    #[test]
+    fn test_single_band_piecewise_validates() {
+        let mowed = TermNormalization::piecewise(vec![ThresholdBand { threshold: 15.0, score: 1.0 }]);
+        assert!(mowed.validate("grass").is_ok());
+        assert_eq!(mowed.apply(10.0), 0.0);
+        assert_eq!(mowed.apply(15.0), 1.0);
+    }
+
+    #[test]
+    fn test_log_and_sigmoid_curves() {
+        let mut log = TermNormalization::linear(0.0, 2000.0);
+        log.curve = Curve::Log;
+        assert_eq!(log.apply(0.0), 0.0);
+        assert_abs_diff_eq!(log.apply(2000.0), 1.0, epsilon = 1e-12);
+        assert!(log.apply(500.0) > 0.25);
+
+        let mut sigmoid = TermNormalization::linear(0.0, 60.0);
+        sigmoid.curve = Curve::Sigmoid { midpoint: 0.5, steepness: 10.0 };
+        assert_abs_diff_eq!(sigmoid.apply(30.0), 0.5, epsilon = 1e-12);
+        assert_eq!(sigmoid.apply(0.0), 0.0);
+        assert_abs_diff_eq!(sigmoid.apply(60.0), 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_profiles_coexist_from_json() {
+        let amazon_json = r#"{
+            "name": "amazon",
+            "vegetation": { "floor": 0.0, "reference": 2000.0, "ceiling": 5000.0, "curve": { "shape": "log" } },
+            "grass": { "floor": 0.0, "reference": 60.0, "ceiling": 100.0, "curve": { "shape": "linear" } },
+            "slope": { "floor": 0.0, "reference": 45.0, "ceiling": 90.0,
+                       "curve": { "shape": "sigmoid", "midpoint": 0.5, "steepness": 8.0 } }
+        }"#;
+        let amazon = NormalizationProfile::from_json(amazon_json).unwrap();
+        let phoenix = NormalizationProfile::from_json(&NormalizationProfile::sonoran().to_json().unwrap()).unwrap();
+
+        assert_eq!(phoenix, NormalizationProfile::sonoran());
+        assert!(amazon.apply(500.0, 30.0, 20.0).gi > phoenix.apply(500.0, 30.0, 20.0).gi);
+    }
+
+    #[test]
+    fn test_invalid_profile_rejected() {
+        let mut profile = NormalizationProfile::sonoran();
+        profile.slope.reference = 0.0;
+        assert!(matches!(
+            profile.validate(),
+            Err(CybersomethingError::DataValidationError { .. })
+        ));
+
+        profile = NormalizationProfile::sonoran();
+        profile.grass.curve = Curve::Piecewise {
+            bands: vec![
+                ThresholdBand { threshold: 10.0, score: 0.5 },
+                ThresholdBand { threshold: 5.0, score: 1.0 },
+            ],
+        };
+        assert!(profile.validate().is_err());
+    }
+}
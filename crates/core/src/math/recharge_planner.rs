@@ -0,0 +1,431 @@
+//! Energy-aware drone mission planning with recharge stops
+//! Walks a waypoint sequence, tracking state of charge per leg, and detours
+//! to a charging site whenever a leg would breach the flight SoC floor.
+
+use super::energy_calc::{joules_to_wh, DroneEnergyModel};
+use super::flight_energy::{ElevationSource, FlatTerrain, FlightConditions, FlightEnergyModel, FlightLeg};
+use super::routing::Waypoint;
+use crate::models::{Drone, LatLon, PowerGrid};
+use crate::utils::constants::energy::DRONE_MIN_SOC_PERCENT;
+use crate::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Drone charging location
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChargingSite {
+    pub site_id: u32,
+    pub location: LatLon,
+    pub charge_power_w: f64,
+}
+
+impl ChargingSite {
+    pub fn new(site_id: u32, location: LatLon, charge_power_w: f64) -> Self {
+        Self {
+            site_id,
+            location,
+            charge_power_w,
+        }
+    }
+
+    /// Site backed by a power grid node, charging at the grid's available power
+    pub fn from_grid(grid: &PowerGrid, location: LatLon) -> Self {
+        Self::new(grid.grid_id, location, grid.available_power_w())
+    }
+
+    /// Time (s) to put `energy_wh` back into a pack; errors when the site has no charge power
+    pub fn charge_time_s(&self, energy_wh: f64) -> Result<f64> {
+        if self.charge_power_w.is_nan() || self.charge_power_w <= 0.0 {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("charging site {} has no charge power ({} W)", self.site_id, self.charge_power_w),
+            });
+        }
+        Ok(energy_wh / self.charge_power_w * 3600.0)
+    }
+}
+
+/// Where a leg ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LegStop {
+    Waypoint { zone_id: u32 },
+    Recharge { site_id: u32 },
+}
+
+/// One flown leg with state of charge before and after
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedLeg {
+    pub stop: LegStop,
+    pub location: LatLon,
+    pub distance_m: f64,
+    pub energy_wh: f64,
+    pub soc_start_percent: f64,
+    pub soc_end_percent: f64,    // On arrival, before any charging
+    pub charge_time_s: f64,      // Recharge stops only: time to full
+}
+
+/// Feasible mission plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyMission {
+    pub drone_id: u64,
+    pub legs: Vec<PlannedLeg>,
+    pub total_distance_m: f64,
+    pub total_energy_wh: f64,
+}
+
+impl EnergyMission {
+    pub fn recharge_count(&self) -> usize {
+        self.legs.iter().filter(|l| matches!(l.stop, LegStop::Recharge { .. })).count()
+    }
+}
+
+/// Per-plan inputs shared by leg costing
+struct LegContext<'a> {
+    drone: &'a Drone,
+    terrain: &'a dyn ElevationSource,
+}
+
+/// Plans waypoint missions against battery limits
+#[derive(Debug, Clone)]
+pub struct RechargePlanner {
+    pub sites: Vec<ChargingSite>,
+    pub battery_wh: Option<f64>, // None = drone's current pack capacity
+    pub min_soc_percent: f64,
+    pub energy_model: DroneEnergyModel,
+    pub flight: Option<(FlightEnergyModel, FlightConditions)>, // Physical model replaces `energy_model`
+    pub delivery_hover_s: f64,   // Hover at waypoints with an action (physical model)
+}
+
+impl Default for RechargePlanner {
+    fn default() -> Self {
+        Self {
+            sites: Vec::new(),
+            battery_wh: None,
+            min_soc_percent: DRONE_MIN_SOC_PERCENT,
+            energy_model: DroneEnergyModel::default(),
+            flight: None,
+            delivery_hover_s: 30.0,
+        }
+    }
+}
+
+impl RechargePlanner {
+    pub fn new(sites: Vec<ChargingSite>) -> Self {
+        Self {
+            sites,
+            ..Self::default()
+        }
+    }
+
+    pub fn add_site(&mut self, site: ChargingSite) {
+        self.sites.push(site);
+    }
+
+    /// Use the terrain/wind/density-aware model instead of the MATH.md budget
+    pub fn with_flight_model(mut self, model: FlightEnergyModel, conditions: FlightConditions) -> Self {
+        self.battery_wh = Some(model.battery_wh);
+        self.flight = Some((model, conditions));
+        self
+    }
+
+    /// Leg energy (Wh); unflyable legs (headwind above airspeed) cost infinity
+    fn leg_energy_wh(&self, leg: &LegContext, from: &LatLon, to: &LatLon, delivers_payload: bool) -> f64 {
+        match &self.flight {
+            Some((model, conditions)) => {
+                let hover_s = if delivers_payload { self.delivery_hover_s } else { 0.0 };
+                let flight_leg = FlightLeg::new(*from, *to).with_hover(hover_s);
+                model
+                    .leg_energy(&flight_leg, conditions, leg.terrain)
+                    .map_or(f64::INFINITY, |e| e.total_wh)
+            }
+            None => joules_to_wh(
+                self.energy_model
+                    .cost(leg.drone, from.distance_to(to), delivers_payload as u32)
+                    .energy_j,
+            ),
+        }
+    }
+
+    /// Energy above the SoC floor (Wh)
+    fn usable_wh(&self, battery_wh: f64, soc_percent: f64) -> f64 {
+        ((soc_percent - self.min_soc_percent) / 100.0 * battery_wh).max(0.0)
+    }
+
+    /// Energy to fly from a point to the nearest charging site (Wh), 0 if none
+    fn reserve_wh(&self, leg: &LegContext, from: &LatLon) -> f64 {
+        self.sites
+            .iter()
+            .map(|s| self.leg_energy_wh(leg, from, &s.location, false))
+            .fold(None, |best: Option<f64>, e| Some(best.map_or(e, |b| b.min(e))))
+            .unwrap_or(0.0)
+    }
+
+    /// Shortest chain of charging sites (indices into `sites`) from `from` to `target`.
+    ///
+    /// Dijkstra over sites by distance flown. The state of charge at departure is
+    /// the only thing that limits a hop: the drone leaves `from` with `start_wh`
+    /// above the floor and every site fully charged (`full_wh`). The chain ends at a
+    /// site for which `can_finish` holds; None if no such chain exists.
+    fn recharge_chain(
+        &self,
+        ctx: &LegContext,
+        from: &LatLon,
+        start_wh: f64,
+        full_wh: f64,
+        target: &LatLon,
+        can_finish: impl Fn(&LatLon) -> bool,
+    ) -> Option<Vec<usize>> {
+        let n = self.sites.len();
+        let goal = n; // Virtual node: the target, reached from a finishing site
+        let mut dist = vec![f64::INFINITY; n + 1];
+        let mut prev: Vec<Option<usize>> = vec![None; n + 1];
+        let mut done = vec![false; n + 1];
+
+        for (i, site) in self.sites.iter().enumerate() {
+            if self.leg_energy_wh(ctx, from, &site.location, false) <= start_wh {
+                dist[i] = from.distance_to(&site.location);
+            }
+        }
+
+        loop {
+            let current = (0..=n)
+                .filter(|&i| !done[i] && dist[i].is_finite())
+                .min_by(|&a, &b| dist[a].total_cmp(&dist[b]))?;
+            if current == goal {
+                break;
+            }
+            done[current] = true;
+
+            let here = self.sites[current].location;
+            if can_finish(&here) {
+                let total = dist[current] + here.distance_to(target);
+                if total < dist[goal] {
+                    dist[goal] = total;
+                    prev[goal] = Some(current);
+                }
+            }
+            for (next, site) in self.sites.iter().enumerate() {
+                if done[next] || self.leg_energy_wh(ctx, &here, &site.location, false) > full_wh {
+                    continue;
+                }
+                let total = dist[current] + here.distance_to(&site.location);
+                if total < dist[next] {
+                    dist[next] = total;
+                    prev[next] = Some(current);
+                }
+            }
+        }
+
+        let mut chain = Vec::new();
+        let mut node = prev[goal];
+        while let Some(site) = node {
+            chain.push(site);
+            node = prev[site];
+        }
+        chain.reverse();
+        Some(chain)
+    }
+
+    /// Plan the waypoint sequence from the drone's current position and charge.
+    /// Intermediate waypoints must leave enough charge to reach a site; the last may not.
+    pub fn plan(&self, drone: &Drone, waypoints: &[Waypoint]) -> Result<EnergyMission> {
+        self.plan_over_terrain(drone, waypoints, &FlatTerrain(0.0))
+    }
+
+    /// As `plan`, with climbs taken from a DEM (physical model only)
+    pub fn plan_over_terrain(&self, drone: &Drone, waypoints: &[Waypoint], terrain: &dyn ElevationSource) -> Result<EnergyMission> {
+        let ctx = LegContext { drone, terrain };
+        let battery_wh = self.battery_wh.unwrap_or(drone.battery_capacity_wh());
+        let usable_wh = |soc: f64| self.usable_wh(battery_wh, soc);
+        let mut position = LatLon {
+            latitude: drone.position.0,
+            longitude: drone.position.1,
+        };
+        let mut soc = drone.battery_percent;
+        let mut legs = Vec::new();
+
+        for (i, wp) in waypoints.iter().enumerate() {
+            let target = LatLon {
+                latitude: wp.latitude,
+                longitude: wp.longitude,
+            };
+            let is_last = i + 1 == waypoints.len();
+            let demand = |from: &LatLon| {
+                let leg = self.leg_energy_wh(&ctx, from, &target, wp.action.is_some());
+                let reserve = if is_last { 0.0 } else { self.reserve_wh(&ctx, &target) };
+                (leg, reserve)
+            };
+
+            let (leg, reserve) = demand(&position);
+            if leg + reserve > usable_wh(soc) {
+                // Detour through one or more sites, the last of which can make the leg on a full charge
+                let full = usable_wh(100.0);
+                let chain = self
+                    .recharge_chain(&ctx, &position, usable_wh(soc), full, &target, |site| {
+                        let (leg, reserve) = demand(site);
+                        leg + reserve <= full
+                    })
+                    .ok_or(CybersomethingError::InsufficientEnergy {
+                        needed_wh: leg + reserve,
+                        available_wh: usable_wh(soc),
+                    })?;
+
+                for site in chain.into_iter().map(|i| &self.sites[i]) {
+                    let distance_m = position.distance_to(&site.location);
+                    let energy_wh = self.leg_energy_wh(&ctx, &position, &site.location, false);
+                    let soc_end = soc - energy_wh / battery_wh * 100.0;
+                    legs.push(PlannedLeg {
+                        stop: LegStop::Recharge { site_id: site.site_id },
+                        location: site.location,
+                        distance_m,
+                        energy_wh,
+                        soc_start_percent: soc,
+                        soc_end_percent: soc_end,
+                        charge_time_s: site.charge_time_s((100.0 - soc_end) / 100.0 * battery_wh)?,
+                    });
+                    position = site.location;
+                    soc = 100.0;
+                }
+            }
+
+            let distance_m = position.distance_to(&target);
+            let energy_wh = self.leg_energy_wh(&ctx, &position, &target, wp.action.is_some());
+            let soc_end = soc - energy_wh / battery_wh * 100.0;
+            legs.push(PlannedLeg {
+                stop: LegStop::Waypoint { zone_id: wp.zone_id },
+                location: target,
+                distance_m,
+                energy_wh,
+                soc_start_percent: soc,
+                soc_end_percent: soc_end,
+                charge_time_s: 0.0,
+            });
+            position = target;
+            soc = soc_end;
+        }
+
+        Ok(EnergyMission {
+            drone_id: drone.id,
+            total_distance_m: legs.iter().map(|l| l.distance_m).sum(),
+            total_energy_wh: legs.iter().map(|l| l.energy_wh).sum(),
+            legs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DroneType;
+
+    fn waypoint(zone_id: u32, lat: f64, lon: f64) -> Waypoint {
+        Waypoint {
+            zone_id,
+            latitude: lat,
+            longitude: lon,
+            altitude_m: 50.0,
+            action: Some("water_2L".to_string()),
+        }
+    }
+
+    /// Quadcopter at 21% charge: ~5 Wh above the floor, roughly 15 km of flight
+    fn drone() -> Drone {
+        let mut drone = Drone::new(1, DroneType::Quadcopter);
+        drone.position = (33.40, -112.00, 0.0);
+        drone.battery_percent = 21.0;
+        drone
+    }
+
+    #[test]
+    fn test_short_mission_needs_no_recharge() {
+        let planner = RechargePlanner::default();
+        let mission = planner.plan(&drone(), &[waypoint(1, 33.41, -112.00), waypoint(2, 33.42, -112.00)]).unwrap();
+        assert_eq!(mission.recharge_count(), 0);
+        assert_eq!(mission.legs.len(), 2);
+        assert!(mission.legs[1].soc_end_percent > DRONE_MIN_SOC_PERCENT);
+        assert!((mission.legs[0].soc_end_percent - mission.legs[1].soc_start_percent).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_inserts_recharge_stop() {
+        let site = ChargingSite::new(7, LatLon::new(33.45, -112.00), 1000.0);
+        let planner = RechargePlanner::new(vec![site]);
+        // ~33 km north: out of reach on 1% usable charge
+        let mission = planner.plan(&drone(), &[waypoint(1, 33.70, -112.00)]).unwrap();
+
+        assert_eq!(mission.recharge_count(), 1);
+        assert_eq!(mission.legs[0].stop, LegStop::Recharge { site_id: 7 });
+        assert!(mission.legs[0].charge_time_s > 0.0);
+        assert_eq!(mission.legs[1].soc_start_percent, 100.0);
+        assert!(mission.legs.iter().all(|l| l.soc_end_percent >= DRONE_MIN_SOC_PERCENT));
+    }
+
+    #[test]
+    fn test_unpowered_site_is_an_error() {
+        let site = ChargingSite::new(7, LatLon::new(33.45, -112.00), 0.0);
+        let planner = RechargePlanner::new(vec![site]);
+        assert!(matches!(
+            planner.plan(&drone(), &[waypoint(1, 33.70, -112.00)]),
+            Err(CybersomethingError::DataValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_chains_two_recharge_stops() {
+        // 20 Wh pack: a full charge covers ~48 km, 21% only ~600 m. Sites lie in a
+        // line north of the drone; the target 83 km out needs both of them.
+        let near = ChargingSite::new(1, LatLon::new(33.404, -112.00), 1000.0);
+        let middle = ChargingSite::new(2, LatLon::new(33.80, -112.00), 1000.0);
+        let target = [waypoint(1, 34.15, -112.00)];
+
+        let mut planner = RechargePlanner::new(vec![middle, near]);
+        planner.battery_wh = Some(20.0);
+        let mission = planner.plan(&drone(), &target).unwrap();
+
+        assert_eq!(mission.recharge_count(), 2);
+        assert_eq!(mission.legs[0].stop, LegStop::Recharge { site_id: 1 });
+        assert_eq!(mission.legs[1].stop, LegStop::Recharge { site_id: 2 });
+        assert_eq!(mission.legs[2].stop, LegStop::Waypoint { zone_id: 1 });
+        assert_eq!(mission.legs[2].soc_start_percent, 100.0);
+        assert!(mission.legs.iter().all(|l| l.soc_end_percent >= DRONE_MIN_SOC_PERCENT));
+
+        // Without the middle site the same mission cannot be flown
+        planner.sites = vec![near];
+        assert!(matches!(
+            planner.plan(&drone(), &target),
+            Err(CybersomethingError::InsufficientEnergy { .. })
+        ));
+    }
+
+    #[test]
+    fn test_insufficient_energy() {
+        let planner = RechargePlanner::default();
+        let err = planner.plan(&drone(), &[waypoint(1, 33.70, -112.00)]).unwrap_err();
+        assert!(matches!(err, CybersomethingError::InsufficientEnergy { .. }));
+    }
+
+    #[test]
+    fn test_flight_model_headwind_costs_more() {
+        use crate::math::flight_energy::Wind;
+        use crate::models::DroneType;
+
+        let mut drone = drone();
+        drone.battery_percent = 100.0;
+        let model = FlightEnergyModel::for_drone_type(DroneType::Quadcopter);
+        let route = [waypoint(1, 33.45, -112.00)];
+
+        let calm = RechargePlanner::default().with_flight_model(model, FlightConditions::default());
+        let windy = RechargePlanner::default()
+            .with_flight_model(model, FlightConditions { wind: Wind::new(6.0, 0.0), ..Default::default() });
+
+        let calm = calm.plan(&drone, &route).unwrap();
+        let windy = windy.plan(&drone, &route).unwrap();
+        assert!(windy.total_energy_wh > calm.total_energy_wh);
+    }
+
+    #[test]
+    fn test_site_from_grid() {
+        let grid = PowerGrid::new(3);
+        let site = ChargingSite::from_grid(&grid, LatLon::new(33.4, -112.0));
+        assert_eq!(site.site_id, 3);
+        assert!(site.charge_power_w > 0.0);
+    }
+}
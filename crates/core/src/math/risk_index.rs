@@ -1,13 +1,18 @@
 //! Sonoran Desert Parcel Risk Index
-//! Pi = α·Vi + β·Gi + γ·Si
+//! Additive form (docs/MATH.md):   Pi = α·Vi + β·Gi + γ·Si
+//! Multiplicative form (README):   Pi = Vi·Gi·Si
 //! Where:
 //!   Vi = Vegetation density (trees/ha)
 //!   Gi = Invasive grass cover (%)
 //!   Si = Slope steepness (degrees)
 
-use approx::abs_diff_eq;
+use crate::math::defensible::{DefensibleRuleTable, DefensibleZonePlan};
+use crate::math::normalization::NormalizationProfile;
+use crate::utils::constants::risk_coefficients::{RISK_LOW_THRESHOLD, RISK_MEDIUM_THRESHOLD};
+use serde::{Deserialize, Serialize};
 
 /// Risk coefficients calibrated for Sonoran Desert WUI
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct RiskWeights {
     pub alpha: f64,   // Vegetation density weight
     pub beta: f64,    // Invasive grass weight
@@ -24,44 +29,231 @@ impl Default for RiskWeights {
     }
 }
 
+/// Normalized risk terms, each in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskTerms {
+    pub vi: f64,
+    pub gi: f64,
+    pub si: f64,
+}
+
+impl RiskTerms {
+    pub fn new(vi: f64, gi: f64, si: f64) -> Self {
+        Self { vi, gi, si }
+    }
+}
+
+/// Composition of normalized terms into a single priority index
+pub trait RiskFormula: Send + Sync {
+    /// Combine normalized terms into Pi (unclamped)
+    fn combine(&self, terms: &RiskTerms) -> f64;
+}
+
+/// Pi = α·Vi + β·Gi + γ·Si
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedSum {
+    pub weights: RiskWeights,
+}
+
+impl RiskFormula for WeightedSum {
+    fn combine(&self, terms: &RiskTerms) -> f64 {
+        self.weights.alpha * terms.vi + self.weights.beta * terms.gi + self.weights.gamma * terms.si
+    }
+}
+
+/// Pi = Vi·Gi·Si (any zero term zeroes the cell)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Product;
+
+impl RiskFormula for Product {
+    fn combine(&self, terms: &RiskTerms) -> f64 {
+        terms.vi * terms.gi * terms.si
+    }
+}
+
+/// User-defined composition over the normalized terms
+pub struct CustomFormula<F>
+where
+    F: Fn(&RiskTerms) -> f64 + Send + Sync,
+{
+    combine_fn: F,
+}
+
+impl<F> CustomFormula<F>
+where
+    F: Fn(&RiskTerms) -> f64 + Send + Sync,
+{
+    pub fn new(combine_fn: F) -> Self {
+        Self { combine_fn }
+    }
+}
+
+impl<F> RiskFormula for CustomFormula<F>
+where
+    F: Fn(&RiskTerms) -> f64 + Send + Sync,
+{
+    fn combine(&self, terms: &RiskTerms) -> f64 {
+        (self.combine_fn)(terms)
+    }
+}
+
+/// Built-in formula a jurisdiction can be held to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RiskFormulaKind {
+    Additive,        // α·V + β·G + γ·S
+    Multiplicative,  // V·G·S
+}
+
+impl RiskFormulaKind {
+    /// Instantiate the formula (weights only apply to the additive form)
+    pub fn build(&self, weights: RiskWeights) -> Box<dyn RiskFormula> {
+        match self {
+            Self::Additive => Box::new(WeightedSum { weights }),
+            Self::Multiplicative => Box::new(Product),
+        }
+    }
+}
+
+/// Risk zone boundaries on Pi
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskThresholds {
+    pub low: f64,     // Pi below this is Low
+    pub medium: f64,  // Pi below this (and ≥ low) is Medium
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self {
+            low: RISK_LOW_THRESHOLD,
+            medium: RISK_MEDIUM_THRESHOLD,
+        }
+    }
+}
+
+/// Risk zone classification of a priority index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RiskZone {
+    Low,     // Pi < 0.33
+    Medium,  // 0.33 ≤ Pi < 0.67
+    High,    // Pi ≥ 0.67
+}
+
+impl RiskZone {
+    /// Classify with the default Sonoran thresholds
+    pub fn from_index(risk_index: f64) -> Self {
+        Self::classify(risk_index, &RiskThresholds::default())
+    }
+
+    /// Classify with jurisdiction-specific thresholds
+    pub fn classify(risk_index: f64, thresholds: &RiskThresholds) -> Self {
+        match risk_index {
+            r if r < thresholds.low => Self::Low,
+            r if r < thresholds.medium => Self::Medium,
+            _ => Self::High,
+        }
+    }
+
+    /// Class value used in zone rasters (0 = Low, 1 = Medium, 2 = High)
+    pub fn class_value(&self) -> u8 {
+        match self {
+            Self::Low => 0,
+            Self::Medium => 1,
+            Self::High => 2,
+        }
+    }
+}
+
 /// Parcel risk index calculation
 pub struct RiskCalculator {
-    weights: RiskWeights,
+    formula: Box<dyn RiskFormula>,
+    normalization: NormalizationProfile,
+    defensible_rules: DefensibleRuleTable,
+    thresholds: RiskThresholds,
 }
 
 impl RiskCalculator {
+    /// Additive calculator with the given weights
     pub fn new(weights: RiskWeights) -> Self {
-        Self { weights }
+        Self {
+            formula: Box::new(WeightedSum { weights }),
+            normalization: NormalizationProfile::default(),
+            defensible_rules: DefensibleRuleTable::default(),
+            thresholds: RiskThresholds::default(),
+        }
     }
 
-    /// Compute risk index for a parcel (0.0 = safe, 1.0 = critical)
-    pub fn compute_risk(&self, vi: f64, gi: f64, si: f64) -> f64 {
-        // Normalize inputs to [0, 1]
-        let vi_norm = (vi / 1000.0).min(1.0);  // Max 1000 trees/ha
-        let gi_norm = gi / 100.0;               // Already percentage
-        let si_norm = (si / 60.0).min(1.0);    // Max 60° slope
+    /// Calculator using an arbitrary formula
+    pub fn with_formula<F: RiskFormula + 'static>(formula: F) -> Self {
+        Self {
+            formula: Box::new(formula),
+            normalization: NormalizationProfile::default(),
+            defensible_rules: DefensibleRuleTable::default(),
+            thresholds: RiskThresholds::default(),
+        }
+    }
 
-        // Weighted sum
-        let pi = self.weights.alpha * vi_norm
-            + self.weights.beta * gi_norm
-            + self.weights.gamma * si_norm;
+    /// Calculator using a built-in formula kind
+    pub fn from_kind(kind: RiskFormulaKind, weights: RiskWeights) -> Self {
+        Self {
+            formula: kind.build(weights),
+            normalization: NormalizationProfile::default(),
+            defensible_rules: DefensibleRuleTable::default(),
+            thresholds: RiskThresholds::default(),
+        }
+    }
 
-        pi.min(1.0)  // Clamp to [0, 1]
+    /// Replace the normalization profile (defaults to Sonoran references)
+    pub fn with_normalization(mut self, normalization: NormalizationProfile) -> Self {
+        self.normalization = normalization;
+        self
     }
 
-    /// Defensible zone recommendation (meters) based on risk
-    pub fn defensible_zone(&self, risk_index: f64) -> (u32, u32, u32) {
-        match risk_index {
-            r if r < 0.33 => (0, 100, 20),    // Low: 0-1m (0cm), 1-10m (10cm), 10m+ (20cm)
-            r if r < 0.67 => (100, 200, 20),  // Medium: 0-10m (10cm), 10m+ (20cm)
-            _ => (200, 300, 30),              // High: full 0-30m defensible space
-        }
+    pub fn normalization(&self) -> &NormalizationProfile {
+        &self.normalization
+    }
+
+    /// Replace the defensible-space rule table (defaults to county ordinance)
+    pub fn with_defensible_rules(mut self, defensible_rules: DefensibleRuleTable) -> Self {
+        self.defensible_rules = defensible_rules;
+        self
+    }
+
+    /// Replace the risk zone thresholds
+    pub fn with_thresholds(mut self, thresholds: RiskThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Risk zone of an index under this calculator's thresholds
+    pub fn classify(&self, risk_index: f64) -> RiskZone {
+        RiskZone::classify(risk_index, &self.thresholds)
+    }
+
+    /// Normalize raw inputs to [0, 1] using the active profile
+    pub fn normalize(&self, vi: f64, gi: f64, si: f64) -> RiskTerms {
+        self.normalization.apply(vi, gi, si)
+    }
+
+    /// Compute risk index from already-normalized terms
+    pub fn compute_from_terms(&self, terms: &RiskTerms) -> f64 {
+        self.formula.combine(terms).clamp(0.0, 1.0)
+    }
+
+    /// Compute risk index for a parcel (0.0 = safe, 1.0 = critical)
+    pub fn compute_risk(&self, vi: f64, gi: f64, si: f64) -> f64 {
+        self.compute_from_terms(&self.normalize(vi, gi, si))
+    }
+
+    /// Defensible-space ring plan for a parcel based on risk
+    pub fn defensible_zone(&self, risk_index: f64) -> DefensibleZonePlan {
+        self.defensible_rules.plan(risk_index, &self.thresholds)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_abs_diff_eq;
 
     #[test]
     fn test_risk_calculation() {
@@ -70,10 +262,59 @@ mod tests {
         assert!(0.0 <= risk && risk <= 1.0);
     }
 
+    #[test]
+    fn test_additive_matches_math_doc() {
+        // 0.35·0.5 + 0.45·0.8 + 0.20·0.25 = 0.585
+        let calc = RiskCalculator::from_kind(RiskFormulaKind::Additive, RiskWeights::default());
+        assert_abs_diff_eq!(calc.compute_risk(500.0, 80.0, 15.0), 0.585, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_multiplicative_matches_readme() {
+        // 0.5·0.8·0.25 = 0.1
+        let calc = RiskCalculator::from_kind(RiskFormulaKind::Multiplicative, RiskWeights::default());
+        assert_abs_diff_eq!(calc.compute_risk(500.0, 80.0, 15.0), 0.1, epsilon = 1e-12);
+        assert_eq!(calc.compute_risk(500.0, 0.0, 15.0), 0.0);
+    }
+
+    #[test]
+    fn test_forms_agree_at_saturation() {
+        let additive = RiskCalculator::new(RiskWeights::default());
+        let product = RiskCalculator::with_formula(Product);
+        let terms = RiskTerms::new(1.0, 1.0, 1.0);
+        assert_abs_diff_eq!(additive.compute_from_terms(&terms), 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(product.compute_from_terms(&terms), 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_grass_cover_clamped() {
+        let calc = RiskCalculator::with_formula(Product);
+        assert_eq!(calc.normalize(500.0, 150.0, 15.0).gi, 1.0);
+        assert_eq!(calc.normalize(500.0, -10.0, 15.0).gi, 0.0);
+    }
+
+    #[test]
+    fn test_custom_formula() {
+        let calc = RiskCalculator::with_formula(CustomFormula::new(|t: &RiskTerms| t.gi.max(t.si)));
+        assert_abs_diff_eq!(calc.compute_risk(0.0, 40.0, 30.0), 0.5, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_risk_zone_classification() {
+        assert_eq!(RiskZone::from_index(0.2), RiskZone::Low);
+        assert_eq!(RiskZone::from_index(0.5), RiskZone::Medium);
+        assert_eq!(RiskZone::from_index(0.67), RiskZone::High);
+
+        let strict = RiskThresholds { low: 0.2, medium: 0.5 };
+        assert_eq!(RiskZone::classify(0.55, &strict), RiskZone::High);
+    }
+
     #[test]
     fn test_defensible_zone_low_risk() {
         let calc = RiskCalculator::new(RiskWeights::default());
-        let (inner, mid, outer) = calc.defensible_zone(0.2);
-        assert_eq!(inner, 0);
+        let plan = calc.defensible_zone(0.2);
+        assert_eq!(plan.risk_zone, RiskZone::Low);
+        assert_eq!(plan.rings[0].inner_radius_m, 0.0);
+        assert_eq!(plan.outer_radius_m(), 30.0);
     }
 }
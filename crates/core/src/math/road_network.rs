@@ -0,0 +1,461 @@
+//! Road and trail network graph for ground routing
+//! Directed edges with surface costs, vehicle restrictions and seasonal closures
+
+use crate::models::LatLon;
+use crate::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Road surface, scales edge length into travel cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SurfaceType {
+    Paved,
+    Gravel,
+    Dirt,
+    Trail,
+}
+
+impl SurfaceType {
+    /// Cost multiplier relative to paved road (always >= 1.0)
+    pub fn cost_factor(&self) -> f64 {
+        match self {
+            Self::Paved => 1.0,
+            Self::Gravel => 1.3,
+            Self::Dirt => 1.6,
+            Self::Trail => 2.5,
+        }
+    }
+
+    /// Parse a surface tag ("paved", "gravel", ...)
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag.trim().to_ascii_lowercase().as_str() {
+            "paved" | "asphalt" | "concrete" => Some(Self::Paved),
+            "gravel" => Some(Self::Gravel),
+            "dirt" | "unpaved" => Some(Self::Dirt),
+            "trail" | "path" | "track" => Some(Self::Trail),
+            _ => None,
+        }
+    }
+}
+
+/// Ground vehicle class for edge restrictions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VehicleClass {
+    Truck,  // Water tanker, flatbed
+    Atv,    // Quad / side-by-side
+    Foot,   // Crew on foot
+}
+
+impl VehicleClass {
+    /// Parse a vehicle tag ("truck", "atv", "foot")
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag.trim().to_ascii_lowercase().as_str() {
+            "truck" => Some(Self::Truck),
+            "atv" => Some(Self::Atv),
+            "foot" => Some(Self::Foot),
+            _ => None,
+        }
+    }
+}
+
+/// Closure window in day-of-year (1-366), wraps across year end when start > end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeasonalClosure {
+    pub start_day: u16,
+    pub end_day: u16,
+}
+
+impl SeasonalClosure {
+    pub fn new(start_day: u16, end_day: u16) -> Self {
+        Self { start_day, end_day }
+    }
+
+    /// Whether the closure is in force on a day of year
+    pub fn covers(&self, day_of_year: u16) -> bool {
+        if self.start_day <= self.end_day {
+            (self.start_day..=self.end_day).contains(&day_of_year)
+        } else {
+            day_of_year >= self.start_day || day_of_year <= self.end_day
+        }
+    }
+}
+
+/// Network node (junction or endpoint)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RoadNode {
+    pub node_id: u32,
+    pub location: LatLon,
+}
+
+/// Directed edge between two nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoadEdge {
+    pub from: u32,
+    pub to: u32,
+    pub length_m: f64,
+    pub surface: SurfaceType,
+    pub allowed_vehicles: Vec<VehicleClass>, // Empty = open to all
+    pub closures: Vec<SeasonalClosure>,
+    pub geometry: Vec<LatLon>,               // From -> to, empty = straight segment
+}
+
+impl RoadEdge {
+    pub fn new(from: u32, to: u32, length_m: f64, surface: SurfaceType) -> Self {
+        Self {
+            from,
+            to,
+            length_m,
+            surface,
+            allowed_vehicles: Vec::new(),
+            closures: Vec::new(),
+            geometry: Vec::new(),
+        }
+    }
+
+    /// Restrict the edge to the given vehicle classes
+    pub fn restricted_to(mut self, vehicles: &[VehicleClass]) -> Self {
+        self.allowed_vehicles = vehicles.to_vec();
+        self
+    }
+
+    pub fn with_closure(mut self, closure: SeasonalClosure) -> Self {
+        self.closures.push(closure);
+        self
+    }
+
+    pub fn with_geometry(mut self, geometry: Vec<LatLon>) -> Self {
+        self.geometry = geometry;
+        self
+    }
+
+    /// Travel cost = length × surface factor
+    pub fn cost(&self) -> f64 {
+        self.length_m * self.surface.cost_factor()
+    }
+
+    /// Whether the edge can be used under the given constraints
+    pub fn is_open(&self, constraints: &TravelConstraints) -> bool {
+        let vehicle_ok = match constraints.vehicle {
+            Some(v) => self.allowed_vehicles.is_empty() || self.allowed_vehicles.contains(&v),
+            None => true,
+        };
+        let season_ok = match constraints.day_of_year {
+            Some(day) => !self.closures.iter().any(|c| c.covers(day)),
+            None => true,
+        };
+        vehicle_ok && season_ok
+    }
+
+    /// Same edge in the opposite direction
+    pub fn reversed(&self) -> Self {
+        let mut edge = self.clone();
+        std::mem::swap(&mut edge.from, &mut edge.to);
+        edge.geometry.reverse();
+        edge
+    }
+}
+
+/// Vehicle and date to route for (None = unrestricted)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TravelConstraints {
+    pub vehicle: Option<VehicleClass>,
+    pub day_of_year: Option<u16>,
+}
+
+/// Path through the network
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkPath {
+    pub nodes: Vec<u32>,
+    pub geometry: Vec<LatLon>,
+    pub length_m: f64,
+    pub total_cost: f64,
+}
+
+/// Search frontier entry
+#[derive(Clone, Copy, PartialEq)]
+struct Frontier {
+    estimate: f64, // cost so far + heuristic
+    node_id: u32,
+}
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate) // Min-heap
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Directed road/trail graph
+#[derive(Debug, Clone, Default)]
+pub struct RoadNetwork {
+    nodes: HashMap<u32, RoadNode>,
+    adjacency: HashMap<u32, Vec<RoadEdge>>,
+}
+
+impl RoadNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, node_id: u32, location: LatLon) {
+        self.nodes.insert(node_id, RoadNode { node_id, location });
+    }
+
+    /// Add a directed edge; both endpoints must already exist
+    pub fn add_edge(&mut self, edge: RoadEdge) -> Result<()> {
+        for id in [edge.from, edge.to] {
+            if !self.nodes.contains_key(&id) {
+                return Err(CybersomethingError::DataValidationError {
+                    reason: format!("edge references unknown node {}", id),
+                });
+            }
+        }
+        if edge.length_m.is_nan() || edge.length_m < 0.0 {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("edge {} -> {} has invalid length {}", edge.from, edge.to, edge.length_m),
+            });
+        }
+        self.adjacency.entry(edge.from).or_default().push(edge);
+        Ok(())
+    }
+
+    /// Add an edge and its reverse
+    pub fn add_two_way(&mut self, edge: RoadEdge) -> Result<()> {
+        let reverse = edge.reversed();
+        self.add_edge(edge)?;
+        self.add_edge(reverse)
+    }
+
+    pub fn node(&self, node_id: u32) -> Option<&RoadNode> {
+        self.nodes.get(&node_id)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &RoadNode> {
+        self.nodes.values()
+    }
+
+    /// Outgoing edges of a node
+    pub fn edges_from(&self, node_id: u32) -> &[RoadEdge] {
+        self.adjacency.get(&node_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.values().map(Vec::len).sum()
+    }
+
+    /// Closest node to a location and its distance (m)
+    pub fn nearest_node(&self, location: &LatLon) -> Option<(u32, f64)> {
+        self.nodes
+            .values()
+            .map(|n| (n.node_id, n.location.distance_to(location)))
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+    }
+
+    /// Dijkstra's algorithm over edge costs
+    pub fn dijkstra(&self, start: u32, goal: u32, constraints: &TravelConstraints) -> Option<NetworkPath> {
+        self.search(start, goal, constraints, |_| 0.0)
+    }
+
+    /// A* with a straight-line heuristic.
+    /// Admissible as long as edge lengths are at least the endpoint separation.
+    pub fn astar(&self, start: u32, goal: u32, constraints: &TravelConstraints) -> Option<NetworkPath> {
+        let target = self.nodes.get(&goal)?.location;
+        self.search(start, goal, constraints, |node| node.location.distance_to(&target))
+    }
+
+    /// Cheapest cost from a start node to every reachable node (single-source Dijkstra)
+    pub fn costs_from(&self, start: u32, constraints: &TravelConstraints) -> HashMap<u32, f64> {
+        let mut best: HashMap<u32, f64> = HashMap::new();
+        if !self.nodes.contains_key(&start) {
+            return best;
+        }
+        let mut queue = BinaryHeap::new();
+        best.insert(start, 0.0);
+        queue.push(Frontier { estimate: 0.0, node_id: start });
+
+        while let Some(Frontier { estimate: cost, node_id }) = queue.pop() {
+            if cost > best[&node_id] + 1e-9 {
+                continue; // Stale entry
+            }
+            for edge in self.edges_from(node_id).iter().filter(|e| e.is_open(constraints)) {
+                let new_cost = cost + edge.cost();
+                if new_cost < best.get(&edge.to).copied().unwrap_or(f64::INFINITY) {
+                    best.insert(edge.to, new_cost);
+                    queue.push(Frontier { estimate: new_cost, node_id: edge.to });
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Many-to-many cost matrix, one Dijkstra per distinct source node.
+    /// `matrix[i][j]` is the cost from `nodes[i]` to `nodes[j]`, None if unreachable.
+    pub fn cost_matrix(&self, nodes: &[u32], constraints: &TravelConstraints) -> Vec<Vec<Option<f64>>> {
+        let mut reach: HashMap<u32, HashMap<u32, f64>> = HashMap::new();
+        nodes
+            .iter()
+            .map(|from| {
+                let costs = reach.entry(*from).or_insert_with(|| self.costs_from(*from, constraints));
+                nodes.iter().map(|to| costs.get(to).copied()).collect()
+            })
+            .collect()
+    }
+
+    fn search<H>(&self, start: u32, goal: u32, constraints: &TravelConstraints, heuristic: H) -> Option<NetworkPath>
+    where
+        H: Fn(&RoadNode) -> f64,
+    {
+        let start_node = self.nodes.get(&start)?;
+        self.nodes.get(&goal)?;
+
+        let mut best: HashMap<u32, f64> = HashMap::new();
+        let mut came_from: HashMap<u32, &RoadEdge> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+
+        best.insert(start, 0.0);
+        queue.push(Frontier { estimate: heuristic(start_node), node_id: start });
+
+        while let Some(Frontier { estimate, node_id }) = queue.pop() {
+            if node_id == goal {
+                return Some(self.build_path(start, goal, &came_from, best[&goal]));
+            }
+
+            let cost = best[&node_id];
+            if estimate > cost + heuristic(&self.nodes[&node_id]) + 1e-9 {
+                continue; // Stale entry
+            }
+
+            for edge in self.edges_from(node_id).iter().filter(|e| e.is_open(constraints)) {
+                let new_cost = cost + edge.cost();
+                if new_cost < best.get(&edge.to).copied().unwrap_or(f64::INFINITY) {
+                    best.insert(edge.to, new_cost);
+                    came_from.insert(edge.to, edge);
+                    queue.push(Frontier {
+                        estimate: new_cost + heuristic(&self.nodes[&edge.to]),
+                        node_id: edge.to,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    fn build_path(&self, start: u32, goal: u32, came_from: &HashMap<u32, &RoadEdge>, total_cost: f64) -> NetworkPath {
+        let mut edges = Vec::new();
+        let mut current = goal;
+        while current != start {
+            let edge = came_from[&current];
+            edges.push(edge);
+            current = edge.from;
+        }
+        edges.reverse();
+
+        let mut nodes = vec![start];
+        let mut geometry = vec![self.nodes[&start].location];
+        let mut length_m = 0.0;
+        for edge in edges {
+            nodes.push(edge.to);
+            length_m += edge.length_m;
+            // Interior vertices only; endpoints come from the nodes themselves
+            let interior = edge.geometry.len().saturating_sub(1);
+            geometry.extend(edge.geometry.iter().take(interior).skip(1).copied());
+            geometry.push(self.nodes[&edge.to].location);
+        }
+
+        NetworkPath { nodes, geometry, length_m, total_cost }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1 -> 2 -> 4 paved detour, 1 -> 3 -> 4 shorter dirt track
+    fn diamond() -> RoadNetwork {
+        let mut net = RoadNetwork::new();
+        net.add_node(1, LatLon::new(33.40, -112.00));
+        net.add_node(2, LatLon::new(33.41, -111.99));
+        net.add_node(3, LatLon::new(33.39, -111.99));
+        net.add_node(4, LatLon::new(33.40, -111.98));
+        net.add_two_way(RoadEdge::new(1, 2, 1500.0, SurfaceType::Paved)).unwrap();
+        net.add_two_way(RoadEdge::new(2, 4, 1500.0, SurfaceType::Paved)).unwrap();
+        net.add_two_way(RoadEdge::new(1, 3, 1400.0, SurfaceType::Dirt).restricted_to(&[VehicleClass::Atv])).unwrap();
+        net.add_two_way(RoadEdge::new(3, 4, 1400.0, SurfaceType::Dirt)).unwrap();
+        net
+    }
+
+    #[test]
+    fn test_surface_cost_prefers_paved() {
+        let net = diamond();
+        let path = net.dijkstra(1, 4, &TravelConstraints::default()).unwrap();
+        assert_eq!(path.nodes, vec![1, 2, 4]);
+        assert!((path.total_cost - 3000.0).abs() < 1e-9);
+        assert_eq!(path.geometry.len(), 3);
+    }
+
+    #[test]
+    fn test_vehicle_restriction_and_closure() {
+        let mut net = diamond();
+        net.add_two_way(
+            RoadEdge::new(2, 4, 100.0, SurfaceType::Paved).with_closure(SeasonalClosure::new(335, 60)),
+        )
+        .unwrap();
+
+        let summer = TravelConstraints { vehicle: Some(VehicleClass::Truck), day_of_year: Some(180) };
+        assert!((net.dijkstra(1, 4, &summer).unwrap().total_cost - 1600.0).abs() < 1e-9);
+
+        let winter = TravelConstraints { vehicle: Some(VehicleClass::Truck), day_of_year: Some(10) };
+        assert!((net.dijkstra(1, 4, &winter).unwrap().total_cost - 3000.0).abs() < 1e-9);
+
+        net.adjacency.get_mut(&1).unwrap().retain(|e| e.to != 2);
+        assert!(net.dijkstra(1, 4, &winter).is_none()); // Only the ATV track is left
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let net = diamond();
+        let atv = TravelConstraints { vehicle: Some(VehicleClass::Atv), day_of_year: None };
+        let d = net.dijkstra(1, 4, &atv).unwrap();
+        let a = net.astar(1, 4, &atv).unwrap();
+        assert_eq!(d.nodes, a.nodes);
+        assert!((d.total_cost - a.total_cost).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cost_matrix_matches_search() {
+        let net = diamond();
+        let nodes = [1, 2, 3, 4, 9];
+        let matrix = net.cost_matrix(&nodes, &TravelConstraints::default());
+        for (i, from) in nodes.iter().enumerate() {
+            for (j, to) in nodes.iter().enumerate() {
+                let expected = net.dijkstra(*from, *to, &TravelConstraints::default()).map(|p| p.total_cost);
+                assert_eq!(matrix[i][j].map(|c| c.round()), expected.map(|c| c.round()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_validation() {
+        let mut net = RoadNetwork::new();
+        net.add_node(1, LatLon::new(33.0, -112.0));
+        assert!(net.add_edge(RoadEdge::new(1, 9, 10.0, SurfaceType::Paved)).is_err());
+        assert!(SeasonalClosure::new(335, 60).covers(1));
+        assert!(!SeasonalClosure::new(335, 60).covers(180));
+    }
+}
@@ -1,5 +1,7 @@
 //! Routing algorithms for swarm agents and drones
 
+use super::road_network::{NetworkPath, RoadNetwork, TravelConstraints};
+use crate::models::LatLon;
 use std::collections::{BinaryHeap, HashMap};
 use std::cmp::Ordering;
 
@@ -33,25 +35,79 @@ impl PartialOrd for RouteNode {
     }
 }
 
+/// Zone-to-zone costs (m) for one planning query; u32::MAX = unreachable
+struct ZoneDistances {
+    index: HashMap<u32, usize>,
+    costs: Vec<Vec<u32>>,
+}
+
+impl ZoneDistances {
+    fn get(&self, from_zone: u32, to_zone: u32) -> u32 {
+        match (self.index.get(&from_zone), self.index.get(&to_zone)) {
+            (Some(&i), Some(&j)) => self.costs[i][j],
+            _ => u32::MAX,
+        }
+    }
+}
+
 /// Multi-agent routing solver
 pub struct RoutePlanner {
     zones: HashMap<u32, (f64, f64)>, // zone_id -> (lat, lon)
+    network: Option<RoadNetwork>,    // None = straight-line complete graph
+    constraints: TravelConstraints,
 }
 
 impl RoutePlanner {
     pub fn new() -> Self {
         Self {
             zones: HashMap::new(),
+            network: None,
+            constraints: TravelConstraints::default(),
         }
     }
 
+    /// Route over a road/trail network instead of straight lines
+    pub fn with_network(mut self, network: RoadNetwork, constraints: TravelConstraints) -> Self {
+        self.network = Some(network);
+        self.constraints = constraints;
+        self
+    }
+
+    pub fn network(&self) -> Option<&RoadNetwork> {
+        self.network.as_ref()
+    }
+
     /// Register a zone location
     pub fn register_zone(&mut self, zone_id: u32, latitude: f64, longitude: f64) {
         self.zones.insert(zone_id, (latitude, longitude));
     }
 
-    /// Haversine distance between two zones in meters
-    fn zone_distance(&self, from_zone: u32, to_zone: u32) -> u32 {
+    /// Network path between two zones, each snapped to its nearest node
+    pub fn network_path(&self, from_zone: u32, to_zone: u32) -> Option<NetworkPath> {
+        let network = self.network.as_ref()?;
+        let snap = |zone_id: u32| {
+            let (lat, lon) = self.zones.get(&zone_id).copied()?;
+            network.nearest_node(&LatLon { latitude: lat, longitude: lon }).map(|(id, _)| id)
+        };
+        network.astar(snap(from_zone)?, snap(to_zone)?, &self.constraints)
+    }
+
+    /// Zone-to-zone cost in meters: network cost when a network is set, else haversine.
+    /// Unreachable pairs cost u32::MAX.
+    pub fn zone_distance(&self, from_zone: u32, to_zone: u32) -> u32 {
+        if self.network.is_some() {
+            if from_zone == to_zone {
+                return 0;
+            }
+            return self
+                .network_path(from_zone, to_zone)
+                .map_or(u32::MAX, |p| p.total_cost.round() as u32);
+        }
+        self.straight_line_distance(from_zone, to_zone)
+    }
+
+    /// Haversine distance in meters between two registered zones
+    fn straight_line_distance(&self, from_zone: u32, to_zone: u32) -> u32 {
         let (lat1, lon1) = self.zones.get(&from_zone).copied().unwrap_or((0.0, 0.0));
         let (lat2, lon2) = self.zones.get(&to_zone).copied().unwrap_or((0.0, 0.0));
 
@@ -67,8 +123,65 @@ impl RoutePlanner {
         (R * c) as u32
     }
 
+    /// Costs between every pair of the given zones, computed once per query.
+    /// On a network this is one Dijkstra per snapped node instead of an A* per pair.
+    fn distance_matrix(&self, zone_ids: &[u32]) -> ZoneDistances {
+        let mut index: HashMap<u32, usize> = HashMap::new();
+        let mut ids = Vec::new();
+        for zone_id in zone_ids {
+            index.entry(*zone_id).or_insert_with(|| {
+                ids.push(*zone_id);
+                ids.len() - 1
+            });
+        }
+
+        let costs = match &self.network {
+            Some(network) => {
+                let snapped: Vec<Option<u32>> = ids
+                    .iter()
+                    .map(|zone_id| {
+                        let (lat, lon) = self.zones.get(zone_id).copied()?;
+                        network.nearest_node(&LatLon { latitude: lat, longitude: lon }).map(|(id, _)| id)
+                    })
+                    .collect();
+                let nodes: Vec<u32> = snapped.iter().flatten().copied().collect();
+                let matrix = network.cost_matrix(&nodes, &self.constraints);
+                let mut row_of = Vec::with_capacity(ids.len()); // Matrix row of each zone
+                let mut next_row = 0;
+                for node in &snapped {
+                    row_of.push(node.map(|_| {
+                        next_row += 1;
+                        next_row - 1
+                    }));
+                }
+
+                (0..ids.len())
+                    .map(|i| {
+                        (0..ids.len())
+                            .map(|j| match (row_of[i], row_of[j]) {
+                                _ if i == j => 0,
+                                (Some(r), Some(c)) => matrix[r][c].map_or(u32::MAX, |cost| cost.round() as u32),
+                                _ => u32::MAX,
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+            None => ids
+                .iter()
+                .map(|from| ids.iter().map(|to| self.straight_line_distance(*from, *to)).collect())
+                .collect(),
+        };
+
+        ZoneDistances { index, costs }
+    }
+
     /// Dijkstra's algorithm: shortest path from start to end
     pub fn shortest_path(&self, start_zone: u32, end_zone: u32) -> Option<Vec<u32>> {
+        let mut zone_ids: Vec<u32> = self.zones.keys().copied().collect();
+        zone_ids.extend([start_zone, end_zone]);
+        let distances_m = self.distance_matrix(&zone_ids);
+
         let mut visited = std::collections::HashSet::new();
         let mut distances: HashMap<u32, u32> = HashMap::new();
         let mut predecessors: HashMap<u32, u32> = HashMap::new();
@@ -93,8 +206,11 @@ impl RoutePlanner {
 
             for neighbor_id in self.zones.keys() {
                 if !visited.contains(neighbor_id) {
-                    let edge_cost = self.zone_distance(zone_id, *neighbor_id);
-                    let new_cost = cost_m + edge_cost;
+                    let edge_cost = distances_m.get(zone_id, *neighbor_id);
+                    if edge_cost == u32::MAX {
+                        continue;
+                    }
+                    let new_cost = cost_m.saturating_add(edge_cost);
                     let best_known = distances.get(neighbor_id).copied().unwrap_or(u32::MAX);
 
                     if new_cost < best_known {
@@ -117,6 +233,10 @@ impl RoutePlanner {
 
     /// Traveling Salesman Problem approximation (nearest neighbor heuristic)
     pub fn tsp_greedy(&self, start_zone: u32, zones_to_visit: &[u32]) -> Vec<u32> {
+        let mut zone_ids = vec![start_zone];
+        zone_ids.extend_from_slice(zones_to_visit);
+        let distances_m = self.distance_matrix(&zone_ids);
+
         let mut route = vec![start_zone];
         let mut unvisited: std::collections::HashSet<_> = zones_to_visit.iter().copied().collect();
 
@@ -124,7 +244,7 @@ impl RoutePlanner {
         while !unvisited.is_empty() {
             let next = *unvisited
                 .iter()
-                .min_by_key(|z| self.zone_distance(current, **z))
+                .min_by_key(|z| distances_m.get(current, **z))
                 .unwrap();
 
             route.push(next);
@@ -137,9 +257,10 @@ impl RoutePlanner {
 
     /// Calculate total route distance
     pub fn route_distance(&self, route: &[u32]) -> u32 {
+        let distances_m = self.distance_matrix(route);
         let mut total = 0u32;
         for window in route.windows(2) {
-            total += self.zone_distance(window[0], window[1]);
+            total = total.saturating_add(distances_m.get(window[0], window[1]));
         }
         total
     }
@@ -193,4 +314,45 @@ mod tests {
         let dist = planner.route_distance(&route);
         assert!(dist > 0);
     }
+
+    #[test]
+    fn test_network_routing() {
+        use crate::math::road_network::{RoadEdge, SurfaceType};
+
+        // Zones 1 and 3 are only linked through the junction at zone 2
+        let mut network = RoadNetwork::new();
+        network.add_node(10, LatLon::new(33.00, -112.00));
+        network.add_node(20, LatLon::new(33.05, -111.95));
+        network.add_node(30, LatLon::new(33.00, -111.90));
+        network.add_two_way(RoadEdge::new(10, 20, 7000.0, SurfaceType::Paved)).unwrap();
+        network.add_two_way(RoadEdge::new(20, 30, 7000.0, SurfaceType::Paved)).unwrap();
+
+        let mut planner = RoutePlanner::new().with_network(network, TravelConstraints::default());
+        planner.register_zone(1, 33.00, -112.00);
+        planner.register_zone(2, 33.05, -111.95);
+        planner.register_zone(3, 33.00, -111.90);
+
+        assert_eq!(planner.zone_distance(1, 3), 14000);
+        let path = planner.network_path(1, 3).unwrap();
+        assert_eq!(path.nodes, vec![10, 20, 30]);
+        assert_eq!(planner.route_distance(&[1, 3, 1]), 28000);
+        assert_eq!(planner.tsp_greedy(1, &[3, 2]), vec![1, 2, 3]);
+        assert_eq!(planner.shortest_path(1, 3).unwrap().first(), Some(&1));
+    }
+
+    #[test]
+    fn test_distance_matrix_matches_pairwise() {
+        let mut planner = RoutePlanner::new();
+        for i in 1..=4 {
+            planner.register_zone(i, 33.0 + i as f64 * 0.05, -112.0 + i as f64 * 0.02);
+        }
+        let zones = [1, 2, 3, 4, 2];
+        let matrix = planner.distance_matrix(&zones);
+        for from in zones {
+            for to in zones {
+                assert_eq!(matrix.get(from, to), planner.zone_distance(from, to));
+            }
+        }
+        assert_eq!(matrix.get(1, 99), u32::MAX);
+    }
 }
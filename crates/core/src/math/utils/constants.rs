@@ -6,6 +6,9 @@ pub mod sonoran {
     pub const PHOENIX_LAT: f64 = 33.4484;
     pub const PHOENIX_LON: f64 = -112.0742;
 
+    /// Arizona standard time offset from UTC (hours, no daylight saving)
+    pub const UTC_OFFSET_HOURS: f64 = -7.0;
+
     /// Typical elevation (feet to meters)
     pub const PHOENIX_ELEVATION_M: f64 = 345.0;
 
@@ -15,6 +18,9 @@ pub mod sonoran {
     /// Growing season length (days)
     pub const GROWING_SEASON_DAYS: u32 = 180;
 
+    /// Arid foresummer dry season length (weeks, April–June)
+    pub const DRY_SEASON_WEEKS: u32 = 12;
+
     /// Peak summer temperature (°C)
     pub const PEAK_TEMP_C: f64 = 45.0;
 
@@ -59,6 +65,9 @@ pub mod energy {
     /// Drone battery capacity (Wh)
     pub const DRONE_BATTERY_WH: f64 = 500.0;
 
+    /// Drone state-of-charge floor for flight (%)
+    pub const DRONE_MIN_SOC_PERCENT: f64 = 20.0;
+
     /// Nanobot battery capacity (mJ)
     pub const NANOBOT_BATTERY_MJ: f64 = 100.0;
 
@@ -73,6 +82,24 @@ pub mod energy {
 
     /// RF harvesting efficiency (%)
     pub const RF_EFFICIENCY_PERCENT: f64 = 8.0;
+
+    /// Drone flight energy (J/m)
+    pub const DRONE_FLIGHT_J_PER_M: f64 = 0.5;
+
+    /// Drone payload delivery energy (J/drop)
+    pub const DRONE_PAYLOAD_J: f64 = 1000.0;
+
+    /// Drone airborne overhead (J/min)
+    pub const DRONE_AIRBORNE_J_PER_MIN: f64 = 500.0;
+
+    /// Nanobot soil traversal energy (J/m)
+    pub const NANOBOT_TRAVEL_J_PER_M: f64 = 0.1;
+
+    /// Nanobot nutrient injection energy (J/injection)
+    pub const NANOBOT_INJECTION_J: f64 = 50.0;
+
+    /// Nanobot recharge interval (hours, solar/RF harvesting)
+    pub const NANOBOT_RECHARGE_INTERVAL_H: f64 = 4.0;
 }
 
 /// Ecological thresholds
@@ -99,6 +126,21 @@ pub mod ecology {
     pub const CRITICAL_WATER_DEFICIT_MM: f64 = 50.0;
 }
 
+/// Nanoswarm safety envelope (hardware-only deployments)
+pub mod nanoswarm {
+    /// Maximum deployed mass per hectare (grams)
+    pub const MAX_MASS_G_PER_HA: f64 = 50.0;
+
+    /// Mission kill-switch (hours)
+    pub const MAX_MISSION_HOURS: f64 = 72.0;
+
+    /// Maximum duty cycle (fraction of time active)
+    pub const MAX_DUTY_CYCLE: f64 = 0.5;
+
+    /// Mass of a single nanobot (grams)
+    pub const NANOBOT_MASS_G: f64 = 0.01;
+}
+
 /// Time constants
 pub mod time {
     /// Simulation timestep (seconds)
@@ -0,0 +1,560 @@
+//! Capacitated vehicle routing with time windows (CVRPTW)
+//! Assigns stops to crews/drones from depots: cheapest-insertion construction
+//! followed by 2-opt, or-opt and relocate local search. Seeded for repeatability.
+
+use crate::models::{Drone, LatLon};
+use crate::utils::errors::{CybersomethingError, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+const EPSILON: f64 = 1e-6;
+
+/// Vehicle base
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Depot {
+    pub depot_id: u32,
+    pub location: LatLon,
+}
+
+/// Crew truck or drone available for routing
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Vehicle {
+    pub vehicle_id: u64,
+    pub depot_id: u32,
+    pub capacity: f64,               // Same unit as stop demand (kg or liters)
+    pub max_range_m: f64,            // Depot -> stops -> depot
+    pub speed_mps: f64,
+    pub max_duration_s: Option<f64>, // Must be back at the depot by then
+}
+
+impl Vehicle {
+    pub fn new(vehicle_id: u64, depot_id: u32, capacity: f64, max_range_m: f64, speed_mps: f64) -> Self {
+        Self {
+            vehicle_id,
+            depot_id,
+            capacity,
+            max_range_m,
+            speed_mps,
+            max_duration_s: None,
+        }
+    }
+
+    /// Drone limits: payload (kg), battery-limited range and cruise speed
+    pub fn from_drone(drone: &Drone, depot_id: u32) -> Self {
+        Self::new(
+            drone.id,
+            depot_id,
+            drone.drone_type.payload_capacity_kg(),
+            drone.available_range_m(),
+            drone.drone_type.cruise_speed_mps(),
+        )
+    }
+
+    pub fn with_max_duration(mut self, seconds: f64) -> Self {
+        self.max_duration_s = Some(seconds);
+        self
+    }
+}
+
+/// Service window in seconds from dispatch
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub earliest_s: f64,
+    pub latest_s: f64, // Latest service start
+}
+
+impl TimeWindow {
+    pub fn new(earliest_s: f64, latest_s: f64) -> Self {
+        Self { earliest_s, latest_s }
+    }
+
+    /// Deadline-only window [0, deadline]
+    pub fn deadline(deadline_s: f64) -> Self {
+        Self::new(0.0, deadline_s)
+    }
+}
+
+/// Location to serve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stop {
+    pub stop_id: u32,
+    pub location: LatLon,
+    pub demand: f64,
+    pub service_s: f64,
+    pub window: Option<TimeWindow>,
+}
+
+impl Stop {
+    pub fn new(stop_id: u32, location: LatLon, demand: f64, service_s: f64) -> Self {
+        Self {
+            stop_id,
+            location,
+            demand,
+            service_s,
+            window: None,
+        }
+    }
+
+    pub fn with_window(mut self, window: TimeWindow) -> Self {
+        self.window = Some(window);
+        self
+    }
+}
+
+/// Depots, fleet and stops
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VrpProblem {
+    pub depots: Vec<Depot>,
+    pub vehicles: Vec<Vehicle>,
+    pub stops: Vec<Stop>,
+}
+
+impl VrpProblem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_depot(&mut self, depot_id: u32, location: LatLon) {
+        self.depots.push(Depot { depot_id, location });
+    }
+
+    pub fn add_vehicle(&mut self, vehicle: Vehicle) {
+        self.vehicles.push(vehicle);
+    }
+
+    pub fn add_stop(&mut self, stop: Stop) {
+        self.stops.push(stop);
+    }
+
+    /// Check ids, depot references and windows
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(CybersomethingError::DataValidationError { reason });
+        let mut depots = HashSet::new();
+        for d in &self.depots {
+            if !depots.insert(d.depot_id) {
+                return invalid(format!("duplicate depot id {}", d.depot_id));
+            }
+        }
+
+        for v in &self.vehicles {
+            if !depots.contains(&v.depot_id) {
+                return invalid(format!("vehicle {} references unknown depot {}", v.vehicle_id, v.depot_id));
+            }
+            if v.speed_mps.is_nan() || v.speed_mps <= 0.0 {
+                return invalid(format!("vehicle {} has non-positive speed", v.vehicle_id));
+            }
+            if v.capacity.is_nan() || v.capacity < 0.0 || v.max_range_m.is_nan() || v.max_range_m < 0.0 {
+                return invalid(format!("vehicle {} has NaN or negative capacity or range", v.vehicle_id));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for s in &self.stops {
+            if !seen.insert(s.stop_id) {
+                return invalid(format!("duplicate stop id {}", s.stop_id));
+            }
+            if s.demand.is_nan() || s.demand < 0.0 || s.service_s.is_nan() || s.service_s < 0.0 {
+                return invalid(format!("stop {} has NaN or negative demand or service time", s.stop_id));
+            }
+            if let Some(w) = s.window {
+                if w.earliest_s > w.latest_s {
+                    return invalid(format!("stop {} window closes before it opens", s.stop_id));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One vehicle's tour
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VehicleRoute {
+    pub vehicle_id: u64,
+    pub depot_id: u32,
+    pub stop_ids: Vec<u32>,
+    pub arrival_s: Vec<f64>, // Per stop, before any waiting
+    pub distance_m: f64,
+    pub load: f64,
+    pub return_s: f64,
+}
+
+/// Solver output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingSolution {
+    pub routes: Vec<VehicleRoute>,
+    pub unassigned: Vec<u32>, // Stops no vehicle can serve feasibly
+    pub total_distance_m: f64,
+}
+
+/// Route feasibility summary
+struct RouteEval {
+    distance_m: f64,
+    load: f64,
+    arrival_s: Vec<f64>,
+    return_s: f64,
+}
+
+/// Precomputed problem view; node index = depot index, then depots.len() + stop index
+struct Context<'a> {
+    problem: &'a VrpProblem,
+    vehicle_depot: Vec<usize>,
+    dist: Vec<Vec<f64>>,
+}
+
+impl<'a> Context<'a> {
+    fn new(problem: &'a VrpProblem) -> Self {
+        let points: Vec<LatLon> = problem
+            .depots
+            .iter()
+            .map(|d| d.location)
+            .chain(problem.stops.iter().map(|s| s.location))
+            .collect();
+        let dist = points
+            .iter()
+            .map(|a| points.iter().map(|b| a.distance_to(b)).collect())
+            .collect();
+        let depot_index: HashMap<u32, usize> =
+            problem.depots.iter().enumerate().map(|(i, d)| (d.depot_id, i)).collect();
+        let vehicle_depot = problem.vehicles.iter().map(|v| depot_index[&v.depot_id]).collect();
+
+        Self { problem, vehicle_depot, dist }
+    }
+
+    fn stop_node(&self, stop: usize) -> usize {
+        self.problem.depots.len() + stop
+    }
+
+    /// Feasible route metrics, or None if any limit is broken
+    fn evaluate(&self, vehicle: usize, route: &[usize]) -> Option<RouteEval> {
+        let v = &self.problem.vehicles[vehicle];
+        let depot = self.vehicle_depot[vehicle];
+
+        let load: f64 = route.iter().map(|&s| self.problem.stops[s].demand).sum();
+        if load > v.capacity + EPSILON {
+            return None;
+        }
+
+        let mut at = depot;
+        let mut time = 0.0;
+        let mut distance_m = 0.0;
+        let mut arrival_s = Vec::with_capacity(route.len());
+        for &s in route {
+            let node = self.stop_node(s);
+            let stop = &self.problem.stops[s];
+            distance_m += self.dist[at][node];
+            time += self.dist[at][node] / v.speed_mps;
+            arrival_s.push(time);
+            if let Some(w) = stop.window {
+                if time > w.latest_s + EPSILON {
+                    return None;
+                }
+                time = time.max(w.earliest_s);
+            }
+            time += stop.service_s;
+            at = node;
+        }
+        distance_m += self.dist[at][depot];
+        time += self.dist[at][depot] / v.speed_mps;
+
+        if distance_m > v.max_range_m + EPSILON || v.max_duration_s.is_some_and(|d| time > d + EPSILON) {
+            return None;
+        }
+        Some(RouteEval { distance_m, load, arrival_s, return_s: time })
+    }
+
+    fn cost(&self, vehicle: usize, route: &[usize]) -> Option<f64> {
+        self.evaluate(vehicle, route).map(|e| e.distance_m)
+    }
+}
+
+/// CVRPTW solver
+#[derive(Debug, Clone, Copy)]
+pub struct VrpSolver {
+    pub seed: u64,
+    pub max_passes: usize, // Local-search passes over all operators
+}
+
+impl Default for VrpSolver {
+    fn default() -> Self {
+        Self { seed: 42, max_passes: 50 }
+    }
+}
+
+impl VrpSolver {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, ..Self::default() }
+    }
+
+    pub fn with_max_passes(mut self, max_passes: usize) -> Self {
+        self.max_passes = max_passes;
+        self
+    }
+
+    /// Solve: cheapest insertion, then local search until no move improves distance
+    pub fn solve(&self, problem: &VrpProblem) -> Result<RoutingSolution> {
+        problem.validate()?;
+        let ctx = Context::new(problem);
+        let mut routes: Vec<Vec<usize>> = vec![Vec::new(); problem.vehicles.len()];
+        let mut unassigned: Vec<usize> = (0..problem.stops.len()).collect();
+
+        Self::insert_cheapest(&ctx, &mut routes, &mut unassigned);
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        for _ in 0..self.max_passes {
+            let mut order: Vec<usize> = (0..routes.len()).collect();
+            order.shuffle(&mut rng);
+
+            let mut improved = false;
+            for &v in &order {
+                improved |= Self::two_opt(&ctx, v, &mut routes[v]);
+                improved |= Self::or_opt(&ctx, v, &mut routes[v]);
+            }
+            improved |= Self::relocate(&ctx, &order, &mut routes);
+            if !unassigned.is_empty() {
+                let before = unassigned.len();
+                Self::insert_cheapest(&ctx, &mut routes, &mut unassigned);
+                improved |= unassigned.len() < before;
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        let routes: Vec<VehicleRoute> = routes
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| !r.is_empty())
+            .map(|(v, r)| {
+                let eval = ctx.evaluate(v, r).expect("local search keeps routes feasible");
+                VehicleRoute {
+                    vehicle_id: problem.vehicles[v].vehicle_id,
+                    depot_id: problem.vehicles[v].depot_id,
+                    stop_ids: r.iter().map(|&s| problem.stops[s].stop_id).collect(),
+                    arrival_s: eval.arrival_s,
+                    distance_m: eval.distance_m,
+                    load: eval.load,
+                    return_s: eval.return_s,
+                }
+            })
+            .collect();
+
+        Ok(RoutingSolution {
+            total_distance_m: routes.iter().map(|r| r.distance_m).sum(),
+            routes,
+            unassigned: unassigned.iter().map(|&s| problem.stops[s].stop_id).collect(),
+        })
+    }
+
+    /// Repeatedly insert the stop with the globally cheapest feasible insertion
+    fn insert_cheapest(ctx: &Context, routes: &mut [Vec<usize>], unassigned: &mut Vec<usize>) {
+        loop {
+            let mut best: Option<(f64, usize, usize, usize)> = None; // (added m, unassigned idx, vehicle, pos)
+            for (ui, &s) in unassigned.iter().enumerate() {
+                for (v, route) in routes.iter().enumerate() {
+                    let Some(base) = ctx.cost(v, route) else { continue };
+                    for pos in 0..=route.len() {
+                        let mut candidate = route.clone();
+                        candidate.insert(pos, s);
+                        if let Some(cost) = ctx.cost(v, &candidate) {
+                            if best.is_none_or(|b| cost - base < b.0) {
+                                best = Some((cost - base, ui, v, pos));
+                            }
+                        }
+                    }
+                }
+            }
+
+            let Some((_, ui, v, pos)) = best else { return };
+            let s = unassigned.remove(ui);
+            routes[v].insert(pos, s);
+        }
+    }
+
+    /// Reverse route segments while that shortens the route
+    fn two_opt(ctx: &Context, v: usize, route: &mut Vec<usize>) -> bool {
+        let mut improved = false;
+        let Some(mut current) = ctx.cost(v, route) else { return false };
+        for i in 0..route.len() {
+            for j in i + 1..route.len() {
+                let mut candidate = route.clone();
+                candidate[i..=j].reverse();
+                if let Some(cost) = ctx.cost(v, &candidate) {
+                    if cost < current - EPSILON {
+                        *route = candidate;
+                        current = cost;
+                        improved = true;
+                    }
+                }
+            }
+        }
+        improved
+    }
+
+    /// Move chains of 1-3 consecutive stops elsewhere in the same route
+    fn or_opt(ctx: &Context, v: usize, route: &mut Vec<usize>) -> bool {
+        let mut improved = false;
+        let Some(mut current) = ctx.cost(v, route) else { return false };
+        for len in 1..=3 {
+            let mut i = 0;
+            while i + len <= route.len() {
+                let mut rest = route.clone();
+                let chain: Vec<usize> = rest.drain(i..i + len).collect();
+                let mut moved = false;
+                for pos in 0..=rest.len() {
+                    if pos == i {
+                        continue;
+                    }
+                    let mut candidate = rest.clone();
+                    candidate.splice(pos..pos, chain.iter().copied());
+                    if let Some(cost) = ctx.cost(v, &candidate) {
+                        if cost < current - EPSILON {
+                            *route = candidate;
+                            current = cost;
+                            improved = true;
+                            moved = true;
+                            break;
+                        }
+                    }
+                }
+                if !moved {
+                    i += 1;
+                }
+            }
+        }
+        improved
+    }
+
+    /// Move single stops between vehicles when total distance drops
+    fn relocate(ctx: &Context, order: &[usize], routes: &mut [Vec<usize>]) -> bool {
+        let mut improved = false;
+        for &a in order {
+            let mut i = 0;
+            while i < routes[a].len() {
+                let mut moved = false;
+                for &b in order.iter().filter(|&&b| b != a) {
+                    let (Some(cost_a), Some(cost_b)) = (ctx.cost(a, &routes[a]), ctx.cost(b, &routes[b])) else {
+                        continue;
+                    };
+                    let mut from = routes[a].clone();
+                    let s = from.remove(i);
+                    let Some(new_a) = ctx.cost(a, &from) else { continue };
+
+                    for pos in 0..=routes[b].len() {
+                        let mut to = routes[b].clone();
+                        to.insert(pos, s);
+                        if let Some(new_b) = ctx.cost(b, &to) {
+                            if new_a + new_b < cost_a + cost_b - EPSILON {
+                                routes[a] = from;
+                                routes[b] = to;
+                                improved = true;
+                                moved = true;
+                                break;
+                            }
+                        }
+                    }
+                    if moved {
+                        break;
+                    }
+                }
+                if !moved {
+                    i += 1;
+                }
+            }
+        }
+        improved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depot() -> LatLon {
+        LatLon::new(33.40, -112.00)
+    }
+
+    /// Stops on a 0.01° grid east of the depot, 100 L each
+    fn problem(vehicles: usize, capacity: f64) -> VrpProblem {
+        let mut p = VrpProblem::new();
+        p.add_depot(1, depot());
+        for v in 0..vehicles {
+            p.add_vehicle(Vehicle::new(v as u64 + 1, 1, capacity, 50_000.0, 10.0));
+        }
+        for i in 0..6 {
+            let lat = 33.40 + (i % 2) as f64 * 0.01;
+            let lon = -112.00 + (i / 2 + 1) as f64 * 0.01;
+            p.add_stop(Stop::new(i as u32 + 1, LatLon::new(lat, lon), 100.0, 300.0));
+        }
+        p
+    }
+
+    #[test]
+    fn test_all_stops_served_within_capacity() {
+        let p = problem(2, 300.0);
+        let solution = VrpSolver::default().solve(&p).unwrap();
+        assert!(solution.unassigned.is_empty());
+        assert_eq!(solution.routes.len(), 2);
+        assert!(solution.routes.iter().all(|r| r.load <= 300.0));
+        let served: usize = solution.routes.iter().map(|r| r.stop_ids.len()).sum();
+        assert_eq!(served, 6);
+    }
+
+    #[test]
+    fn test_capacity_shortfall_leaves_unassigned() {
+        let p = problem(1, 250.0);
+        let solution = VrpSolver::default().solve(&p).unwrap();
+        assert_eq!(solution.routes[0].stop_ids.len(), 2);
+        assert_eq!(solution.unassigned.len(), 4);
+    }
+
+    #[test]
+    fn test_time_window_orders_stops() {
+        let mut p = problem(1, 1000.0);
+        // Farthest stop must be served first
+        p.stops[5].window = Some(TimeWindow::deadline(500.0));
+        let solution = VrpSolver::default().solve(&p).unwrap();
+        let route = &solution.routes[0];
+        assert_eq!(route.stop_ids[0], 6);
+        assert!(route.arrival_s[0] <= 500.0);
+    }
+
+    #[test]
+    fn test_deterministic_for_seed() {
+        let p = problem(3, 200.0);
+        let a = VrpSolver::new(7).solve(&p).unwrap();
+        let b = VrpSolver::new(7).solve(&p).unwrap();
+        assert_eq!(a.total_distance_m, b.total_distance_m);
+        assert_eq!(
+            a.routes.iter().map(|r| r.stop_ids.clone()).collect::<Vec<_>>(),
+            b.routes.iter().map(|r| r.stop_ids.clone()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_drone_vehicle_and_validation() {
+        let drone = Drone::new(9, crate::models::DroneType::Quadcopter);
+        let vehicle = Vehicle::from_drone(&drone, 1);
+        assert_eq!(vehicle.capacity, 2.5);
+        assert!(vehicle.max_range_m > 0.0);
+
+        let mut p = VrpProblem::new();
+        p.add_vehicle(vehicle);
+        assert!(p.validate().is_err()); // Unknown depot
+    }
+
+    #[test]
+    fn test_rejects_bad_fleet_limits_and_duplicate_depots() {
+        for (capacity, range) in [(f64::NAN, 50_000.0), (-1.0, 50_000.0), (300.0, f64::NAN), (300.0, -5.0)] {
+            let mut p = problem(1, capacity);
+            p.vehicles[0].max_range_m = range;
+            assert!(p.validate().is_err(), "capacity {capacity}, range {range}");
+        }
+
+        let mut p = problem(1, 300.0);
+        p.add_depot(1, LatLon::new(33.5, -112.1));
+        assert!(p.validate().is_err());
+    }
+}
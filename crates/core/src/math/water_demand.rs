@@ -0,0 +1,211 @@
+//! Water demand block for eco-recovery plantings
+//! V = N_t · W_w · T   (total liters for the dry season)
+//! R = T · ⌈N_t · W_w / C⌉ (tank trips; tanks are not pooled across weeks)
+
+use crate::math::hydrology::WaterBottle;
+use crate::models::ecology::TreeSpecies;
+use crate::models::jurisdiction::JurisdictionProfile;
+use crate::utils::constants::sonoran::DRY_SEASON_WEEKS;
+use crate::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Plantings in a zone needing supplemental water
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlantingZone {
+    pub zone_id: u32,
+    pub plantings: Vec<(TreeSpecies, u32)>, // (species, plant count)
+}
+
+impl PlantingZone {
+    pub fn new(zone_id: u32) -> Self {
+        Self {
+            zone_id,
+            plantings: Vec::new(),
+        }
+    }
+
+    pub fn add_plants(&mut self, species: TreeSpecies, count: u32) {
+        self.plantings.push((species, count));
+    }
+
+    /// N_t: total plants in the zone
+    pub fn plant_count(&self) -> u32 {
+        self.plantings.iter().map(|(_, n)| n).sum()
+    }
+}
+
+/// One week of deliveries to a zone
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyDelivery {
+    pub week: u32,     // 1-based week of the season
+    pub liters: f64,
+    pub trips: u32,    // ceil(liters / C), tanks are not pooled across weeks
+}
+
+/// Season water plan for one zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneWaterPlan {
+    pub zone_id: u32,
+    pub plant_count: u32,
+    pub weekly_liters: f64,   // Σ N·W_w
+    pub total_liters: f64,    // V
+    pub trips: u32,           // Σ weekly trips actually scheduled
+    pub schedule: Vec<WeeklyDelivery>,
+}
+
+/// Planner turning plant counts into liters, trips and weekly schedules
+#[derive(Debug, Clone)]
+pub struct WaterDemandPlanner {
+    pub season_weeks: u32,                          // T
+    rate_overrides: BTreeMap<TreeSpecies, f64>,     // W_w (L/plant/week)
+}
+
+impl WaterDemandPlanner {
+    pub fn new(season_weeks: u32) -> Self {
+        Self {
+            season_weeks,
+            rate_overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Planner using a jurisdiction's water-per-plant rates
+    pub fn from_profile(profile: &JurisdictionProfile, season_weeks: u32) -> Self {
+        Self {
+            season_weeks,
+            rate_overrides: profile.water_per_plant_l_week.clone(),
+        }
+    }
+
+    /// Override W_w for a species
+    pub fn with_rate(mut self, species: TreeSpecies, liters_per_week: f64) -> Self {
+        self.rate_overrides.insert(species, liters_per_week);
+        self
+    }
+
+    /// W_w for a species (override, or the species requirement spread over T)
+    pub fn rate(&self, species: TreeSpecies) -> f64 {
+        self.rate_overrides
+            .get(&species)
+            .copied()
+            .unwrap_or_else(|| species.weekly_water_liters(self.season_weeks))
+    }
+
+    /// Liters needed per week by a zone
+    pub fn weekly_liters(&self, zone: &PlantingZone) -> f64 {
+        zone.plantings
+            .iter()
+            .map(|(species, count)| *count as f64 * self.rate(*species))
+            .sum()
+    }
+
+    /// Season plan for one zone delivered by a tank of capacity C
+    pub fn plan_zone(&self, zone: &PlantingZone, tank: &WaterBottle) -> Result<ZoneWaterPlan> {
+        if tank.capacity_liters.is_nan() || tank.capacity_liters <= 0.0 {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("tank {} has non-positive capacity", tank.id),
+            });
+        }
+
+        let weekly_liters = self.weekly_liters(zone);
+        let total_liters = weekly_liters * self.season_weeks as f64;
+        let weekly_trips = (weekly_liters / tank.capacity_liters).ceil() as u32;
+
+        let schedule: Vec<WeeklyDelivery> = (1..=self.season_weeks)
+            .map(|week| WeeklyDelivery {
+                week,
+                liters: weekly_liters,
+                trips: weekly_trips,
+            })
+            .collect();
+        let trips = schedule.iter().map(|w| w.trips).sum();
+
+        Ok(ZoneWaterPlan {
+            zone_id: zone.zone_id,
+            plant_count: zone.plant_count(),
+            weekly_liters,
+            total_liters,
+            trips,
+            schedule,
+        })
+    }
+
+    /// Season plans for several zones
+    pub fn plan(&self, zones: &[PlantingZone], tank: &WaterBottle) -> Result<Vec<ZoneWaterPlan>> {
+        zones.iter().map(|zone| self.plan_zone(zone, tank)).collect()
+    }
+}
+
+impl Default for WaterDemandPlanner {
+    fn default() -> Self {
+        Self::new(DRY_SEASON_WEEKS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tank(capacity_liters: f64) -> WaterBottle {
+        WaterBottle {
+            id: 1,
+            capacity_liters,
+            source_lat: 33.4484,
+            source_lon: -112.0742,
+        }
+    }
+
+    #[test]
+    fn test_volume_and_trips() {
+        // V = 100 plants · 10 L/week · 12 weeks = 12,000 L; R = 12,000 / 1,000 = 12
+        let mut zone = PlantingZone::new(7);
+        zone.add_plants(TreeSpecies::Mesquite, 100);
+        let planner = WaterDemandPlanner::new(12).with_rate(TreeSpecies::Mesquite, 10.0);
+
+        let plan = planner.plan_zone(&zone, &tank(1000.0)).unwrap();
+        assert_eq!(plan.plant_count, 100);
+        assert!((plan.total_liters - 12_000.0).abs() < 1e-9);
+        assert_eq!(plan.trips, 12);
+        assert_eq!(plan.schedule.len(), 12);
+        assert_eq!(plan.schedule[0].trips, 1);
+    }
+
+    #[test]
+    fn test_species_default_rates() {
+        let mut zone = PlantingZone::new(1);
+        zone.add_plants(TreeSpecies::Paloverde, 10);
+        zone.add_plants(TreeSpecies::Saguaro, 5);
+        let planner = WaterDemandPlanner::default();
+
+        let expected = 10.0 * TreeSpecies::Paloverde.weekly_water_liters(DRY_SEASON_WEEKS)
+            + 5.0 * TreeSpecies::Saguaro.weekly_water_liters(DRY_SEASON_WEEKS);
+        assert!((planner.weekly_liters(&zone) - expected).abs() < 1e-9);
+
+        // The season total is the species requirement, whatever the season length
+        let expected_total = 10.0 * TreeSpecies::Paloverde.water_requirement_liters()
+            + 5.0 * TreeSpecies::Saguaro.water_requirement_liters();
+        let plan = WaterDemandPlanner::new(8).plan_zone(&zone, &tank(1000.0)).unwrap();
+        assert!((plan.total_liters - expected_total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weekly_trips_round_up() {
+        let mut zone = PlantingZone::new(1);
+        zone.add_plants(TreeSpecies::IronWood, 50);
+        let planner = WaterDemandPlanner::new(4).with_rate(TreeSpecies::IronWood, 15.0);
+
+        // 750 L/week in a 500 L tank: 2 trips each week, 8 over the season
+        let plan = planner.plan_zone(&zone, &tank(500.0)).unwrap();
+        assert_eq!(plan.schedule.iter().map(|w| w.trips).sum::<u32>(), 8);
+        assert_eq!(plan.trips, 8);
+        assert!(planner.plan_zone(&zone, &tank(0.0)).is_err());
+    }
+
+    #[test]
+    fn test_profile_rates() {
+        let mut profile = JurisdictionProfile::sonoran_default();
+        profile.water_per_plant_l_week.insert(TreeSpecies::Acacia, 7.5);
+        let planner = WaterDemandPlanner::from_profile(&profile, 10);
+        assert_eq!(planner.rate(TreeSpecies::Acacia), 7.5);
+    }
+}
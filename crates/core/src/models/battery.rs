@@ -0,0 +1,275 @@
+//! Battery state-of-health and degradation (drone packs, nanobot cells)
+//! Capacity fades with equivalent full cycles (deeper discharges weigh more),
+//! accelerated by heat, plus calendar fade while stored hot.
+
+use super::hardware::Drone;
+use serde::{Deserialize, Serialize};
+
+/// Capacity fade per full 100 % depth-of-discharge cycle at 25 °C
+pub const CYCLE_FADE_PER_FULL_CYCLE: f64 = 0.0004;
+
+/// Depth-of-discharge stress exponent (shallow cycles wear less)
+pub const DOD_STRESS_EXPONENT: f64 = 1.5;
+
+/// Temperature above which heat exposure accrues calendar fade (°C)
+pub const HEAT_THRESHOLD_C: f64 = 40.0;
+
+/// Calendar fade per hour above the heat threshold
+pub const HEAT_FADE_PER_HOUR: f64 = 0.00002;
+
+/// State of health below which a pack is due for inspection
+pub const SERVICE_SOH: f64 = 0.85;
+
+/// State of health below which a pack must be retired
+pub const RETIREMENT_SOH: f64 = 0.80;
+
+/// Pack condition for maintenance decisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BatteryStatus {
+    Healthy,
+    ServiceDue,
+    Retire,
+}
+
+/// Accumulated degradation of one battery pack
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatteryHealth {
+    pub equivalent_full_cycles: f64,
+    pub cycle_fade: f64,          // Fraction of rated capacity lost to cycling
+    pub heat_exposure_hours: f64, // Time spent above HEAT_THRESHOLD_C
+    pub thermal_fade: f64,        // Fraction lost to hot storage
+}
+
+impl BatteryHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Arrhenius-style acceleration: wear doubles every 10 °C above 25 °C
+    pub fn temperature_factor(temperature_c: f64) -> f64 {
+        2f64.powf(((temperature_c - 25.0) / 10.0).max(0.0))
+    }
+
+    /// Fade caused by one discharge of the given depth (0-1) at a temperature
+    pub fn cycle_fade_for(depth_of_discharge: f64, temperature_c: f64) -> f64 {
+        let dod = depth_of_discharge.clamp(0.0, 1.0);
+        CYCLE_FADE_PER_FULL_CYCLE * dod.powf(DOD_STRESS_EXPONENT) * Self::temperature_factor(temperature_c)
+    }
+
+    /// Record a discharge cycle
+    pub fn record_cycle(&mut self, depth_of_discharge: f64, temperature_c: f64) {
+        self.equivalent_full_cycles += depth_of_discharge.clamp(0.0, 1.0);
+        self.cycle_fade += Self::cycle_fade_for(depth_of_discharge, temperature_c);
+    }
+
+    /// Record time parked at a temperature; only heat above the threshold counts
+    pub fn record_heat_exposure(&mut self, hours: f64, temperature_c: f64) {
+        if temperature_c > HEAT_THRESHOLD_C && hours > 0.0 {
+            self.heat_exposure_hours += hours;
+            let acceleration = Self::temperature_factor(temperature_c) / Self::temperature_factor(HEAT_THRESHOLD_C);
+            self.thermal_fade += HEAT_FADE_PER_HOUR * hours * acceleration;
+        }
+    }
+
+    /// Usable fraction of rated capacity (0-1)
+    pub fn state_of_health(&self) -> f64 {
+        (1.0 - self.cycle_fade - self.thermal_fade).clamp(0.0, 1.0)
+    }
+
+    pub fn usable_capacity(&self, rated: f64) -> f64 {
+        rated * self.state_of_health()
+    }
+
+    pub fn status(&self) -> BatteryStatus {
+        let soh = self.state_of_health();
+        if soh < RETIREMENT_SOH {
+            BatteryStatus::Retire
+        } else if soh < SERVICE_SOH {
+            BatteryStatus::ServiceDue
+        } else {
+            BatteryStatus::Healthy
+        }
+    }
+
+    /// Cycles of the given duty left before state of health falls below a target
+    pub fn cycles_until(&self, target_soh: f64, depth_of_discharge: f64, temperature_c: f64) -> Option<u32> {
+        let per_cycle = Self::cycle_fade_for(depth_of_discharge, temperature_c);
+        let margin = self.state_of_health() - target_soh;
+        if margin < 0.0 {
+            return Some(0);
+        }
+        if per_cycle <= 0.0 {
+            return None; // Duty causes no wear
+        }
+        Some((margin / per_cycle).floor() as u32)
+    }
+}
+
+/// Expected daily duty for a fleet member
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DutyCycle {
+    pub cycles_per_day: f64,
+    pub depth_of_discharge: f64,
+    pub temperature_c: f64,
+}
+
+impl DutyCycle {
+    /// Two 60 % missions a day in Phoenix summer heat
+    pub fn sonoran_summer() -> Self {
+        Self {
+            cycles_per_day: 2.0,
+            depth_of_discharge: 0.6,
+            temperature_c: 42.0,
+        }
+    }
+}
+
+/// Maintenance entry for one drone pack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatteryServiceItem {
+    pub drone_id: u64,
+    pub state_of_health: f64,
+    pub status: BatteryStatus,
+    pub days_until_service: Option<f64>,    // None = duty causes no wear
+    pub days_until_retirement: Option<f64>,
+}
+
+/// Rank a fleet's packs by how soon they need service or retirement
+pub fn plan_battery_maintenance(drones: &[Drone], duty: &DutyCycle) -> Vec<BatteryServiceItem> {
+    let days = |health: &BatteryHealth, target: f64| {
+        health
+            .cycles_until(target, duty.depth_of_discharge, duty.temperature_c)
+            .filter(|_| duty.cycles_per_day > 0.0)
+            .map(|cycles| cycles as f64 / duty.cycles_per_day)
+    };
+
+    let mut items: Vec<BatteryServiceItem> = drones
+        .iter()
+        .map(|d| BatteryServiceItem {
+            drone_id: d.id,
+            state_of_health: d.battery_health.state_of_health(),
+            status: d.battery_health.status(),
+            days_until_service: days(&d.battery_health, SERVICE_SOH),
+            days_until_retirement: days(&d.battery_health, RETIREMENT_SOH),
+        })
+        .collect();
+
+    items.sort_by(|a, b| {
+        b.status
+            .cmp(&a.status)
+            .then(a.state_of_health.total_cmp(&b.state_of_health))
+            .then(a.drone_id.cmp(&b.drone_id))
+    });
+    items
+}
+
+/// Drones whose packs have worn past the retirement threshold
+pub fn packs_due_for_retirement(drones: &[Drone]) -> Vec<u64> {
+    drones
+        .iter()
+        .filter(|d| d.battery_status() == BatteryStatus::Retire)
+        .map(|d| d.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DroneType;
+
+    #[test]
+    fn test_deep_hot_cycles_wear_faster() {
+        let mut shallow = BatteryHealth::new();
+        let mut deep = BatteryHealth::new();
+        for _ in 0..100 {
+            shallow.record_cycle(0.3, 25.0);
+            shallow.record_cycle(0.3, 25.0);
+        }
+        for _ in 0..60 {
+            deep.record_cycle(1.0, 25.0);
+        }
+        assert!((shallow.equivalent_full_cycles - deep.equivalent_full_cycles).abs() < 1e-9);
+        assert!(deep.state_of_health() < shallow.state_of_health());
+
+        assert_eq!(BatteryHealth::temperature_factor(45.0), 4.0);
+        assert!(BatteryHealth::cycle_fade_for(1.0, 45.0) > BatteryHealth::cycle_fade_for(1.0, 25.0));
+    }
+
+    #[test]
+    fn test_status_thresholds() {
+        let mut health = BatteryHealth::new();
+        assert_eq!(health.status(), BatteryStatus::Healthy);
+        health.cycle_fade = 0.17;
+        assert_eq!(health.status(), BatteryStatus::ServiceDue);
+        health.record_heat_exposure(2000.0, 45.0);
+        assert_eq!(health.status(), BatteryStatus::Retire);
+        assert_eq!(health.cycles_until(RETIREMENT_SOH, 1.0, 25.0), Some(0));
+    }
+
+    #[test]
+    fn test_degradation_shrinks_range() {
+        let fresh = Drone::new(1, DroneType::Quadcopter);
+        let mut worn = Drone::new(2, DroneType::Quadcopter);
+        worn.battery_health.cycle_fade = 0.25;
+        assert!((worn.battery_capacity_wh() - 375.0).abs() < 1e-9);
+        assert!(worn.available_range_m() < fresh.available_range_m());
+    }
+
+    #[test]
+    fn test_missions_and_recharges_record_wear() {
+        let mut drone = Drone::new(1, DroneType::Quadcopter);
+        drone.record_mission(30, 40.0, 45.0);
+        assert_eq!(drone.mission_cycles_completed, 1);
+        assert!((drone.battery_health.equivalent_full_cycles - 0.6).abs() < 1e-9);
+        assert!(drone.battery_health.state_of_health() < 1.0);
+
+        let mut bot = crate::models::NanoBot::new(1);
+        bot.energy_mj = 50.0;
+        bot.recharge(80.0);
+        assert!((bot.battery_health.equivalent_full_cycles - 0.5).abs() < 1e-9);
+        assert!(bot.energy_mj <= bot.capacity_mj());
+        assert_eq!(bot.battery_health.heat_exposure_hours, 0.0);
+    }
+
+    #[test]
+    fn test_heat_exposure_recorded_from_use() {
+        let mut cool = Drone::new(1, DroneType::Quadcopter);
+        let mut hot = Drone::new(2, DroneType::Quadcopter);
+        cool.record_mission(30, 40.0, 25.0);
+        hot.record_mission(30, 40.0, 45.0);
+        assert!((hot.battery_health.heat_exposure_hours - 0.5).abs() < 1e-9);
+        assert!(hot.battery_health.thermal_fade > 0.0);
+        assert_eq!(cool.battery_health.thermal_fade, 0.0);
+
+        hot.record_parked(8.0, 48.0);
+        assert!((hot.battery_health.heat_exposure_hours - 8.5).abs() < 1e-9);
+
+        let mut bot = crate::models::NanoBot::new(1);
+        bot.energy_mj = 50.0;
+        bot.recharge_at(80.0, 6.0, 50.0);
+        assert_eq!(bot.battery_health.heat_exposure_hours, 6.0);
+        assert!(bot.battery_health.cycle_fade > BatteryHealth::cycle_fade_for(0.5, 25.0));
+    }
+
+    #[test]
+    fn test_retired_packs_ground_drones() {
+        let mut drones: Vec<Drone> = (1..=3).map(|id| Drone::new(id, DroneType::Quadcopter)).collect();
+        drones[0].battery_health.cycle_fade = 0.25;
+        drones[2].battery_health.cycle_fade = 0.16; // Service due, still flyable
+        assert_eq!(packs_due_for_retirement(&drones), vec![1]);
+        assert!(!drones[0].can_fly());
+        assert!(drones[2].can_fly());
+    }
+
+    #[test]
+    fn test_maintenance_plan_orders_by_urgency() {
+        let mut drones: Vec<Drone> = (1..=3).map(|id| Drone::new(id, DroneType::Quadcopter)).collect();
+        drones[1].battery_health.cycle_fade = 0.25; // Retire
+        drones[2].battery_health.cycle_fade = 0.16; // Service due
+
+        let plan = plan_battery_maintenance(&drones, &DutyCycle::sonoran_summer());
+        assert_eq!(plan.iter().map(|i| i.drone_id).collect::<Vec<_>>(), vec![2, 3, 1]);
+        assert_eq!(plan[0].days_until_retirement, Some(0.0));
+        assert!(plan[2].days_until_service.unwrap() > plan[1].days_until_retirement.unwrap_or(0.0));
+    }
+}
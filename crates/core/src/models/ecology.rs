@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Native tree species in Sonoran Desert
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum TreeSpecies {
     Paloverde,      // Parkinsonia (primary native)
     Mesquite,       // Prosopis
@@ -26,6 +26,12 @@ impl TreeSpecies {
         }
     }
 
+    /// Weekly water demand (liters/tree/week) when the season requirement is
+    /// delivered over an irrigation season of `season_weeks`
+    pub fn weekly_water_liters(&self, season_weeks: u32) -> f64 {
+        self.water_requirement_liters() / season_weeks.max(1) as f64
+    }
+
     /// Carbon sequestration (kg CO2/year/tree)
     pub fn carbon_sequestration_kg_per_year(&self) -> f64 {
         match self {
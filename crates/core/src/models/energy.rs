@@ -1,6 +1,11 @@
 //! Energy models (hardware-only power budgets, completely decoupled from biology)
 
+use crate::math::harvesting::{HarvestingModel, SolarPanel};
+use crate::models::LatLon;
+use crate::utils::constants::sonoran::{PHOENIX_LAT, PHOENIX_LON};
+use crate::utils::errors::{CybersomethingError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Energy source type (hardware-only, non-biological)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -39,6 +44,19 @@ impl EnergySource {
         }
     }
 
+    /// Expected harvest per day in Wh: solar from the harvesting model (Phoenix
+    /// clear-sky year average for a panel rated at nominal power), other sources
+    /// at nominal power around the clock; both scaled by Sonoran availability
+    pub fn expected_daily_wh(&self) -> f64 {
+        let full_day_wh = match self {
+            Self::Solar => HarvestingModel::new(LatLon::new(PHOENIX_LAT, PHOENIX_LON))
+                .with_panel(SolarPanel::rated(self.nominal_power_w()))
+                .mean_daily_clear_sky_wh(),
+            _ => self.nominal_power_w() * 24.0,
+        };
+        full_day_wh * self.availability_sonoran()
+    }
+
     /// Availability probability (0.0-1.0) in Sonoran Desert
     pub fn availability_sonoran(&self) -> f64 {
         match self {
@@ -100,17 +118,18 @@ impl PowerBudget {
         }
     }
 
-    /// Recharge from energy source
-    pub fn recharge(&mut self, source: EnergySource, energy_wh: f64) {
+    /// Recharge from energy source; returns the Wh actually recovered
+    pub fn recharge(&mut self, source: EnergySource, energy_wh: f64) -> f64 {
         let old = self.energy_consumed_wh;
         self.energy_consumed_wh = (self.energy_consumed_wh - energy_wh).max(0.0);
-        
+
         if !self.energy_sources.contains(&source) {
             self.energy_sources.push(source);
         }
+        old - self.energy_consumed_wh
     }
 
-    /// Daily recovery percentage based on sources
+    /// Daily recovery percentage based on sources (`EnergySource::expected_daily_wh`)
     pub fn daily_recovery_percent(&self) -> f64 {
         if self.energy_sources.is_empty() {
             return 0.0;
@@ -118,7 +137,7 @@ impl PowerBudget {
 
         let avg_recovery = self.energy_sources
             .iter()
-            .map(|src| src.nominal_power_w() * 24.0 / self.total_energy_budget_wh * src.availability_sonoran())
+            .map(|src| src.expected_daily_wh() / self.total_energy_budget_wh)
             .sum::<f64>()
             / self.energy_sources.len() as f64;
 
@@ -136,7 +155,7 @@ pub struct EnergyEvent {
     pub duration_s: u32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EnergyEventType {
     Mission,        // Operational task
     Idle,          // Standby/sleep
@@ -151,6 +170,186 @@ impl EnergyEvent {
     pub fn energy_wh(&self) -> f64 {
         self.power_w * self.duration_s as f64 / 3600.0
     }
+
+    /// Signed effect on stored energy: Charging adds, everything else draws (Wh)
+    pub fn net_wh(&self) -> f64 {
+        if self.event_type == EnergyEventType::Charging {
+            self.energy_wh()
+        } else {
+            -self.energy_wh()
+        }
+    }
+
+    /// Portion of the event elapsed by time t (0-1)
+    fn elapsed_fraction(&self, t: u64) -> f64 {
+        if t <= self.timestamp_s {
+            0.0
+        } else if self.duration_s == 0 || t >= self.timestamp_s + self.duration_s as u64 {
+            1.0
+        } else {
+            (t - self.timestamp_s) as f64 / self.duration_s as f64
+        }
+    }
+}
+
+/// Totals for one event category
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategorySummary {
+    pub events: u32,
+    pub energy_wh: f64,
+    pub duration_s: u64,
+}
+
+/// Per-category energy totals for a device or fleet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LedgerSummary {
+    pub by_category: HashMap<EnergyEventType, CategorySummary>,
+}
+
+impl LedgerSummary {
+    fn add(&mut self, event: &EnergyEvent) {
+        let entry = self.by_category.entry(event.event_type).or_default();
+        entry.events += 1;
+        entry.energy_wh += event.energy_wh();
+        entry.duration_s += event.duration_s as u64;
+    }
+
+    /// Combine with another summary (fleet roll-up)
+    pub fn merge(&mut self, other: &LedgerSummary) {
+        for (category, totals) in &other.by_category {
+            let entry = self.by_category.entry(*category).or_default();
+            entry.events += totals.events;
+            entry.energy_wh += totals.energy_wh;
+            entry.duration_s += totals.duration_s;
+        }
+    }
+
+    pub fn energy_wh(&self, category: EnergyEventType) -> f64 {
+        self.by_category.get(&category).map_or(0.0, |c| c.energy_wh)
+    }
+
+    /// All draws (every category except Charging)
+    pub fn consumed_wh(&self) -> f64 {
+        self.by_category
+            .iter()
+            .filter(|(c, _)| **c != EnergyEventType::Charging)
+            .map(|(_, t)| t.energy_wh)
+            .sum()
+    }
+
+    pub fn recharged_wh(&self) -> f64 {
+        self.energy_wh(EnergyEventType::Charging)
+    }
+
+    /// Share of consumption spent on missions (0-1), the fleet efficiency figure
+    pub fn mission_share(&self) -> f64 {
+        let consumed = self.consumed_wh();
+        if consumed <= 0.0 {
+            return 0.0;
+        }
+        self.energy_wh(EnergyEventType::Mission) / consumed
+    }
+}
+
+/// Append-only energy history for one device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyLedger {
+    pub device_id: u64,
+    pub capacity_wh: f64,
+    pub initial_wh: f64,      // Stored energy before the first event
+    events: Vec<EnergyEvent>, // Ordered by timestamp
+}
+
+impl EnergyLedger {
+    pub fn new(device_id: u64, capacity_wh: f64, initial_wh: f64) -> Self {
+        Self {
+            device_id,
+            capacity_wh,
+            initial_wh: initial_wh.clamp(0.0, capacity_wh),
+            events: Vec::new(),
+        }
+    }
+
+    /// Append an event; it must belong to this device and not predate the last entry
+    pub fn record(&mut self, event: EnergyEvent) -> Result<()> {
+        if event.device_id != self.device_id {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("event for device {} recorded in ledger {}", event.device_id, self.device_id),
+            });
+        }
+        if let Some(last) = self.events.last() {
+            if event.timestamp_s < last.timestamp_s {
+                return Err(CybersomethingError::DataValidationError {
+                    reason: format!("event at {} s predates last entry at {} s", event.timestamp_s, last.timestamp_s),
+                });
+            }
+        }
+        if event.power_w.is_nan() || event.power_w < 0.0 {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("event power must be non-negative, got {}", event.power_w),
+            });
+        }
+        self.events.push(event);
+        Ok(())
+    }
+
+    pub fn record_draw(&mut self, timestamp_s: u64, event_type: EnergyEventType, power_w: f64, duration_s: u32) -> Result<()> {
+        self.record(EnergyEvent {
+            timestamp_s,
+            device_id: self.device_id,
+            event_type,
+            power_w,
+            duration_s,
+        })
+    }
+
+    pub fn record_recharge(&mut self, timestamp_s: u64, power_w: f64, duration_s: u32) -> Result<()> {
+        self.record_draw(timestamp_s, EnergyEventType::Charging, power_w, duration_s)
+    }
+
+    pub fn events(&self) -> &[EnergyEvent] {
+        &self.events
+    }
+
+    /// Replay events up to time t (partial events pro rata), clamping to [0, capacity] (Wh)
+    pub fn stored_wh_at(&self, t: u64) -> f64 {
+        self.events
+            .iter()
+            .take_while(|e| e.timestamp_s < t)
+            .fold(self.initial_wh, |stored, e| {
+                (stored + e.net_wh() * e.elapsed_fraction(t)).clamp(0.0, self.capacity_wh)
+            })
+    }
+
+    /// State of charge at time t (0.0-1.0)
+    pub fn state_of_charge_at(&self, t: u64) -> f64 {
+        if self.capacity_wh <= 0.0 {
+            return 0.0;
+        }
+        self.stored_wh_at(t) / self.capacity_wh
+    }
+
+    pub fn summary(&self) -> LedgerSummary {
+        self.summary_between(0, u64::MAX)
+    }
+
+    /// Totals for events starting in [start_s, end_s)
+    pub fn summary_between(&self, start_s: u64, end_s: u64) -> LedgerSummary {
+        let mut summary = LedgerSummary::default();
+        for event in self.events.iter().filter(|e| e.timestamp_s >= start_s && e.timestamp_s < end_s) {
+            summary.add(event);
+        }
+        summary
+    }
+}
+
+/// Roll up per-device ledgers into one fleet summary
+pub fn fleet_summary(ledgers: &[EnergyLedger]) -> LedgerSummary {
+    let mut total = LedgerSummary::default();
+    for ledger in ledgers {
+        total.merge(&ledger.summary());
+    }
+    total
 }
 
 /// System-wide power distribution
@@ -238,6 +437,48 @@ mod tests {
         assert_eq!(energy, 100.0); // 100W * 1 hour = 100 Wh
     }
 
+    #[test]
+    fn test_recharge_reports_recovered_energy() {
+        let mut budget = PowerBudget::new(1, "drone".to_string(), 500.0);
+        budget.draw_energy(40.0);
+        assert_eq!(budget.recharge(EnergySource::Solar, 100.0), 40.0);
+        assert_eq!(budget.energy_consumed_wh, 0.0);
+    }
+
+    #[test]
+    fn test_ledger_replay() {
+        let mut ledger = EnergyLedger::new(1, 500.0, 400.0);
+        ledger.record_draw(0, EnergyEventType::Mission, 360.0, 3600).unwrap(); // -360 Wh
+        ledger.record_recharge(7200, 200.0, 3600).unwrap();                   // +200 Wh
+
+        assert_eq!(ledger.stored_wh_at(0), 400.0);
+        assert_eq!(ledger.stored_wh_at(1800), 220.0); // Half-way through the mission
+        assert_eq!(ledger.stored_wh_at(5000), 40.0);
+        assert_eq!(ledger.stored_wh_at(10_800), 240.0);
+        assert!((ledger.state_of_charge_at(10_800) - 0.48).abs() < 1e-12);
+
+        // Append-only: no back-dated or foreign events
+        assert!(ledger.record_draw(100, EnergyEventType::Idle, 1.0, 10).is_err());
+        let foreign = EnergyEvent { timestamp_s: 20_000, device_id: 2, event_type: EnergyEventType::Idle, power_w: 1.0, duration_s: 1 };
+        assert!(ledger.record(foreign).is_err());
+    }
+
+    #[test]
+    fn test_category_and_fleet_summaries() {
+        let mut a = EnergyLedger::new(1, 500.0, 500.0);
+        a.record_draw(0, EnergyEventType::Mission, 300.0, 3600).unwrap();
+        a.record_draw(3600, EnergyEventType::Communication, 10.0, 3600).unwrap();
+        let mut b = EnergyLedger::new(2, 500.0, 500.0);
+        b.record_draw(0, EnergyEventType::Idle, 90.0, 3600).unwrap();
+        b.record_recharge(3600, 100.0, 1800).unwrap();
+
+        let fleet = fleet_summary(&[a.clone(), b]);
+        assert_eq!(fleet.consumed_wh(), 400.0);
+        assert_eq!(fleet.recharged_wh(), 50.0);
+        assert!((fleet.mission_share() - 0.75).abs() < 1e-12);
+        assert_eq!(a.summary_between(3600, 7200).by_category.len(), 1);
+    }
+
     #[test]
     fn test_power_grid_availability() {
         let grid = PowerGrid::new(1);
@@ -18,6 +18,11 @@ impl LatLon {
         Self { latitude, longitude }
     }
 
+    /// Coordinate pair, None when out of range or NaN
+    pub fn try_new(latitude: f64, longitude: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)).then_some(Self { latitude, longitude })
+    }
+
     /// Distance to another point in meters (Haversine formula)
     pub fn distance_to(&self, other: &LatLon) -> f64 {
         const R: f64 = 6371000.0; // Earth radius in meters
@@ -1,5 +1,7 @@
 //! Hardware domain models (drones, nanobots, sensors, actuators)
 
+use super::battery::{BatteryHealth, BatteryStatus};
+use crate::utils::constants::energy::{DRONE_MIN_SOC_PERCENT, NANOBOT_BATTERY_MJ};
 use serde::{Deserialize, Serialize};
 
 /// Drone platform type
@@ -37,6 +39,20 @@ impl DroneType {
             Self::HybridVTOL => 3.5,
         }
     }
+
+    /// Battery capacity in Wh
+    pub fn battery_capacity_wh(&self) -> f64 {
+        match self {
+            Self::Quadcopter => 500.0,
+            Self::FixedWing => 800.0,
+            Self::HybridVTOL => 1200.0,
+        }
+    }
+
+    /// Still-air cruise power in Watts (battery drained over rated flight time)
+    pub fn cruise_power_w(&self) -> f64 {
+        self.battery_capacity_wh() / (self.max_flight_time_minutes() as f64 / 60.0)
+    }
 }
 
 /// Drone platform instance with telemetry
@@ -49,6 +65,8 @@ pub struct Drone {
     pub total_flight_time_minutes: u32,
     pub mission_cycles_completed: u32,
     pub status: DroneStatus,
+    #[serde(default)]
+    pub battery_health: BatteryHealth,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -71,31 +89,54 @@ impl Drone {
             total_flight_time_minutes: 0,
             mission_cycles_completed: 0,
             status: DroneStatus::Idle,
+            battery_health: BatteryHealth::new(),
         }
     }
 
-    /// Energy cost for distance in Wh (Watt-hours)
+    /// Current pack capacity in Wh (rated × state of health)
+    pub fn battery_capacity_wh(&self) -> f64 {
+        self.battery_health.usable_capacity(self.drone_type.battery_capacity_wh())
+    }
+
+    /// Log a completed mission: flight time, cycle count and pack wear (cycle plus heat at mission temperature)
+    pub fn record_mission(&mut self, flight_minutes: u32, end_battery_percent: f64, temperature_c: f64) {
+        let depth = (self.battery_percent - end_battery_percent).max(0.0) / 100.0;
+        self.battery_health.record_cycle(depth, temperature_c);
+        self.battery_health.record_heat_exposure(flight_minutes as f64 / 60.0, temperature_c);
+        self.total_flight_time_minutes += flight_minutes;
+        self.mission_cycles_completed += 1;
+        self.battery_percent = end_battery_percent;
+    }
+
+    /// Energy cost for distance in Wh: cruise power over the time at cruise speed
+    /// (still air, sea level, no payload; see `math::flight_energy` for terrain and
+    /// weather). Replaces the former flat 50/25/35 Wh/km rates (~15 Wh/km quadcopter).
     pub fn energy_cost_wh(&self, distance_m: f64) -> f64 {
-        // Baseline: 50 Wh/km for multirotor
-        let base_efficiency = match self.drone_type {
-            DroneType::Quadcopter => 50.0,
-            DroneType::FixedWing => 25.0,
-            DroneType::HybridVTOL => 35.0,
-        };
-
-        (distance_m / 1000.0) * base_efficiency
+        let seconds = distance_m / self.drone_type.cruise_speed_mps();
+        self.drone_type.cruise_power_w() * seconds / 3600.0
     }
 
     /// Available range in meters given current battery
     pub fn available_range_m(&self) -> f64 {
-        let battery_wh = self.battery_percent * 5.0; // Assume 500 Wh battery
+        let battery_wh = self.battery_percent / 100.0 * self.battery_capacity_wh();
         let efficiency = self.energy_cost_wh(1000.0);
 
         (battery_wh / efficiency) * 1000.0 * 0.8 // 0.8 = safety margin
     }
 
+    /// Log time parked (staged, charging) at an ambient temperature
+    pub fn record_parked(&mut self, hours: f64, ambient_c: f64) {
+        self.battery_health.record_heat_exposure(hours, ambient_c);
+    }
+
+    /// Pack condition; drones with a pack due for retirement are grounded
+    pub fn battery_status(&self) -> BatteryStatus {
+        self.battery_health.status()
+    }
+
     pub fn can_fly(&self) -> bool {
-        self.battery_percent > 20.0
+        self.battery_percent > DRONE_MIN_SOC_PERCENT
+            && self.battery_status() != BatteryStatus::Retire
             && (self.status == DroneStatus::Idle || self.status == DroneStatus::OnMission)
     }
 }
@@ -108,6 +149,8 @@ pub struct NanoBot {
     pub energy_mj: f64,                // Millijoules (RF/solar harvesting)
     pub active: bool,
     pub task_queue: Vec<NanoBotTask>,
+    #[serde(default)]
+    pub battery_health: BatteryHealth,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -148,12 +191,18 @@ impl NanoBot {
         Self {
             id,
             position: (0.0, 0.0, 0.0),
-            energy_mj: 100.0,
+            energy_mj: NANOBOT_BATTERY_MJ,
             active: true,
             task_queue: Vec::new(),
+            battery_health: BatteryHealth::new(),
         }
     }
 
+    /// Current storage capacity in mJ (rated × state of health)
+    pub fn capacity_mj(&self) -> f64 {
+        self.battery_health.usable_capacity(NANOBOT_BATTERY_MJ)
+    }
+
     pub fn can_execute_task(&self, task: &NanoBotTask) -> bool {
         self.active && self.energy_mj >= task.energy_required_mj()
     }
@@ -167,9 +216,21 @@ impl NanoBot {
         }
     }
 
-    /// Recharge from RF/solar (Millijoules added)
+    /// Recharge from RF/solar (Millijoules); the refill counts as a partial cycle at 25 °C
     pub fn recharge(&mut self, mj: f64) {
-        self.energy_mj = (self.energy_mj + mj).min(100.0);
+        self.recharge_at(mj, 0.0, 25.0);
+    }
+
+    /// Recharge over `hours` in the soil at `temperature_c`; the refill counts as a
+    /// partial cycle and the harvest time as heat exposure
+    pub fn recharge_at(&mut self, mj: f64, hours: f64, temperature_c: f64) {
+        let capacity = self.capacity_mj();
+        let added = (self.energy_mj + mj).min(capacity) - self.energy_mj;
+        if added > 0.0 && capacity > 0.0 {
+            self.battery_health.record_cycle(added / capacity, temperature_c);
+        }
+        self.battery_health.record_heat_exposure(hours, temperature_c);
+        self.energy_mj = (self.energy_mj + mj).min(self.capacity_mj());
     }
 }
 
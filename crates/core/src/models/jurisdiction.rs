@@ -0,0 +1,475 @@
+//! Jurisdiction policy profiles
+//! Risk weights, thresholds, defensible-space rings, water-per-plant rates and
+//! nanoswarm limits loaded from TOML/JSON files, with `extends` inheritance
+//! (e.g. Maricopa County extends the Arizona default).
+
+use crate::math::defensible::DefensibleRuleTable;
+use crate::math::normalization::NormalizationProfile;
+use crate::math::risk_index::{RiskCalculator, RiskFormulaKind, RiskThresholds, RiskWeights};
+use crate::models::ecology::TreeSpecies;
+use crate::utils::constants::nanoswarm;
+use crate::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Nanoswarm deployment envelope
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NanoswarmLimits {
+    pub max_mass_g_per_ha: f64,
+    pub max_mission_hours: f64,   // Kill-switch
+    pub max_duty_cycle: f64,      // 0-1
+}
+
+impl NanoswarmLimits {
+    /// Reject a planned release that exceeds the mass, duration or duty-cycle envelope
+    pub fn check(&self, dose: &NanoswarmDose) -> Result<()> {
+        let violation = |reason: String| Err(CybersomethingError::ALNComplianceViolation { reason });
+
+        // NaN fails every limit comparison, so reject non-finite inputs up front
+        for (field, value) in [
+            ("area", dose.area_ha),
+            ("bot mass", dose.bot_mass_g),
+            ("mission hours", dose.mission_hours),
+            ("duty cycle", dose.duty_cycle),
+        ] {
+            if !value.is_finite() {
+                return Err(CybersomethingError::DataValidationError {
+                    reason: format!("nanoswarm dose {} must be finite (got {})", field, value),
+                });
+            }
+        }
+        if dose.area_ha <= 0.0 {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("nanoswarm dose area must be positive (got {} ha)", dose.area_ha),
+            });
+        }
+        let density = dose.mass_g_per_ha();
+        if density > self.max_mass_g_per_ha {
+            return violation(format!(
+                "nanoswarm dose {:.2} g/ha exceeds limit {:.2} g/ha",
+                density, self.max_mass_g_per_ha
+            ));
+        }
+        if dose.mission_hours > self.max_mission_hours {
+            return violation(format!(
+                "nanoswarm mission {:.1} h exceeds kill-switch {:.1} h",
+                dose.mission_hours, self.max_mission_hours
+            ));
+        }
+        if dose.duty_cycle > self.max_duty_cycle {
+            return violation(format!(
+                "nanoswarm duty cycle {:.2} exceeds limit {:.2}",
+                dose.duty_cycle, self.max_duty_cycle
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Planned nanoswarm release over an area
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NanoswarmDose {
+    pub bots: u32,
+    pub bot_mass_g: f64,
+    pub area_ha: f64,
+    pub mission_hours: f64,
+    pub duty_cycle: f64,  // 0-1
+}
+
+impl NanoswarmDose {
+    /// Release of standard nanobots (`nanoswarm::NANOBOT_MASS_G` each)
+    pub fn new(bots: u32, area_ha: f64, mission_hours: f64, duty_cycle: f64) -> Self {
+        Self {
+            bots,
+            bot_mass_g: nanoswarm::NANOBOT_MASS_G,
+            area_ha,
+            mission_hours,
+            duty_cycle,
+        }
+    }
+
+    /// Deployed mass density (g/ha)
+    pub fn mass_g_per_ha(&self) -> f64 {
+        self.bots as f64 * self.bot_mass_g / self.area_ha
+    }
+}
+
+impl Default for NanoswarmLimits {
+    fn default() -> Self {
+        Self {
+            max_mass_g_per_ha: nanoswarm::MAX_MASS_G_PER_HA,
+            max_mission_hours: nanoswarm::MAX_MISSION_HOURS,
+            max_duty_cycle: nanoswarm::MAX_DUTY_CYCLE,
+        }
+    }
+}
+
+/// Fully resolved jurisdiction parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JurisdictionProfile {
+    pub name: String,
+    pub extends: Option<String>,
+    pub risk_formula: RiskFormulaKind,
+    pub risk_weights: RiskWeights,
+    pub risk_thresholds: RiskThresholds,
+    pub normalization: NormalizationProfile,
+    pub defensible_rules: DefensibleRuleTable,
+    pub water_per_plant_l_week: BTreeMap<TreeSpecies, f64>, // Overrides species defaults
+    pub nanoswarm: NanoswarmLimits,
+}
+
+/// Partial weights in a profile file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeightOverrides {
+    pub alpha: Option<f64>,
+    pub beta: Option<f64>,
+    pub gamma: Option<f64>,
+}
+
+/// Partial thresholds in a profile file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdOverrides {
+    pub low: Option<f64>,
+    pub medium: Option<f64>,
+}
+
+/// Partial nanoswarm limits in a profile file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NanoswarmOverrides {
+    pub max_mass_g_per_ha: Option<f64>,
+    pub max_mission_hours: Option<f64>,
+    pub max_duty_cycle: Option<f64>,
+}
+
+/// Contents of a profile file: only the fields it sets
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileOverrides {
+    pub name: String,
+    pub extends: Option<String>,
+    pub risk_formula: Option<RiskFormulaKind>,
+    pub risk_weights: Option<WeightOverrides>,
+    pub risk_thresholds: Option<ThresholdOverrides>,
+    pub normalization: Option<NormalizationProfile>,
+    pub defensible_rules: Option<DefensibleRuleTable>,
+    #[serde(default)]
+    pub water_per_plant_l_week: BTreeMap<TreeSpecies, f64>,
+    pub nanoswarm: Option<NanoswarmOverrides>,
+}
+
+/// Parse or schema failure at a field path (e.g. `risk_weights.alpha`)
+fn field_error<E: std::fmt::Display>(err: serde_path_to_error::Error<E>) -> CybersomethingError {
+    CybersomethingError::DataValidationError {
+        reason: format!("profile field '{}': {}", err.path(), err.inner()),
+    }
+}
+
+impl ProfileOverrides {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        serde_path_to_error::deserialize(toml::Deserializer::new(text)).map_err(field_error)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        let mut de = serde_json::Deserializer::from_str(text);
+        let overrides = serde_path_to_error::deserialize(&mut de).map_err(field_error)?;
+        de.end().map_err(|e| CybersomethingError::DataValidationError {
+            reason: format!("profile field '.': {}", e),
+        })?;
+        Ok(overrides)
+    }
+
+    /// Parse by file extension (.toml or .json)
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            Some("json") => Self::from_json(&text),
+            _ => Err(CybersomethingError::DataValidationError {
+                reason: format!("unsupported profile format: {}", path.display()),
+            }),
+        }
+    }
+}
+
+impl JurisdictionProfile {
+    /// Built-in Sonoran defaults from `utils::constants`
+    pub fn sonoran_default() -> Self {
+        Self {
+            name: "sonoran_default".to_string(),
+            extends: None,
+            risk_formula: RiskFormulaKind::Additive,
+            risk_weights: RiskWeights::default(),
+            risk_thresholds: RiskThresholds::default(),
+            normalization: NormalizationProfile::sonoran(),
+            defensible_rules: DefensibleRuleTable::county_ordinance(),
+            water_per_plant_l_week: BTreeMap::new(),
+            nanoswarm: NanoswarmLimits::default(),
+        }
+    }
+
+    /// Layer a child's overrides on top of this profile and validate the result
+    pub fn apply(&self, overrides: ProfileOverrides) -> Result<Self> {
+        let mut profile = self.clone();
+        profile.name = overrides.name;
+        profile.extends = overrides.extends;
+
+        if let Some(formula) = overrides.risk_formula {
+            profile.risk_formula = formula;
+        }
+        if let Some(w) = overrides.risk_weights {
+            profile.risk_weights.alpha = w.alpha.unwrap_or(profile.risk_weights.alpha);
+            profile.risk_weights.beta = w.beta.unwrap_or(profile.risk_weights.beta);
+            profile.risk_weights.gamma = w.gamma.unwrap_or(profile.risk_weights.gamma);
+        }
+        if let Some(t) = overrides.risk_thresholds {
+            profile.risk_thresholds.low = t.low.unwrap_or(profile.risk_thresholds.low);
+            profile.risk_thresholds.medium = t.medium.unwrap_or(profile.risk_thresholds.medium);
+        }
+        if let Some(normalization) = overrides.normalization {
+            profile.normalization = normalization;
+        }
+        if let Some(rules) = overrides.defensible_rules {
+            profile.defensible_rules = rules;
+        }
+        profile.water_per_plant_l_week.extend(overrides.water_per_plant_l_week);
+        if let Some(n) = overrides.nanoswarm {
+            profile.nanoswarm.max_mass_g_per_ha = n.max_mass_g_per_ha.unwrap_or(profile.nanoswarm.max_mass_g_per_ha);
+            profile.nanoswarm.max_mission_hours = n.max_mission_hours.unwrap_or(profile.nanoswarm.max_mission_hours);
+            profile.nanoswarm.max_duty_cycle = n.max_duty_cycle.unwrap_or(profile.nanoswarm.max_duty_cycle);
+        }
+
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// Load a profile file, resolving `extends` against sibling `<name>.toml` / `<name>.json` files
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::load_chain(path.as_ref(), &mut HashSet::new())
+    }
+
+    fn load_chain(path: &Path, visited: &mut HashSet<PathBuf>) -> Result<Self> {
+        let canonical = path.canonicalize()?;
+        if !visited.insert(canonical) {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("profile inheritance cycle at {}", path.display()),
+            });
+        }
+
+        let overrides = ProfileOverrides::from_file(path)?;
+        let parent = match &overrides.extends {
+            None => Self::sonoran_default(),
+            Some(parent_name) => {
+                let dir = path.parent().unwrap_or_else(|| Path::new("."));
+                let parent_path = ["toml", "json"]
+                    .iter()
+                    .map(|ext| dir.join(format!("{}.{}", parent_name, ext)))
+                    .find(|p| p.exists())
+                    .ok_or_else(|| CybersomethingError::DataValidationError {
+                        reason: format!(
+                            "profile '{}' extends unknown profile '{}'",
+                            overrides.name, parent_name
+                        ),
+                    })?;
+                Self::load_chain(&parent_path, visited)?
+            }
+        };
+
+        parent.apply(overrides)
+    }
+
+    /// Check every parameter is usable
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| CybersomethingError::DataValidationError {
+            reason: format!("profile '{}': {}", self.name, reason),
+        };
+
+        let w = &self.risk_weights;
+        if [w.alpha, w.beta, w.gamma].iter().any(|v| !v.is_finite() || *v < 0.0) {
+            return Err(invalid("risk_weights: must be finite and non-negative".into()));
+        }
+        if w.alpha + w.beta + w.gamma <= 0.0 {
+            return Err(invalid("risk_weights: sum to zero".into()));
+        }
+
+        let t = &self.risk_thresholds;
+        if !(0.0 < t.low && t.low < t.medium && t.medium <= 1.0) {
+            return Err(invalid(format!(
+                "risk_thresholds: must satisfy 0 < low < medium <= 1 (got {}, {})",
+                t.low, t.medium
+            )));
+        }
+
+        self.normalization.validate()?;
+
+        for (zone, rings) in [
+            ("low", &self.defensible_rules.low),
+            ("medium", &self.defensible_rules.medium),
+            ("high", &self.defensible_rules.high),
+        ] {
+            if rings.iter().any(|r| r.inner_radius_m < 0.0 || r.outer_radius_m <= r.inner_radius_m) {
+                return Err(invalid(format!("defensible_rules.{}: ring has inner radius >= outer", zone)));
+            }
+            if rings.iter().any(|r| r.max_grass_height_cm < 0.0) {
+                return Err(invalid(format!("defensible_rules.{}: ring has negative grass height", zone)));
+            }
+            if rings.windows(2).any(|p| p[1].inner_radius_m < p[0].outer_radius_m) {
+                return Err(invalid(format!("defensible_rules.{}: rings overlap or are unordered", zone)));
+            }
+        }
+
+        if let Some((species, rate)) = self.water_per_plant_l_week.iter().find(|(_, r)| r.is_nan() || **r <= 0.0) {
+            return Err(invalid(format!("water_per_plant_l_week.{:?}: must be positive (got {})", species, rate)));
+        }
+
+        let n = &self.nanoswarm;
+        if !(n.max_mass_g_per_ha > 0.0 && n.max_mission_hours > 0.0) {
+            return Err(invalid("nanoswarm: mass and mission limits must be positive".into()));
+        }
+        if !(0.0..=1.0).contains(&n.max_duty_cycle) {
+            return Err(invalid("nanoswarm.max_duty_cycle: must lie in [0, 1]".into()));
+        }
+
+        Ok(())
+    }
+
+    /// Liters per plant per week for a species (profile override, or the species
+    /// requirement spread over `season_weeks`)
+    pub fn water_per_plant_l_week(&self, species: TreeSpecies, season_weeks: u32) -> f64 {
+        self.water_per_plant_l_week
+            .get(&species)
+            .copied()
+            .unwrap_or_else(|| species.weekly_water_liters(season_weeks))
+    }
+
+    /// Risk calculator configured with this profile's formula, normalization, thresholds and rings
+    pub fn risk_calculator(&self) -> RiskCalculator {
+        RiskCalculator::from_kind(self.risk_formula, self.risk_weights)
+            .with_normalization(self.normalization.clone())
+            .with_thresholds(self.risk_thresholds)
+            .with_defensible_rules(self.defensible_rules.clone())
+    }
+}
+
+impl Default for JurisdictionProfile {
+    fn default() -> Self {
+        Self::sonoran_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::risk_index::RiskZone;
+
+    fn profiles_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../profiles")
+    }
+
+    #[test]
+    fn test_load_with_inheritance() {
+        let arizona = JurisdictionProfile::load(profiles_dir().join("arizona.toml")).unwrap();
+        let maricopa = JurisdictionProfile::load(profiles_dir().join("maricopa.toml")).unwrap();
+
+        assert_eq!(maricopa.extends.as_deref(), Some("arizona"));
+        // Inherited from Arizona
+        assert_eq!(maricopa.risk_weights, arizona.risk_weights);
+        assert_eq!(
+            maricopa.water_per_plant_l_week(TreeSpecies::Paloverde, 12),
+            arizona.water_per_plant_l_week(TreeSpecies::Paloverde, 12)
+        );
+        // Overridden by Maricopa
+        assert!(maricopa.risk_thresholds.medium < arizona.risk_thresholds.medium);
+        assert_eq!(maricopa.defensible_rules.jurisdiction, "maricopa_county");
+        assert!(maricopa.nanoswarm.max_mass_g_per_ha < arizona.nanoswarm.max_mass_g_per_ha);
+    }
+
+    #[test]
+    fn test_json_overrides() {
+        let overrides = ProfileOverrides::from_json(
+            r#"{ "name": "amazon", "risk_formula": "Multiplicative",
+                 "risk_weights": { "beta": 0.3 },
+                 "water_per_plant_l_week": { "Mesquite": 12.0 } }"#,
+        )
+        .unwrap();
+        let profile = JurisdictionProfile::sonoran_default().apply(overrides).unwrap();
+
+        assert_eq!(profile.risk_formula, RiskFormulaKind::Multiplicative);
+        assert_eq!(profile.risk_weights.beta, 0.3);
+        assert_eq!(profile.risk_weights.alpha, RiskWeights::default().alpha);
+        assert_eq!(profile.water_per_plant_l_week(TreeSpecies::Mesquite, 12), 12.0);
+        assert_eq!(
+            profile.water_per_plant_l_week(TreeSpecies::Saguaro, 12),
+            TreeSpecies::Saguaro.weekly_water_liters(12)
+        );
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let overrides = ProfileOverrides::from_toml(
+            "name = \"broken\"\n[risk_thresholds]\nlow = 0.8\nmedium = 0.5\n",
+        )
+        .unwrap();
+        let err = JurisdictionProfile::sonoran_default().apply(overrides).unwrap_err();
+        assert!(matches!(err, CybersomethingError::DataValidationError { .. }));
+
+        let overrides = ProfileOverrides::from_toml("name = \"x\"\n[water_per_plant_l_week]\nSaguaro = -1.0\n").unwrap();
+        assert!(JurisdictionProfile::sonoran_default().apply(overrides).is_err());
+
+        assert!(ProfileOverrides::from_toml("name = \"x\"\nunknown_key = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors_carry_field_path() {
+        let err = ProfileOverrides::from_toml("name = \"x\"\n[risk_weights]\nalpha = \"high\"\n").unwrap_err();
+        match err {
+            CybersomethingError::DataValidationError { reason } => assert!(reason.contains("risk_weights.alpha"), "{}", reason),
+            other => panic!("unexpected error {:?}", other),
+        }
+
+        let err = ProfileOverrides::from_json(r#"{ "name": "x", "nanoswarm": { "max_duty_cycle": [] } }"#).unwrap_err();
+        match err {
+            CybersomethingError::DataValidationError { reason } => assert!(reason.contains("nanoswarm.max_duty_cycle"), "{}", reason),
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_nanoswarm_limits_enforced() {
+        let maricopa = JurisdictionProfile::load(profiles_dir().join("maricopa.toml")).unwrap();
+        let arizona = JurisdictionProfile::load(profiles_dir().join("arizona.toml")).unwrap();
+
+        // 3000 bots × 0.01 g over 1 ha = 30 g/ha: within Arizona (50), over Maricopa (25)
+        let dose = NanoswarmDose::new(3000, 1.0, 24.0, 0.4);
+        assert!(arizona.nanoswarm.check(&dose).is_ok());
+        assert!(matches!(
+            maricopa.nanoswarm.check(&dose),
+            Err(CybersomethingError::ALNComplianceViolation { .. })
+        ));
+
+        let long = NanoswarmDose::new(100, 1.0, 96.0, 0.4);
+        assert!(arizona.nanoswarm.check(&long).is_err());
+        let busy = NanoswarmDose::new(100, 1.0, 24.0, 0.9);
+        assert!(arizona.nanoswarm.check(&busy).is_err());
+
+        for dose in [NanoswarmDose::new(100, 1.0, f64::NAN, 0.4), NanoswarmDose::new(100, 1.0, 24.0, f64::NAN)] {
+            assert!(matches!(
+                arizona.nanoswarm.check(&dose),
+                Err(CybersomethingError::DataValidationError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_risk_calculator_from_profile() {
+        let maricopa = JurisdictionProfile::load(profiles_dir().join("maricopa.toml")).unwrap();
+        let calc = maricopa.risk_calculator();
+
+        let risk = calc.compute_risk(500.0, 80.0, 15.0);
+        assert_eq!(calc.classify(risk), RiskZone::classify(risk, &maricopa.risk_thresholds));
+        assert_eq!(calc.defensible_zone(risk).jurisdiction, "maricopa_county");
+    }
+}
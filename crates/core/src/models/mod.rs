@@ -3,9 +3,13 @@
 pub mod geospatial;
 pub mod ecology;
 pub mod hardware;
+pub mod battery;
 pub mod energy;
+pub mod jurisdiction;
 
 pub use geospatial::*;
 pub use ecology::*;
 pub use hardware::*;
+pub use battery::*;
 pub use energy::*;
+pub use jurisdiction::*;
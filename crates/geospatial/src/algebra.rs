@@ -0,0 +1,636 @@
+//! Raster algebra: per-pixel expressions over named bands
+//! e.g. `(NIR - Red) / (NIR + Red + 0.5) * 1.5`, with comparisons, `&&`/`||`/`!`,
+//! `if(c, a, b)`, `min`/`max`/`clamp`, and nodata propagation (any nodata input
+//! or non-finite result yields nodata unless guarded by `coalesce`).
+
+use crate::raster::{RasterBand, RasterDataset};
+use crate::tiled::{map_blocks, BandSource, TiledBand};
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
+use rayon::prelude::*;
+
+/// Band id of expression results
+pub const EXPRESSION_BAND_ID: u32 = 1030;
+/// Output nodata unless set with `BandExpression::with_no_data`
+pub const EXPRESSION_NO_DATA: f32 = -9999.0;
+/// Deepest operator/parenthesis nesting the parser accepts
+pub const MAX_EXPRESSION_DEPTH: usize = 64;
+
+/// Built-in spectral indices (Landsat/Sentinel-style band names).
+/// `dNBR` expects pre/post-fire bands bound as `NIR_pre`, `SWIR2_post`, …
+pub const SPECTRAL_INDICES: &[(&str, &str)] = &[
+    ("NDVI", "(NIR - Red) / (NIR + Red)"),
+    ("SAVI", "(NIR - Red) / (NIR + Red + 0.5) * 1.5"),
+    ("EVI", "2.5 * (NIR - Red) / (NIR + 6 * Red - 7.5 * Blue + 1)"),
+    ("NDMI", "(NIR - SWIR1) / (NIR + SWIR1)"),
+    ("NBR", "(NIR - SWIR2) / (NIR + SWIR2)"),
+    ("dNBR", "(NIR_pre - SWIR2_pre) / (NIR_pre + SWIR2_pre) - (NIR_post - SWIR2_post) / (NIR_post + SWIR2_post)"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+impl BinaryOp {
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        Some(match symbol {
+            "||" => Self::Or,
+            "&&" => Self::And,
+            "==" => Self::Eq,
+            "!=" => Self::Ne,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            "+" => Self::Add,
+            "-" => Self::Sub,
+            "*" => Self::Mul,
+            "/" => Self::Div,
+            "%" => Self::Rem,
+            "^" => Self::Pow,
+            _ => return None,
+        })
+    }
+
+    /// (left, right) binding power; right < left makes `^` right-associative
+    fn binding_power(self) -> (u8, u8) {
+        match self {
+            Self::Or => (1, 2),
+            Self::And => (3, 4),
+            Self::Eq | Self::Ne | Self::Lt | Self::Le | Self::Gt | Self::Ge => (5, 6),
+            Self::Add | Self::Sub => (7, 8),
+            Self::Mul | Self::Div | Self::Rem => (9, 10),
+            Self::Pow => (14, 13),
+        }
+    }
+
+    fn apply(self, a: f64, b: f64) -> f64 {
+        let truth = |t: bool| if t { 1.0 } else { 0.0 };
+        match self {
+            Self::Or => truth(a != 0.0 || b != 0.0),
+            Self::And => truth(a != 0.0 && b != 0.0),
+            Self::Eq => truth(a == b),
+            Self::Ne => truth(a != b),
+            Self::Lt => truth(a < b),
+            Self::Le => truth(a <= b),
+            Self::Gt => truth(a > b),
+            Self::Ge => truth(a >= b),
+            Self::Add => a + b,
+            Self::Sub => a - b,
+            Self::Mul => a * b,
+            Self::Div => a / b,
+            Self::Rem => a % b,
+            Self::Pow => a.powf(b),
+        }
+    }
+}
+
+/// Prefix operators bind tighter than `*` but looser than `^` (`-x^2` = `-(x^2)`)
+const PREFIX_BINDING_POWER: u8 = 11;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Min,
+    Max,
+    Abs,
+    Sqrt,
+    Ln,
+    Exp,
+    Clamp,
+    If,
+    Coalesce,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "abs" => Self::Abs,
+            "sqrt" => Self::Sqrt,
+            "ln" => Self::Ln,
+            "exp" => Self::Exp,
+            "clamp" => Self::Clamp,
+            "if" => Self::If,
+            "coalesce" => Self::Coalesce,
+            _ => return None,
+        })
+    }
+
+    fn accepts(self, args: usize) -> bool {
+        match self {
+            Self::Min | Self::Max | Self::Coalesce => args >= 1,
+            Self::Abs | Self::Sqrt | Self::Ln | Self::Exp => args == 1,
+            Self::Clamp | Self::If => args == 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Band(usize), // Index into BandExpression::bands
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+impl Expr {
+    /// Evaluate at one pixel; `None` is nodata
+    fn eval(&self, pixel: &[Option<f64>]) -> Option<f64> {
+        match self {
+            Expr::Number(v) => Some(*v),
+            Expr::Band(i) => pixel[*i],
+            Expr::Neg(e) => e.eval(pixel).map(|v| -v),
+            Expr::Not(e) => e.eval(pixel).map(|v| if v == 0.0 { 1.0 } else { 0.0 }),
+            Expr::Binary(op, a, b) => {
+                let a = a.eval(pixel)?;
+                // Short-circuit so a guarded branch may be nodata
+                match op {
+                    BinaryOp::And if a == 0.0 => Some(0.0),
+                    BinaryOp::Or if a != 0.0 => Some(1.0),
+                    _ => Some(op.apply(a, b.eval(pixel)?)),
+                }
+            }
+            Expr::Call(Function::If, args) => {
+                let branch = if args[0].eval(pixel)? != 0.0 { &args[1] } else { &args[2] };
+                branch.eval(pixel)
+            }
+            Expr::Call(Function::Coalesce, args) => args.iter().find_map(|a| a.eval(pixel).filter(|v| v.is_finite())),
+            Expr::Call(function, args) => {
+                let values = args.iter().map(|a| a.eval(pixel)).collect::<Option<Vec<f64>>>()?;
+                Some(match function {
+                    Function::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+                    Function::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    Function::Abs => values[0].abs(),
+                    Function::Sqrt => values[0].sqrt(),
+                    Function::Ln => values[0].ln(),
+                    Function::Exp => values[0].exp(),
+                    Function::Clamp => {
+                        // f64::clamp panics on a NaN bound
+                        if !values[1].is_finite() || !values[2].is_finite() {
+                            return None;
+                        }
+                        values[0].clamp(values[1], values[2].max(values[1]))
+                    }
+                    Function::If | Function::Coalesce => unreachable!(),
+                })
+            }
+        }
+    }
+}
+
+fn parse_error(position: usize, message: impl std::fmt::Display) -> CybersomethingError {
+    CybersomethingError::DataValidationError {
+        reason: format!("expression error at {position}: {message}"),
+    }
+}
+
+/// Split source into (token, byte offset) pairs
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    const SYMBOLS: [&str; 16] = ["&&", "||", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "^", "!", "="];
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        let start = i;
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if c.is_ascii_digit() || (c == '.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)) {
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                i += 1;
+            }
+            // Exponent: 1e-3, 2.5E4
+            if i < bytes.len() && (bytes[i] | 0x20) == b'e' {
+                let sign = usize::from(bytes.get(i + 1).is_some_and(|b| *b == b'+' || *b == b'-'));
+                if bytes.get(i + 1 + sign).is_some_and(u8::is_ascii_digit) {
+                    i += 1 + sign;
+                    while i < bytes.len() && bytes[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text = &source[start..i];
+            Token::Number(text.parse().map_err(|_| parse_error(start, format!("bad number '{text}'")))?)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            Token::Ident(source[start..i].to_string())
+        } else if c == '[' {
+            // Bracketed band names may contain anything but ']'
+            let end = source[i..].find(']').ok_or_else(|| parse_error(start, "unclosed '['"))?;
+            i += end + 1;
+            Token::Ident(source[start + 1..i - 1].trim().to_string())
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| source[i..].starts_with(**s)) {
+            if *symbol == "=" {
+                return Err(parse_error(start, "use '==' for equality"));
+            }
+            i += symbol.len();
+            Token::Op(symbol)
+        } else {
+            i += c.len_utf8();
+            match c {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                _ => return Err(parse_error(start, format!("unexpected '{c}'"))),
+            }
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+/// Pratt parser
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    end: usize, // Source length, for errors at end of input
+    bands: Vec<String>,
+    depth: usize, // Current `expression` nesting
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(_, p)| *p)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        let position = self.position();
+        match self.next() {
+            Some(t) if t == expected => Ok(()),
+            Some(t) => Err(parse_error(position, format!("expected {expected:?}, found {t:?}"))),
+            None => Err(parse_error(position, format!("expected {expected:?}, found end of input"))),
+        }
+    }
+
+    fn band(&mut self, name: String) -> Expr {
+        let index = self.bands.iter().position(|b| *b == name).unwrap_or_else(|| {
+            self.bands.push(name);
+            self.bands.len() - 1
+        });
+        Expr::Band(index)
+    }
+
+    fn expression(&mut self, min_bp: u8) -> Result<Expr> {
+        if self.depth >= MAX_EXPRESSION_DEPTH {
+            return Err(parse_error(self.position(), format!("expression nests deeper than {MAX_EXPRESSION_DEPTH} levels")));
+        }
+        self.depth += 1;
+        let expr = self.binary(min_bp);
+        self.depth -= 1;
+        expr
+    }
+
+    fn binary(&mut self, min_bp: u8) -> Result<Expr> {
+        let mut lhs = self.prefix()?;
+        while let Some(&Token::Op(symbol)) = self.peek() {
+            let position = self.position();
+            let op = BinaryOp::from_symbol(symbol).ok_or_else(|| parse_error(position, format!("'{symbol}' is not a binary operator")))?;
+            let (left_bp, right_bp) = op.binding_power();
+            if left_bp < min_bp {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.expression(right_bp)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn prefix(&mut self) -> Result<Expr> {
+        let position = self.position();
+        match self.next() {
+            Some(Token::Number(v)) => Ok(Expr::Number(v)),
+            Some(Token::Op("-")) => Ok(Expr::Neg(Box::new(self.expression(PREFIX_BINDING_POWER)?))),
+            Some(Token::Op("+")) => self.expression(PREFIX_BINDING_POWER),
+            Some(Token::Op("!")) => Ok(Expr::Not(Box::new(self.expression(PREFIX_BINDING_POWER)?))),
+            Some(Token::LParen) => {
+                let inner = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) if self.peek() == Some(&Token::LParen) => {
+                let function = Function::from_name(&name).ok_or_else(|| parse_error(position, format!("unknown function '{name}'")))?;
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek() != Some(&Token::RParen) {
+                    loop {
+                        args.push(self.expression(0)?);
+                        if self.peek() != Some(&Token::Comma) {
+                            break;
+                        }
+                        self.pos += 1;
+                    }
+                }
+                self.expect(Token::RParen)?;
+                if !function.accepts(args.len()) {
+                    return Err(parse_error(position, format!("wrong number of arguments to '{name}'")));
+                }
+                Ok(Expr::Call(function, args))
+            }
+            Some(Token::Ident(name)) => Ok(self.band(name)),
+            Some(t) => Err(parse_error(position, format!("unexpected {t:?}"))),
+            None => Err(parse_error(position, "unexpected end of input")),
+        }
+    }
+}
+
+/// Parsed per-pixel expression over named bands
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandExpression {
+    source: String,
+    expr: Expr,
+    bands: Vec<String>,            // Referenced bands, in first-use order
+    no_data_value: Option<f32>,    // Output nodata (default: EXPRESSION_NO_DATA)
+}
+
+impl BandExpression {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+            end: source.len(),
+            bands: Vec::new(),
+            depth: 0,
+        };
+        let expr = parser.expression(0)?;
+        if let Some(token) = parser.peek() {
+            return Err(parse_error(parser.position(), format!("unexpected {token:?}")));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+            bands: parser.bands,
+            no_data_value: None,
+        })
+    }
+
+    /// Built-in spectral index by name (see `SPECTRAL_INDICES`)
+    pub fn preset(name: &str) -> Option<Self> {
+        SPECTRAL_INDICES
+            .iter()
+            .find(|(index, _)| index.eq_ignore_ascii_case(name))
+            .map(|(_, source)| Self::parse(source).expect("built-in index parses"))
+    }
+
+    pub fn with_no_data(mut self, no_data_value: f32) -> Self {
+        self.no_data_value = Some(no_data_value);
+        self
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Band names the expression reads
+    pub fn bands(&self) -> &[String] {
+        &self.bands
+    }
+
+    /// Bound band for each referenced name
+    fn bind<'a, B: ?Sized>(&self, bands: &[(&str, &'a B)]) -> Result<Vec<&'a B>> {
+        self.bands
+            .iter()
+            .map(|name| {
+                bands
+                    .iter()
+                    .find(|(bound, _)| bound == name)
+                    .map(|(_, band)| *band)
+                    .ok_or_else(|| CybersomethingError::DataValidationError {
+                        reason: format!("expression '{}' needs band '{}'", self.source, name),
+                    })
+            })
+            .collect()
+    }
+
+    /// Evaluate over bands bound by name (e.g. pre/post-fire bands from two datasets)
+    pub fn evaluate_bands(&self, bands: &[(&str, &RasterBand)], output_name: &str) -> Result<RasterBand> {
+        self.evaluate_inputs(&self.bind(bands)?, output_name)
+    }
+
+    /// Evaluate block by block over bound in-memory or tiled bands into a tiled band
+    pub fn evaluate_tiled(&self, bands: &[(&str, &dyn BandSource)], output: &TiledBand) -> Result<()> {
+        let inputs = self.bind(bands)?;
+        if inputs.is_empty() {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("expression '{}' reads no bands", self.source),
+            });
+        }
+        map_blocks(&inputs, &[output], |windows| {
+            let windows: Vec<&RasterBand> = windows.iter().collect();
+            Ok(vec![self.evaluate_inputs(&windows, output.band_name())?])
+        })
+    }
+
+    fn evaluate_inputs(&self, inputs: &[&RasterBand], output_name: &str) -> Result<RasterBand> {
+        let Some(first) = inputs.first() else {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("expression '{}' reads no bands", self.source),
+            });
+        };
+        if let Some(other) = inputs.iter().find(|b| b.rows != first.rows || b.cols != first.cols) {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!(
+                    "band '{}' is {}x{} but band '{}' is {}x{}",
+                    other.band_name, other.rows, other.cols, first.band_name, first.rows, first.cols
+                ),
+            });
+        }
+
+        let no_data = self.no_data_value.unwrap_or(EXPRESSION_NO_DATA);
+        let data = (0..first.data.len())
+            .into_par_iter()
+            .map_init(
+                || vec![None; inputs.len()],
+                |pixel, idx| {
+                    for (slot, band) in pixel.iter_mut().zip(inputs) {
+                        let v = band.data[idx];
+                        *slot = (!band.is_no_data(v)).then_some(v as f64);
+                    }
+                    match self.expr.eval(pixel) {
+                        Some(v) if v.is_finite() => v as f32,
+                        _ => no_data,
+                    }
+                },
+            )
+            .collect();
+
+        Ok(RasterBand::from_data(EXPRESSION_BAND_ID, output_name.to_string(), first.rows, first.cols, data, no_data))
+    }
+}
+
+impl RasterDataset {
+    /// Evaluate an expression over this dataset's bands
+    pub fn evaluate(&self, expression: &BandExpression, output_name: &str) -> Result<RasterBand> {
+        let inputs = expression
+            .bands()
+            .iter()
+            .map(|name| self.band_or_err(name))
+            .collect::<Result<Vec<&RasterBand>>>()?;
+        expression.evaluate_inputs(&inputs, output_name)
+    }
+
+    /// Parse, evaluate and add the result as a new band
+    pub fn add_expression_band(&mut self, source: &str, output_name: &str) -> Result<()> {
+        let band = self.evaluate(&BandExpression::parse(source)?, output_name)?;
+        self.add_band(band);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cybersomething_core::models::LatLon;
+
+    fn eval(source: &str) -> Option<f64> {
+        BandExpression::parse(source).unwrap().expr.eval(&[])
+    }
+
+    fn scene() -> RasterDataset {
+        let mut dataset = RasterDataset::new(1, (LatLon::new(32.0, -113.0), LatLon::new(32.1, -112.9)));
+        let band = |id, name: &str, data: Vec<f32>| RasterBand::from_data(id, name.to_string(), 1, 3, data, -9999.0);
+        dataset.add_band(band(1, "Red", vec![0.1, 0.2, -9999.0]));
+        dataset.add_band(band(2, "NIR", vec![0.5, 0.2, 0.4]));
+        dataset.add_band(band(3, "Blue", vec![0.05, 0.2, 0.1]));
+        dataset
+    }
+
+    #[test]
+    fn test_precedence_and_errors() {
+        assert_eq!(eval("1 + 2 * 3"), Some(7.0));
+        assert_eq!(eval("-2 ^ 2"), Some(-4.0));
+        assert_eq!(eval("2 ^ 3 ^ 2"), Some(512.0));
+        assert_eq!(eval("1 < 2 && 3 >= 3 || 0"), Some(1.0));
+        assert_eq!(eval("if(!(1 == 2), max(1, 4, 2), 0) % 3"), Some(1.0));
+        assert_eq!(eval("clamp(1.5e1, 0, 10) - min(.5, 2)"), Some(9.5));
+        assert_eq!(eval("clamp(1, 0 / 0, 2)"), None);
+        assert_eq!(eval("clamp(1, ln(-1), 2)"), None);
+        assert_eq!(eval("clamp(1, 0, 1 / 0)"), None);
+
+        for bad in ["1 +", "(1", "foo(1)", "abs(1, 2)", "1 = 2", "1 2", "[NIR"] {
+            assert!(BandExpression::parse(bad).is_err(), "{bad}");
+        }
+
+        // Nesting is bounded instead of overflowing the stack
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(BandExpression::parse(&nested(MAX_EXPRESSION_DEPTH - 1)).is_ok());
+        assert!(BandExpression::parse(&nested(100_000)).is_err());
+        assert!(BandExpression::parse(&"-".repeat(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_presets_match_ndvi() {
+        let dataset = scene();
+        let ndvi = dataset.evaluate(&BandExpression::preset("ndvi").unwrap(), "NDVI").unwrap();
+        for (a, b) in ndvi.data.iter().zip(&dataset.compute_ndvi().unwrap().data) {
+            assert!((a - b).abs() < 1e-6);
+        }
+
+        let savi = dataset.evaluate(&BandExpression::preset("SAVI").unwrap(), "SAVI").unwrap();
+        assert!((savi.data[0] - 0.4 / 1.1 * 1.5).abs() < 1e-6);
+        let evi = dataset.evaluate(&BandExpression::preset("EVI").unwrap(), "EVI").unwrap();
+        assert!((evi.data[0] - 2.5 * 0.4 / (0.5 + 0.6 - 0.375 + 1.0)).abs() < 1e-6);
+        assert!(dataset.evaluate(&BandExpression::preset("NBR").unwrap(), "NBR").is_err()); // No SWIR2
+        for (name, _) in SPECTRAL_INDICES {
+            assert!(BandExpression::preset(name).is_some());
+        }
+    }
+
+    #[test]
+    fn test_nodata_propagation() {
+        let mut dataset = scene();
+        let expr = BandExpression::parse("NIR / (Red - Blue)").unwrap();
+        let out = dataset.evaluate(&expr, "ratio").unwrap();
+        assert!((out.data[0] - 10.0).abs() < 1e-5);
+        assert_eq!(out.data[1], -9999.0); // Division by zero
+        assert_eq!(out.data[2], -9999.0); // Red is nodata
+
+        dataset.add_expression_band("coalesce(Red, 0) + [NIR]", "filled").unwrap();
+        assert!((dataset.get_band("filled").unwrap().data[2] - 0.4).abs() < 1e-6);
+
+        // Short-circuit guards a nodata branch
+        let guarded = dataset.evaluate(&BandExpression::parse("if(NIR > 0.3, NIR, Red)").unwrap().with_no_data(-1.0), "g").unwrap();
+        assert_eq!(guarded.data, vec![0.5, 0.2, 0.4]);
+        assert_eq!(guarded.no_data_value, -1.0);
+
+        // A zero nodata input does not mask valid zero results
+        let zero_nodata = RasterBand::from_data(1, "NIR".to_string(), 1, 3, vec![0.5, 0.0, 0.2], 0.0);
+        let out = BandExpression::parse("NIR - 0.5").unwrap().evaluate_bands(&[("NIR", &zero_nodata)], "d").unwrap();
+        assert_eq!((out.band_id, out.no_data_value), (EXPRESSION_BAND_ID, EXPRESSION_NO_DATA));
+        assert_eq!(&out.data[..2], &[0.0, EXPRESSION_NO_DATA]);
+    }
+
+    #[test]
+    fn test_threshold_classes_and_bound_bands() {
+        let dataset = scene();
+        let fuel = BandExpression::parse("if(NIR - Red > 0.2, 2, if(NIR > 0.15, 1, 0))").unwrap();
+        assert_eq!(dataset.evaluate(&fuel, "fuel").unwrap().data, vec![2.0, 1.0, -9999.0]);
+
+        let b = |name: &str, v: f32| RasterBand::from_data(1, name.to_string(), 1, 1, vec![v], -9999.0);
+        let (nir_pre, swir_pre, nir_post, swir_post) = (b("B8", 0.5), b("B12", 0.1), b("B8", 0.2), b("B12", 0.3));
+        let dnbr = BandExpression::preset("dNBR")
+            .unwrap()
+            .evaluate_bands(
+                &[("NIR_pre", &nir_pre), ("SWIR2_pre", &swir_pre), ("NIR_post", &nir_post), ("SWIR2_post", &swir_post)],
+                "dNBR",
+            )
+            .unwrap();
+        assert!((dnbr.data[0] - (0.4 / 0.6 + 0.1 / 0.5)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_tiled_evaluation_matches_in_memory() {
+        let band = |name: &str, scale: f32| {
+            let data = (0..35).map(|i| if i == 9 { -9999.0 } else { i as f32 * scale }).collect();
+            RasterBand::from_data(1, name.to_string(), 5, 7, data, -9999.0)
+        };
+        let (nir, red) = (band("NIR", 0.03), band("Red", 0.01));
+        let expr = BandExpression::preset("SAVI").unwrap();
+        let expected = expr.evaluate_bands(&[("NIR", &nir), ("Red", &red)], "SAVI").unwrap();
+
+        crate::tiled::tests::assert_blockwise_matches("tiled-algebra", &[&nir, &red], &[&expected], |i, o| {
+            assert!(expr.evaluate_tiled(&[("NIR", i[0])], o[0]).is_err());
+            expr.evaluate_tiled(&[("NIR", i[0]), ("Red", i[1])], o[0])
+        });
+    }
+}
@@ -0,0 +1,498 @@
+//! Burn severity from pre/post-fire imagery
+//! NBR = (NIR - SWIR2) / (NIR + SWIR2), dNBR = NBR_pre - NBR_post (unscaled),
+//! RdNBR = 1000·dNBR / sqrt(|NBR_pre|) (Miller & Thode, on their ×1000 scale so the
+//! published thresholds apply), USGS dNBR classes, and burn perimeters traced
+//! from connected burned cells.
+
+use crate::algebra::BandExpression;
+use crate::patch::{label_components, Connectivity};
+use crate::raster::{RasterBand, RasterDataset};
+use crate::tiled::{map_blocks, BandSource, TiledBand};
+use crate::vector::{Feature, FeatureCollection, Geometry};
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Severity band nodata (class codes start at 1)
+const SEVERITY_NO_DATA: f32 = 0.0;
+
+/// NBR, dNBR and RdNBR nodata: outside NBR/dNBR [-2, 2] and |RdNBR| ≤ 2000/√0.001
+const INDEX_NO_DATA: f32 = -1.0e5;
+
+/// |NBR_pre| floor so RdNBR stays finite over bare ground
+const RDNBR_MIN_PRE_NBR: f64 = 0.001;
+
+/// USGS (Key & Benson) severity classes on unscaled dNBR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BurnSeverity {
+    EnhancedRegrowthHigh, // dNBR < -0.25
+    EnhancedRegrowthLow,  // -0.25 .. -0.1
+    Unburned,             // -0.1 .. 0.1
+    Low,                  // 0.1 .. 0.27
+    ModerateLow,          // 0.27 .. 0.44
+    ModerateHigh,         // 0.44 .. 0.66
+    High,                 // ≥ 0.66
+}
+
+impl BurnSeverity {
+    pub const ALL: [BurnSeverity; 7] = [
+        Self::EnhancedRegrowthHigh,
+        Self::EnhancedRegrowthLow,
+        Self::Unburned,
+        Self::Low,
+        Self::ModerateLow,
+        Self::ModerateHigh,
+        Self::High,
+    ];
+
+    pub fn from_dnbr(dnbr: f64) -> Self {
+        match dnbr {
+            d if d < -0.25 => Self::EnhancedRegrowthHigh,
+            d if d < -0.1 => Self::EnhancedRegrowthLow,
+            d if d < 0.1 => Self::Unburned,
+            d if d < 0.27 => Self::Low,
+            d if d < 0.44 => Self::ModerateLow,
+            d if d < 0.66 => Self::ModerateHigh,
+            _ => Self::High,
+        }
+    }
+
+    /// Raster class code (1-7)
+    pub fn code(&self) -> u8 {
+        *self as u8 + 1
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.get((code as usize).checked_sub(1)?).copied()
+    }
+
+    pub fn is_burned(&self) -> bool {
+        *self >= Self::Low
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::EnhancedRegrowthHigh => "enhanced_regrowth_high",
+            Self::EnhancedRegrowthLow => "enhanced_regrowth_low",
+            Self::Unburned => "unburned",
+            Self::Low => "low",
+            Self::ModerateLow => "moderate_low",
+            Self::ModerateHigh => "moderate_high",
+            Self::High => "high",
+        }
+    }
+}
+
+/// Burn perimeter extraction settings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PerimeterConfig {
+    pub connectivity: Connectivity,
+    pub min_severity: BurnSeverity, // Cells at or above this class are burned
+    pub min_cells: usize,           // Smaller burn scars are dropped
+}
+
+impl Default for PerimeterConfig {
+    fn default() -> Self {
+        Self {
+            connectivity: Connectivity::Eight,
+            min_severity: BurnSeverity::Low,
+            min_cells: 1,
+        }
+    }
+}
+
+/// Area burned in one severity class
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeverityArea {
+    pub severity: BurnSeverity,
+    pub cells: usize,
+    pub area_ha: f64,
+}
+
+/// NBR_pre, NBR_post, dNBR, RdNBR and Severity bands on the post-fire grid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BurnSeverityMap {
+    pub dataset: RasterDataset,
+}
+
+impl BurnSeverityMap {
+    /// Compute from pre/post-fire datasets with `NIR` and `SWIR2` bands on one pixel grid
+    pub fn compute(pre: &RasterDataset, post: &RasterDataset) -> Result<Self> {
+        let pre_nir = pre.band_or_err("NIR")?;
+        let post_nir = post.band_or_err("NIR")?;
+        if !pre.is_aligned_with(pre_nir, post, post_nir) {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!(
+                    "pre-fire dataset {} and post-fire dataset {} are on different grids; align them first",
+                    pre.dataset_id, post.dataset_id
+                ),
+            });
+        }
+
+        let bands = severity_bands(pre_nir, pre.band_or_err("SWIR2")?, post_nir, post.band_or_err("SWIR2")?)?;
+        let (rows, cols) = (bands[0].rows, bands[0].cols);
+        let mut dataset = RasterDataset::from_geotransform(post.dataset_id, post.crs, post.geotransform_for(post_nir), rows, cols);
+        for band in bands {
+            dataset.add_band(band);
+        }
+        Ok(Self { dataset })
+    }
+
+    pub fn dnbr(&self) -> Result<&RasterBand> {
+        self.dataset.band_or_err("dNBR")
+    }
+
+    pub fn rdnbr(&self) -> Result<&RasterBand> {
+        self.dataset.band_or_err("RdNBR")
+    }
+
+    pub fn severity_band(&self) -> Result<&RasterBand> {
+        self.dataset.band_or_err("Severity")
+    }
+
+    pub fn severity_at(&self, row: usize, col: usize) -> Option<BurnSeverity> {
+        class_at(self.severity_band().ok()?, row, col)
+    }
+
+    fn cell_area_ha(&self, severity: &RasterBand) -> f64 {
+        let (width, height) = self.dataset.pixel_size_m(severity);
+        width * height / 10_000.0
+    }
+
+    /// Cells and hectares per severity class (classes with no cells omitted)
+    pub fn class_areas(&self) -> Result<Vec<SeverityArea>> {
+        let severity = self.severity_band()?;
+        let mut cells = [0usize; 7];
+        for &code in &severity.data {
+            if let Some(severity) = BurnSeverity::from_code(code as u8) {
+                cells[severity as usize] += 1;
+            }
+        }
+        let cell_area_ha = self.cell_area_ha(severity);
+        Ok(BurnSeverity::ALL
+            .iter()
+            .zip(cells)
+            .filter(|(_, n)| *n > 0)
+            .map(|(&severity, n)| SeverityArea { severity, cells: n, area_ha: n as f64 * cell_area_ha })
+            .collect())
+    }
+
+    /// Burn scars as polygons, largest first, with area and severity statistics.
+    /// Polygons trace the exterior only: unburned islands lie inside the geometry
+    /// but are excluded from `cells`, `area_ha` and the severity statistics.
+    pub fn perimeters(&self, collection_id: u32, config: &PerimeterConfig) -> Result<FeatureCollection> {
+        let severity = self.severity_band()?;
+        let (dnbr, rdnbr) = (self.dnbr()?, self.rdnbr()?);
+        let burned = |r: usize, c: usize| class_at(severity, r, c).is_some_and(|s| s >= config.min_severity);
+
+        let mut scars = label_components(severity.rows, severity.cols, config.connectivity, burned);
+        scars.retain(|cells| cells.len() >= config.min_cells);
+        scars.sort_by_key(|cells| std::cmp::Reverse(cells.len()));
+
+        let cell_area_ha = self.cell_area_ha(severity);
+        let mut collection = FeatureCollection::new(collection_id, "burn_perimeters".to_string());
+        for (rank, cells) in scars.iter().enumerate() {
+            let ring = trace_exterior(cells, severity.cols, config.connectivity)
+                .into_iter()
+                .map(|(r, c)| self.dataset.pixel_to_coord(severity, r as f64, c as f64))
+                .collect();
+
+            let mean = |band: &RasterBand| {
+                let values: Vec<f64> = cells
+                    .iter()
+                    .filter_map(|&(r, c)| band.get_pixel(r, c).filter(|&v| !band.is_no_data(v)))
+                    .map(|v| v as f64)
+                    .collect();
+                values.iter().sum::<f64>() / values.len().max(1) as f64
+            };
+            let max_dnbr = cells.iter().filter_map(|&(r, c)| dnbr.get_pixel(r, c)).fold(f32::NEG_INFINITY, f32::max);
+
+            let mut class_cells: HashMap<BurnSeverity, usize> = HashMap::new();
+            for &(r, c) in cells {
+                if let Some(s) = class_at(severity, r, c) {
+                    *class_cells.entry(s).or_default() += 1;
+                }
+            }
+            let dominant = class_cells.iter().max_by_key(|(s, n)| (**n, **s)).map(|(s, _)| *s);
+
+            let mut feature = Feature::new(rank as u32 + 1, Geometry::Polygon(ring));
+            feature.set_property("cells".to_string(), cells.len().to_string());
+            feature.set_property("area_ha".to_string(), format!("{:.4}", cells.len() as f64 * cell_area_ha));
+            feature.set_property("mean_dnbr".to_string(), format!("{:.4}", mean(dnbr)));
+            feature.set_property("max_dnbr".to_string(), format!("{max_dnbr:.4}"));
+            feature.set_property("mean_rdnbr".to_string(), format!("{:.4}", mean(rdnbr)));
+            if let Some(dominant) = dominant {
+                feature.set_property("dominant_severity".to_string(), dominant.label().to_string());
+            }
+            for (s, n) in class_cells {
+                feature.set_property(format!("{}_ha", s.label()), format!("{:.4}", n as f64 * cell_area_ha));
+            }
+            collection.add_feature(feature);
+        }
+        Ok(collection)
+    }
+}
+
+fn class_at(severity: &RasterBand, row: usize, col: usize) -> Option<BurnSeverity> {
+    BurnSeverity::from_code(severity.get_pixel(row, col)? as u8)
+}
+
+/// NBR_pre, NBR_post, dNBR, RdNBR and Severity bands (ids 1-5) from same-shape NIR/SWIR2 bands
+fn severity_bands(pre_nir: &RasterBand, pre_swir2: &RasterBand, post_nir: &RasterBand, post_swir2: &RasterBand) -> Result<[RasterBand; 5]> {
+    let nbr = BandExpression::preset("NBR").expect("built-in index").with_no_data(INDEX_NO_DATA);
+    let nbr_pre = nbr.evaluate_bands(&[("NIR", pre_nir), ("SWIR2", pre_swir2)], "NBR_pre")?;
+    let nbr_post = nbr.evaluate_bands(&[("NIR", post_nir), ("SWIR2", post_swir2)], "NBR_post")?;
+    let dnbr = BandExpression::parse("NBR_pre - NBR_post")?
+        .with_no_data(INDEX_NO_DATA)
+        .evaluate_bands(&[("NBR_pre", &nbr_pre), ("NBR_post", &nbr_post)], "dNBR")?;
+    let rdnbr = BandExpression::parse(&format!("1000 * dNBR / sqrt(max(abs(NBR_pre), {RDNBR_MIN_PRE_NBR}))"))?
+        .with_no_data(INDEX_NO_DATA)
+        .evaluate_bands(&[("dNBR", &dnbr), ("NBR_pre", &nbr_pre)], "RdNBR")?;
+
+    let classes = dnbr
+        .data
+        .iter()
+        .map(|&d| if dnbr.is_no_data(d) { SEVERITY_NO_DATA } else { BurnSeverity::from_dnbr(d as f64).code() as f32 })
+        .collect();
+    let severity = RasterBand::from_data(5, "Severity".to_string(), dnbr.rows, dnbr.cols, classes, SEVERITY_NO_DATA);
+
+    let mut bands = [nbr_pre, nbr_post, dnbr, rdnbr, severity];
+    for (band_id, band) in bands.iter_mut().enumerate() {
+        band.band_id = band_id as u32 + 1;
+    }
+    Ok(bands)
+}
+
+/// Compute dNBR, RdNBR and severity classes block by block into tiled bands
+/// (scenes larger than memory); `pre` and `post` are (NIR, SWIR2) on one pixel grid.
+/// Perimeters need whole scars and are traced from an in-memory `BurnSeverityMap`.
+pub fn compute_severity_tiled(
+    pre: (&dyn BandSource, &dyn BandSource),
+    post: (&dyn BandSource, &dyn BandSource),
+    dnbr: &TiledBand,
+    rdnbr: &TiledBand,
+    severity: &TiledBand,
+) -> Result<()> {
+    map_blocks(&[pre.0, pre.1, post.0, post.1], &[dnbr, rdnbr, severity], |w| {
+        let [_, _, dnbr, rdnbr, severity] = severity_bands(&w[0], &w[1], &w[2], &w[3])?;
+        Ok(vec![dnbr, rdnbr, severity])
+    })
+}
+
+/// Exterior ring of a cell component as pixel-corner (row, col) vertices.
+///
+/// Cell sides facing outside the component become directed edges running
+/// clockwise on screen; edges are chained into rings (at a corner shared by two
+/// diagonal cells the ring crosses over for 8-connectivity and stays on the
+/// same cell for 4-connectivity) and the ring enclosing the most area is kept.
+fn trace_exterior(cells: &[(usize, usize)], cols: usize, connectivity: Connectivity) -> Vec<(usize, usize)> {
+    let member: std::collections::HashSet<usize> = cells.iter().map(|&(r, c)| r * cols + c).collect();
+    let inside = |r: i64, c: i64| r >= 0 && c >= 0 && (c as usize) < cols && member.contains(&(r as usize * cols + c as usize));
+
+    let mut outgoing: HashMap<(i64, i64), Vec<(i64, i64)>> = HashMap::new();
+    for &(r, c) in cells {
+        let (r, c) = (r as i64, c as i64);
+        let sides = [
+            ((r - 1, c), (r, c), (r, c + 1)),         // Top, heading right
+            ((r, c + 1), (r, c + 1), (r + 1, c + 1)), // Right, heading down
+            ((r + 1, c), (r + 1, c + 1), (r + 1, c)), // Bottom, heading left
+            ((r, c - 1), (r + 1, c), (r, c)),         // Left, heading up
+        ];
+        for (neighbour, from, to) in sides {
+            if !inside(neighbour.0, neighbour.1) {
+                outgoing.entry(from).or_default().push(to);
+            }
+        }
+    }
+
+    // Start each loop at the smallest remaining vertex: for the exterior this is the top-left
+    // corner of the top-left cell, never a saddle, so the loop cannot close early at a pinch
+    let mut best: (f64, Vec<(i64, i64)>) = (0.0, Vec::new());
+    while let Some(&start) = outgoing.iter().filter(|(_, v)| !v.is_empty()).map(|(k, _)| k).min() {
+        let mut ring = vec![start];
+        let mut current = outgoing.get_mut(&start).and_then(|v| v.pop()).expect("edge");
+        let mut previous = start;
+        while current != start {
+            let heading = (current.0 - previous.0, current.1 - previous.1);
+            let candidates = outgoing.get_mut(&current).expect("closed boundary");
+            // Saddle corner: cross < 0 turns onto the diagonal cell
+            let pick = if candidates.len() > 1 {
+                let cross = |to: &(i64, i64)| heading.1 * (to.0 - current.0) - heading.0 * (to.1 - current.1);
+                let crossing = connectivity == Connectivity::Eight;
+                candidates.iter().position(|to| (cross(to) < 0) == crossing).unwrap_or(0)
+            } else {
+                0
+            };
+            let next = candidates.swap_remove(pick);
+            ring.push(current);
+            (previous, current) = (current, next);
+        }
+
+        let area = ring
+            .iter()
+            .zip(ring.iter().cycle().skip(1))
+            .map(|(a, b)| (a.1 * b.0 - b.1 * a.0) as f64)
+            .sum::<f64>()
+            .abs()
+            / 2.0;
+        if area > best.0 {
+            best = (area, ring);
+        }
+    }
+
+    // Drop vertices in the middle of straight runs
+    let ring = best.1;
+    let n = ring.len();
+    (0..n)
+        .filter(|&i| {
+            let (a, b, c) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+            (b.0 - a.0) * (c.1 - b.1) != (b.1 - a.1) * (c.0 - b.0)
+        })
+        .map(|i| (ring[i].0 as usize, ring[i].1 as usize))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::{CoordinateSystem, GeoTransform};
+
+    /// 6x6 UTM 12N scene at 30 m; `cells` override (row, col, NIR, SWIR2)
+    fn scene(id: u32, cells: &[(usize, usize, f32, f32)]) -> RasterDataset {
+        let crs = CoordinateSystem::UTM { zone: 12, is_north: true };
+        let mut dataset = RasterDataset::from_geotransform(id, crs, GeoTransform::north_up(300_000.0, 3_600_000.0, 30.0, 30.0), 6, 6);
+        let (mut nir, mut swir) = (vec![0.5; 36], vec![0.1; 36]);
+        for &(r, c, n, s) in cells {
+            (nir[r * 6 + c], swir[r * 6 + c]) = (n, s);
+        }
+        dataset.add_band(RasterBand::from_data(1, "NIR".to_string(), 6, 6, nir, -9999.0));
+        dataset.add_band(RasterBand::from_data(2, "SWIR2".to_string(), 6, 6, swir, -9999.0));
+        dataset
+    }
+
+    #[test]
+    fn test_usgs_classes() {
+        assert_eq!(BurnSeverity::from_dnbr(-0.3), BurnSeverity::EnhancedRegrowthHigh);
+        assert_eq!(BurnSeverity::from_dnbr(0.0), BurnSeverity::Unburned);
+        assert_eq!(BurnSeverity::from_dnbr(0.1), BurnSeverity::Low);
+        assert_eq!(BurnSeverity::from_dnbr(0.5), BurnSeverity::ModerateHigh);
+        assert_eq!(BurnSeverity::from_dnbr(0.9), BurnSeverity::High);
+        for s in BurnSeverity::ALL {
+            assert_eq!(BurnSeverity::from_code(s.code()), Some(s));
+        }
+        assert!(!BurnSeverity::Unburned.is_burned() && BurnSeverity::Low.is_burned());
+    }
+
+    #[test]
+    fn test_dnbr_rdnbr_and_class_areas() {
+        let pre = scene(1, &[(5, 5, -9999.0, 0.1)]);
+        let post = scene(2, &[(1, 1, 0.2, 0.3), (1, 2, 0.3, 0.2), (4, 4, 0.45, 0.1)]);
+        let map = BurnSeverityMap::compute(&pre, &post).unwrap();
+
+        let pre_nbr = 0.4 / 0.6;
+        assert!((map.dnbr().unwrap().get_pixel(1, 1).unwrap() as f64 - (pre_nbr + 0.2)).abs() < 1e-5);
+        let rdnbr = map.rdnbr().unwrap().get_pixel(1, 1).unwrap() as f64;
+        assert!((rdnbr - 1000.0 * (pre_nbr + 0.2) / pre_nbr.sqrt()).abs() < 1e-2);
+        assert_eq!(map.severity_at(1, 1), Some(BurnSeverity::High));
+        assert_eq!(map.severity_at(1, 2), Some(BurnSeverity::ModerateHigh)); // 0.667 - 0.2
+        assert_eq!(map.severity_at(4, 4), Some(BurnSeverity::Unburned)); // 0.667 - 0.636
+        assert_eq!(map.severity_at(5, 5), None); // Pre-fire nodata
+
+        let areas = map.class_areas().unwrap();
+        let unburned = areas.iter().find(|a| a.severity == BurnSeverity::Unburned).unwrap();
+        assert_eq!(unburned.cells, 33);
+        assert!((unburned.area_ha - 33.0 * 0.09).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_perimeters_trace_scars() {
+        // L-shaped scar (3 cells) touching a single cell diagonally, plus a separate cell
+        let post = scene(2, &[(1, 1, 0.1, 0.4), (1, 2, 0.1, 0.4), (2, 1, 0.1, 0.4), (3, 2, 0.1, 0.4), (4, 5, 0.1, 0.4)]);
+        let map = BurnSeverityMap::compute(&scene(1, &[]), &post).unwrap();
+        let band = map.severity_band().unwrap();
+
+        let eight = map.perimeters(7, &PerimeterConfig::default()).unwrap();
+        assert_eq!(eight.features.len(), 2);
+        let scar = &eight.features[0];
+        assert_eq!(scar.feature_id, 1);
+        assert_eq!(scar.get_property("cells").map(String::as_str), Some("4"));
+        assert_eq!(scar.get_property("area_ha").map(String::as_str), Some("0.3600"));
+        assert_eq!(scar.get_property("dominant_severity").map(String::as_str), Some("high"));
+        for (r, c, expected) in [(1, 1, true), (2, 1, true), (3, 2, true), (2, 2, false), (0, 0, false)] {
+            let center = map.dataset.pixel_center(band, r, c);
+            assert_eq!(scar.geometry.contains_point(&center), expected, "({r}, {c})");
+        }
+        let Geometry::Polygon(ring) = &eight.features[1].geometry else { panic!() };
+        assert_eq!(ring.len(), 4); // Single cell -> square
+
+        let four = map.perimeters(7, &PerimeterConfig { connectivity: Connectivity::Four, min_cells: 2, ..Default::default() }).unwrap();
+        assert_eq!(four.features.len(), 1);
+        let Geometry::Polygon(ring) = &four.features[0].geometry else { panic!() };
+        assert_eq!(ring.len(), 6); // L-shape
+    }
+
+    #[test]
+    fn test_island_inside_exterior_but_not_counted() {
+        // 3x3 burned ring around an unburned cell at (2, 2)
+        let ring: Vec<(usize, usize, f32, f32)> = (1..4)
+            .flat_map(|r| (1..4).map(move |c| (r, c)))
+            .filter(|&(r, c)| (r, c) != (2, 2))
+            .map(|(r, c)| (r, c, 0.1, 0.4))
+            .collect();
+        let map = BurnSeverityMap::compute(&scene(1, &[]), &scene(2, &ring)).unwrap();
+        let scar = &map.perimeters(7, &PerimeterConfig::default()).unwrap().features[0];
+        assert_eq!(scar.get_property("cells").map(String::as_str), Some("8"));
+        let island = map.dataset.pixel_center(map.severity_band().unwrap(), 2, 2);
+        assert!(scar.geometry.contains_point(&island));
+    }
+
+    #[test]
+    fn test_tiled_severity_matches_in_memory() {
+        let pre = scene(1, &[(5, 5, -9999.0, 0.1)]);
+        let post = scene(2, &[(1, 1, 0.2, 0.3), (4, 4, 0.1, 0.4), (4, 5, 0.3, 0.2)]);
+        let map = BurnSeverityMap::compute(&pre, &post).unwrap();
+        let band = |dataset: &RasterDataset, name: &str| dataset.get_band(name).unwrap().clone();
+        let inputs = [band(&pre, "NIR"), band(&pre, "SWIR2"), band(&post, "NIR"), band(&post, "SWIR2")];
+
+        crate::tiled::tests::assert_blockwise_matches(
+            "tiled-burn",
+            &inputs.iter().collect::<Vec<_>>(),
+            &[map.dnbr().unwrap(), map.rdnbr().unwrap(), map.severity_band().unwrap()],
+            |i, o| compute_severity_tiled((i[0], i[1]), (i[2], i[3]), o[0], o[1], o[2]),
+        );
+    }
+
+    #[test]
+    fn test_zero_indices_stay_valid_with_zero_nodata_inputs() {
+        // NIR == SWIR2 gives NBR = dNBR = RdNBR = 0, the inputs' nodata value
+        let zero_nodata = |mut dataset: RasterDataset| {
+            dataset.bands.iter_mut().for_each(|b| b.no_data_value = 0.0);
+            dataset
+        };
+        let pre = zero_nodata(scene(1, &[(2, 2, 0.3, 0.3)]));
+        let post = zero_nodata(scene(2, &[(2, 2, 0.3, 0.3)]));
+        let mut map = BurnSeverityMap::compute(&pre, &post).unwrap();
+
+        let dnbr = map.dnbr().unwrap();
+        assert_eq!(dnbr.no_data_value, INDEX_NO_DATA);
+        assert_eq!(dnbr.get_pixel(2, 2), Some(0.0));
+        assert_eq!(map.rdnbr().unwrap().get_pixel(2, 2), Some(0.0));
+        assert_eq!(map.severity_at(2, 2), Some(BurnSeverity::Unburned));
+
+        map.dataset.bands.retain(|b| b.band_name != "Severity");
+        assert!(map.class_areas().is_err() && map.perimeters(1, &PerimeterConfig::default()).is_err());
+        assert_eq!(map.severity_at(2, 2), None);
+    }
+
+    #[test]
+    fn test_requires_aligned_nir_swir() {
+        let pre = scene(1, &[]);
+        let mut shifted = scene(2, &[]);
+        shifted.geotransform = Some(GeoTransform::north_up(300_030.0, 3_600_000.0, 30.0, 30.0));
+        assert!(BurnSeverityMap::compute(&pre, &shifted).is_err());
+
+        let mut no_swir = scene(2, &[]);
+        no_swir.bands.retain(|b| b.band_name != "SWIR2");
+        assert!(BurnSeverityMap::compute(&pre, &no_swir).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Defensible-space compliance checking
+//! Compares a measured grass-height raster (cm) against distance-banded
+//! height caps around structure footprints and reports violations per parcel.
+
+use crate::raster::{RasterBand, RasterDataset};
+use crate::vector::{FeatureCollection, Geometry};
+use cybersomething_core::math::defensible::GrassHeightRuleSet;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Band id assigned to distance-to-structure rasters
+pub const STRUCTURE_DISTANCE_BAND_ID: u32 = 1020;
+
+/// Out-of-compliance pixels under a single rule within a parcel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Violation {
+    pub rule_index: usize,
+    pub rule: String,              // Human-readable rule that applied
+    pub max_measured_cm: f64,      // Tallest grass observed in violation
+    pub max_exceedance_cm: f64,    // Largest height over the cap
+    pub pixel_count: usize,
+    pub area_m2: f64,
+}
+
+/// Compliance result for one parcel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParcelCompliance {
+    pub parcel_id: u32,
+    pub checked_pixels: usize,     // Regulated pixels inside the parcel
+    pub violations: Vec<Violation>,
+}
+
+impl ParcelCompliance {
+    pub fn is_compliant(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn violation_area_m2(&self) -> f64 {
+        self.violations.iter().map(|v| v.area_m2).sum()
+    }
+}
+
+/// Compliance results for all parcels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    pub rule_set: String,
+    pub parcels: Vec<ParcelCompliance>,
+}
+
+impl ComplianceReport {
+    /// Parcels with at least one violation
+    pub fn non_compliant(&self) -> Vec<&ParcelCompliance> {
+        self.parcels.iter().filter(|p| !p.is_compliant()).collect()
+    }
+}
+
+/// Grass-height compliance engine for a rule set
+pub struct ComplianceChecker {
+    rules: GrassHeightRuleSet,
+}
+
+impl ComplianceChecker {
+    pub fn new(rules: GrassHeightRuleSet) -> Self {
+        Self { rules }
+    }
+
+    /// Per-pixel distance (m) to the nearest structure footprint
+    pub fn distance_to_structures(&self, dataset: &RasterDataset, band: &RasterBand, structures: &[Geometry]) -> RasterBand {
+        let distances = (0..band.rows * band.cols)
+            .into_par_iter()
+            .map(|idx| {
+                let center = dataset.pixel_center(band, idx / band.cols, idx % band.cols);
+                structures
+                    .iter()
+                    .map(|s| s.distance_to_point_m(&center))
+                    .fold(f64::INFINITY, f64::min) as f32
+            })
+            .collect();
+
+        RasterBand::from_data(
+            STRUCTURE_DISTANCE_BAND_ID,
+            "StructureDistance".to_string(),
+            band.rows,
+            band.cols,
+            distances,
+            band.no_data_value,
+        )
+    }
+
+    /// Check measured grass heights (cm) in each parcel polygon
+    pub fn check(
+        &self,
+        dataset: &RasterDataset,
+        grass_height_cm: &RasterBand,
+        structures: &[Geometry],
+        parcels: &FeatureCollection,
+    ) -> ComplianceReport {
+        let distances = self.distance_to_structures(dataset, grass_height_cm, structures);
+        let (width_m, height_m) = dataset.pixel_size_m(grass_height_cm);
+        let pixel_area_m2 = width_m * height_m;
+
+        let cols = grass_height_cm.cols;
+        let parcels = parcels
+            .features
+            .par_iter()
+            .map(|parcel| {
+                let mut checked_pixels = 0;
+                let mut by_rule: BTreeMap<usize, Violation> = BTreeMap::new();
+
+                // Only pixels under the parcel's bounding box can fall inside it
+                let window = parcel.geometry.bounds().and_then(|bounds| dataset.window_for(grass_height_cm, bounds));
+                let pixels = window.iter().flat_map(|w| {
+                    (w.row_off..w.row_off + w.rows).flat_map(move |row| (w.col_off..w.col_off + w.cols).map(move |col| (row, col)))
+                });
+
+                for (row, col) in pixels {
+                    let idx = row * cols + col;
+                    let measured = grass_height_cm.data[idx];
+                    if grass_height_cm.is_no_data(measured) {
+                        continue;
+                    }
+                    let center = dataset.pixel_center(grass_height_cm, row, col);
+                    if !parcel.geometry.contains_point(&center) {
+                        continue;
+                    }
+                    // Pixels under a footprint are the structure, not surrounding grass
+                    let distance_m = distances.data[idx] as f64;
+                    if distance_m <= 0.0 {
+                        continue;
+                    }
+                    let Some((rule_index, cap_cm)) = self.rules.limit_at(distance_m) else {
+                        continue;
+                    };
+
+                    checked_pixels += 1;
+                    let measured = measured as f64;
+                    if measured <= cap_cm {
+                        continue;
+                    }
+
+                    let violation = by_rule.entry(rule_index).or_insert_with(|| Violation {
+                        rule_index,
+                        rule: self.rules.rules[rule_index].describe(),
+                        max_measured_cm: 0.0,
+                        max_exceedance_cm: 0.0,
+                        pixel_count: 0,
+                        area_m2: 0.0,
+                    });
+                    violation.max_measured_cm = violation.max_measured_cm.max(measured);
+                    violation.max_exceedance_cm = violation.max_exceedance_cm.max(measured - cap_cm);
+                    violation.pixel_count += 1;
+                    violation.area_m2 += pixel_area_m2;
+                }
+
+                ParcelCompliance {
+                    parcel_id: parcel.feature_id,
+                    checked_pixels,
+                    violations: by_rule.into_values().collect(),
+                }
+            })
+            .collect();
+
+        ComplianceReport {
+            rule_set: self.rules.name.clone(),
+            parcels,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::Feature;
+    use cybersomething_core::models::LatLon;
+
+    /// 10×10 raster of ~1.1 m pixels with a structure in the top-left corner
+    fn scene(grass_cm: f32) -> (RasterDataset, RasterBand, Vec<Geometry>, FeatureCollection) {
+        let sw = LatLon::new(33.0, -112.0);
+        let ne = LatLon::new(33.0001, -111.99988);
+        let dataset = RasterDataset::new(1, (sw, ne));
+        let band = RasterBand::from_data(1, "GrassHeight".to_string(), 10, 10, vec![grass_cm; 100], -9999.0);
+
+        let structure = Geometry::Polygon(vec![
+            LatLon::new(33.0001, -112.0),
+            LatLon::new(33.0001, -111.99998),
+            LatLon::new(33.00008, -111.99998),
+            LatLon::new(33.00008, -112.0),
+        ]);
+
+        let mut parcels = FeatureCollection::new(1, "Parcels".to_string());
+        parcels.add_feature(Feature::new(7, Geometry::Polygon(vec![sw, LatLon::new(ne.latitude, sw.longitude), ne, LatLon::new(sw.latitude, ne.longitude)])));
+
+        (dataset, band, vec![structure], parcels)
+    }
+
+    #[test]
+    fn test_compliant_parcel() {
+        let (dataset, band, structures, parcels) = scene(5.0);
+        let report = ComplianceChecker::new(GrassHeightRuleSet::banded_default()).check(&dataset, &band, &structures, &parcels);
+
+        assert_eq!(report.parcels.len(), 1);
+        assert!(report.parcels[0].is_compliant());
+        assert_eq!(report.parcels[0].checked_pixels, 96); // 2×2 pixels lie under the structure
+    }
+
+    #[test]
+    fn test_footprint_pixels_not_checked() {
+        // Roof pixels read tall but are not grass around the structure
+        let (dataset, mut band, structures, parcels) = scene(5.0);
+        for idx in [0, 1, 10, 11] {
+            band.data[idx] = 40.0;
+        }
+        let report = ComplianceChecker::new(GrassHeightRuleSet::banded_default()).check(&dataset, &band, &structures, &parcels);
+        assert!(report.parcels[0].is_compliant());
+    }
+
+    #[test]
+    fn test_violations_reported_per_rule() {
+        // 8 cm breaks only the 0–1.5 m (6 cm) band
+        let (dataset, band, structures, parcels) = scene(8.0);
+        let report = ComplianceChecker::new(GrassHeightRuleSet::banded_default()).check(&dataset, &band, &structures, &parcels);
+
+        let parcel = &report.parcels[0];
+        assert_eq!(parcel.parcel_id, 7);
+        assert_eq!(parcel.violations.len(), 1);
+        let violation = &parcel.violations[0];
+        assert_eq!(violation.rule_index, 0);
+        assert_eq!(violation.rule, "0-1.5 m: 6 cm");
+        assert!((violation.max_exceedance_cm - 2.0).abs() < 1e-6);
+        assert!(violation.pixel_count > 0 && violation.pixel_count < 100);
+        assert!(violation.area_m2 > 0.0);
+        assert_eq!(report.non_compliant().len(), 1);
+    }
+
+    #[test]
+    fn test_distance_raster() {
+        let (dataset, band, structures, _) = scene(5.0);
+        let checker = ComplianceChecker::new(GrassHeightRuleSet::linear_default());
+        let distances = checker.distance_to_structures(&dataset, &band, &structures);
+
+        assert!(distances.get_pixel(0, 0).unwrap() < 1.0);
+        assert!(distances.get_pixel(9, 9).unwrap() > 10.0);
+    }
+}
@@ -0,0 +1,582 @@
+//! GeoTIFF import/export for raster datasets
+//! Single or multi-band (pixel-interleaved) u8/i16/f32 rasters, striped or
+//! tiled, uncompressed/deflate/LZW, with GDAL nodata and band descriptions
+//! and GeoKeys for EPSG:4326 and WGS84 UTM zones.
+
+use crate::raster::{CoordinateSystem, GeoTransform, RasterBand, RasterDataset};
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::encoder::TiffEncoder;
+use tiff::tags::Tag;
+use tiff::TiffError;
+
+/// GDAL_METADATA XML tag (band descriptions)
+const GDAL_METADATA_TAG: u16 = 42112;
+/// ProjectedCSTypeGeoKey / GeographicTypeGeoKey value for a user-defined CRS
+const USER_DEFINED: u16 = 32767;
+
+// GeoKey ids
+const GT_MODEL_TYPE: u16 = 1024;
+const GT_RASTER_TYPE: u16 = 1025;
+const GEOGRAPHIC_TYPE: u16 = 2048;
+const PROJECTED_CS_TYPE: u16 = 3072;
+const RASTER_PIXEL_IS_POINT: u16 = 2;
+
+/// On-disk sample type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SampleType {
+    U8,
+    I16,
+    F32,
+}
+
+impl SampleType {
+    fn bits(&self) -> u16 {
+        match self {
+            SampleType::U8 => 8,
+            SampleType::I16 => 16,
+            SampleType::F32 => 32,
+        }
+    }
+
+    /// TIFF SampleFormat (1 = uint, 2 = int, 3 = float)
+    fn sample_format(&self) -> u16 {
+        match self {
+            SampleType::U8 => 1,
+            SampleType::I16 => 2,
+            SampleType::F32 => 3,
+        }
+    }
+
+    /// Value as stored (integers are rounded and saturated)
+    fn quantize(&self, value: f32) -> f32 {
+        match self {
+            SampleType::U8 => value.round().clamp(0.0, u8::MAX as f32),
+            SampleType::I16 => value.round().clamp(i16::MIN as f32, i16::MAX as f32),
+            SampleType::F32 => value,
+        }
+    }
+
+    /// Whether a value is stored exactly (integers: whole and in range)
+    fn represents(&self, value: f32) -> bool {
+        match self {
+            SampleType::F32 => true,
+            _ => value.is_finite() && self.quantize(value) == value,
+        }
+    }
+
+    fn push_bytes(&self, value: f32, out: &mut Vec<u8>) {
+        let value = self.quantize(value);
+        match self {
+            SampleType::U8 => out.push(value as u8),
+            SampleType::I16 => out.extend_from_slice(&(value as i16).to_ne_bytes()),
+            SampleType::F32 => out.extend_from_slice(&value.to_ne_bytes()),
+        }
+    }
+}
+
+/// Chunk compression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TiffCompression {
+    None,
+    Deflate,
+    Lzw,
+}
+
+impl TiffCompression {
+    fn code(&self) -> u16 {
+        match self {
+            TiffCompression::None => 1,
+            TiffCompression::Lzw => 5,
+            TiffCompression::Deflate => 8,
+        }
+    }
+
+    fn compress(&self, raw: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            TiffCompression::None => Ok(raw),
+            TiffCompression::Deflate => {
+                let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&raw)?;
+                Ok(encoder.finish()?)
+            }
+            TiffCompression::Lzw => weezl::encode::Encoder::with_tiff_size_switch(weezl::BitOrder::Msb, 8)
+                .encode(&raw)
+                .map_err(|e| CybersomethingError::SerializationError(format!("GeoTIFF LZW: {e}"))),
+        }
+    }
+}
+
+/// Chunk layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TiffLayout {
+    Striped { rows_per_strip: u32 },
+    Tiled { tile_size: u32 }, // Square tiles, multiple of 16
+}
+
+/// GeoTIFF export settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoTiffOptions {
+    pub sample_type: SampleType,
+    pub compression: TiffCompression,
+    pub layout: TiffLayout,
+    pub no_data: Option<f32>, // Stored nodata; None = the first band's
+}
+
+impl Default for GeoTiffOptions {
+    fn default() -> Self {
+        Self {
+            sample_type: SampleType::F32,
+            compression: TiffCompression::Deflate,
+            layout: TiffLayout::Striped { rows_per_strip: 64 },
+            no_data: None,
+        }
+    }
+}
+
+impl GeoTiffOptions {
+    pub fn with_sample_type(mut self, sample_type: SampleType) -> Self {
+        self.sample_type = sample_type;
+        self
+    }
+
+    pub fn with_compression(mut self, compression: TiffCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: TiffLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Nodata value to store, e.g. 255 when writing -9999-nodata bands as U8
+    pub fn with_no_data(mut self, no_data: f32) -> Self {
+        self.no_data = Some(no_data);
+        self
+    }
+}
+
+fn tiff_error(err: TiffError) -> CybersomethingError {
+    match err {
+        TiffError::IoError(e) => CybersomethingError::IO(e),
+        other => CybersomethingError::SerializationError(format!("GeoTIFF: {other}")),
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> CybersomethingError {
+    CybersomethingError::DataValidationError { reason: reason.into() }
+}
+
+/// Read the first image of a GeoTIFF into a dataset (one band per sample)
+pub fn read_geotiff<R: Read + Seek>(reader: R, dataset_id: u32) -> Result<RasterDataset> {
+    let mut decoder = Decoder::new(reader).map_err(tiff_error)?;
+    let (cols, rows) = decoder.dimensions().map_err(tiff_error)?;
+    let (rows, cols) = (rows as usize, cols as usize);
+
+    if decoder.find_tag_unsigned::<u16>(Tag::PlanarConfiguration).map_err(tiff_error)? == Some(2) {
+        return Err(invalid("planar (band-sequential) GeoTIFFs are not supported"));
+    }
+    let samples = decoder
+        .find_tag_unsigned::<u16>(Tag::SamplesPerPixel)
+        .map_err(tiff_error)?
+        .unwrap_or(1) as usize;
+
+    let geokeys = match decoder.find_tag(Tag::GeoKeyDirectoryTag).map_err(tiff_error)? {
+        Some(value) => value.into_u16_vec().map_err(tiff_error)?,
+        None => return Err(invalid("TIFF has no GeoKeyDirectory")),
+    };
+    let crs = crs_from_geokeys(&geokeys)?;
+    let mut transform = read_transform(&mut decoder)?;
+    if geokey(&geokeys, GT_RASTER_TYPE) == Some(RASTER_PIXEL_IS_POINT) {
+        // Tiepoints refer to pixel centers; shift to the corner convention
+        let (origin_x, origin_y) = transform.apply(-0.5, -0.5);
+        transform = GeoTransform { origin_x, origin_y, ..transform };
+    }
+
+    let no_data = match decoder.find_tag(Tag::GdalNodata).map_err(tiff_error)? {
+        Some(value) => {
+            let text = value.into_string().map_err(tiff_error)?;
+            text.trim_matches(|c: char| c == '\0' || c.is_whitespace())
+                .parse::<f32>()
+                .map_err(|_| invalid(format!("unreadable GDAL_NODATA '{text}'")))?
+        }
+        None => -9999.0,
+    };
+    let names = match decoder.find_tag(Tag::Unknown(GDAL_METADATA_TAG)).map_err(tiff_error)? {
+        Some(value) => band_descriptions(&value.into_string().map_err(tiff_error)?, samples),
+        None => vec![None; samples],
+    };
+
+    let pixels: Vec<f32> = match decoder.read_image().map_err(tiff_error)? {
+        DecodingResult::U8(v) => v.into_iter().map(|x| x as f32).collect(),
+        DecodingResult::U16(v) => v.into_iter().map(|x| x as f32).collect(),
+        DecodingResult::I8(v) => v.into_iter().map(|x| x as f32).collect(),
+        DecodingResult::I16(v) => v.into_iter().map(|x| x as f32).collect(),
+        DecodingResult::I32(v) => v.into_iter().map(|x| x as f32).collect(),
+        DecodingResult::F32(v) => v,
+        DecodingResult::F64(v) => v.into_iter().map(|x| x as f32).collect(),
+        _ => return Err(invalid("unsupported GeoTIFF sample type")),
+    };
+    if pixels.len() < rows * cols * samples {
+        return Err(invalid("GeoTIFF image data is shorter than its dimensions"));
+    }
+
+    let mut dataset = RasterDataset::try_from_geotransform(dataset_id, crs, transform, rows, cols)?;
+    for (band, name) in names.into_iter().enumerate() {
+        let data = pixels.iter().skip(band).step_by(samples).take(rows * cols).copied().collect();
+        let name = name.unwrap_or_else(|| format!("Band{}", band + 1));
+        dataset.add_band(RasterBand::from_data(band as u32 + 1, name, rows, cols, data, no_data));
+    }
+    Ok(dataset)
+}
+
+/// Write all bands of a dataset as one pixel-interleaved GeoTIFF image
+pub fn write_geotiff<W: Write + Seek>(dataset: &RasterDataset, writer: W, options: &GeoTiffOptions) -> Result<()> {
+    let bands: Vec<&RasterBand> = dataset.bands.iter().collect();
+    let first = *bands.first().ok_or_else(|| invalid("dataset has no bands to write"))?;
+    dataset.ensure_aligned(&bands)?;
+    let (rows, cols) = (first.rows, first.cols);
+    let sample_type = options.sample_type;
+    let no_data = options.no_data.unwrap_or(first.no_data_value);
+    if !sample_type.represents(no_data) {
+        return Err(invalid(format!(
+            "nodata {no_data} cannot be stored as {sample_type:?}; set an explicit nodata in GeoTiffOptions"
+        )));
+    }
+    // A valid pixel that quantizes onto nodata would read back as nodata
+    for band in &bands {
+        if let Some(&value) = band
+            .data
+            .iter()
+            .find(|&&v| !band.is_no_data(v) && sample_type.quantize(v) == no_data)
+        {
+            return Err(invalid(format!(
+                "band '{}' value {value} collides with nodata {no_data} when stored as {sample_type:?}",
+                band.band_name
+            )));
+        }
+    }
+
+    // Pixel-interleaved bytes for a block; cells outside the raster are padded with nodata
+    let block = |r0: usize, r1: usize, c0: usize, c1: usize| {
+        let mut raw = Vec::with_capacity((r1 - r0) * (c1 - c0) * bands.len() * 4);
+        for r in r0..r1 {
+            for c in c0..c1 {
+                for band in &bands {
+                    let value = if r < rows && c < cols { band.data[r * cols + c] } else { no_data };
+                    let value = if band.is_no_data(value) { no_data } else { value };
+                    sample_type.push_bytes(value, &mut raw);
+                }
+            }
+        }
+        options.compression.compress(raw)
+    };
+
+    let chunks = match options.layout {
+        TiffLayout::Striped { rows_per_strip } => {
+            let step = rows_per_strip.max(1) as usize;
+            (0..rows)
+                .step_by(step)
+                .map(|r0| block(r0, (r0 + step).min(rows), 0, cols))
+                .collect::<Result<Vec<_>>>()?
+        }
+        TiffLayout::Tiled { tile_size } => {
+            if tile_size == 0 || tile_size % 16 != 0 {
+                return Err(invalid(format!("tile size {tile_size} is not a multiple of 16")));
+            }
+            let step = tile_size as usize;
+            (0..rows)
+                .step_by(step)
+                .flat_map(|r0| (0..cols).step_by(step).map(move |c0| (r0, c0)))
+                .map(|(r0, c0)| block(r0, r0 + step, c0, c0 + step))
+                .collect::<Result<Vec<_>>>()?
+        }
+    };
+
+    let mut encoder = TiffEncoder::new(writer).map_err(tiff_error)?;
+    let mut dir = encoder.image_directory().map_err(tiff_error)?;
+    let mut offsets = Vec::with_capacity(chunks.len());
+    for chunk in &chunks {
+        let offset = dir.write_data(&chunk[..]).map_err(tiff_error)?;
+        offsets.push(u32::try_from(offset).map_err(|_| invalid("GeoTIFF exceeds 4 GiB"))?);
+    }
+    let counts: Vec<u32> = chunks.iter().map(|c| c.len() as u32).collect();
+
+    let n = bands.len();
+    dir.write_tag(Tag::ImageWidth, cols as u32).map_err(tiff_error)?;
+    dir.write_tag(Tag::ImageLength, rows as u32).map_err(tiff_error)?;
+    dir.write_tag(Tag::BitsPerSample, &vec![sample_type.bits(); n][..]).map_err(tiff_error)?;
+    dir.write_tag(Tag::Compression, options.compression.code()).map_err(tiff_error)?;
+    dir.write_tag(Tag::PhotometricInterpretation, 1u16).map_err(tiff_error)?;
+    dir.write_tag(Tag::SamplesPerPixel, n as u16).map_err(tiff_error)?;
+    dir.write_tag(Tag::PlanarConfiguration, 1u16).map_err(tiff_error)?;
+    dir.write_tag(Tag::SampleFormat, &vec![sample_type.sample_format(); n][..]).map_err(tiff_error)?;
+    if n > 1 {
+        dir.write_tag(Tag::ExtraSamples, &vec![0u16; n - 1][..]).map_err(tiff_error)?;
+    }
+    match options.layout {
+        TiffLayout::Striped { rows_per_strip } => {
+            dir.write_tag(Tag::RowsPerStrip, rows_per_strip.max(1)).map_err(tiff_error)?;
+            dir.write_tag(Tag::StripOffsets, &offsets[..]).map_err(tiff_error)?;
+            dir.write_tag(Tag::StripByteCounts, &counts[..]).map_err(tiff_error)?;
+        }
+        TiffLayout::Tiled { tile_size } => {
+            dir.write_tag(Tag::TileWidth, tile_size).map_err(tiff_error)?;
+            dir.write_tag(Tag::TileLength, tile_size).map_err(tiff_error)?;
+            dir.write_tag(Tag::TileOffsets, &offsets[..]).map_err(tiff_error)?;
+            dir.write_tag(Tag::TileByteCounts, &counts[..]).map_err(tiff_error)?;
+        }
+    }
+
+    let gt = dataset.geotransform_for(first);
+    if gt.is_north_up() {
+        let scale = [gt.pixel_width, -gt.pixel_height, 0.0];
+        let tiepoint = [0.0, 0.0, 0.0, gt.origin_x, gt.origin_y, 0.0];
+        dir.write_tag(Tag::ModelPixelScaleTag, &scale[..]).map_err(tiff_error)?;
+        dir.write_tag(Tag::ModelTiepointTag, &tiepoint[..]).map_err(tiff_error)?;
+    } else {
+        let matrix = [
+            gt.pixel_width, gt.rotation_x, 0.0, gt.origin_x,
+            gt.rotation_y, gt.pixel_height, 0.0, gt.origin_y,
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ];
+        dir.write_tag(Tag::ModelTransformationTag, &matrix[..]).map_err(tiff_error)?;
+    }
+    dir.write_tag(Tag::GeoKeyDirectoryTag, &geokeys_for(dataset.crs)[..]).map_err(tiff_error)?;
+    dir.write_tag(Tag::GdalNodata, no_data.to_string().as_str()).map_err(tiff_error)?;
+    let metadata = gdal_metadata(&bands);
+    dir.write_tag(Tag::Unknown(GDAL_METADATA_TAG), metadata.as_str()).map_err(tiff_error)?;
+
+    dir.finish().map_err(tiff_error)
+}
+
+impl RasterDataset {
+    /// Load a GeoTIFF file
+    pub fn from_geotiff_file(path: impl AsRef<Path>, dataset_id: u32) -> Result<Self> {
+        read_geotiff(BufReader::new(File::open(path)?), dataset_id)
+    }
+
+    /// Save all bands to a GeoTIFF file
+    pub fn to_geotiff_file(&self, path: impl AsRef<Path>, options: &GeoTiffOptions) -> Result<()> {
+        write_geotiff(self, BufWriter::new(File::create(path)?), options)
+    }
+}
+
+/// Pixel-to-model transform from a tiepoint + pixel scale or a full transformation matrix
+fn read_transform<R: Read + Seek>(decoder: &mut Decoder<R>) -> Result<GeoTransform> {
+    let mut doubles = |tag: Tag| -> Result<Option<Vec<f64>>> {
+        match decoder.find_tag(tag).map_err(tiff_error)? {
+            Some(value) => Ok(Some(value.into_f64_vec().map_err(tiff_error)?)),
+            None => Ok(None),
+        }
+    };
+
+    if let Some(m) = doubles(Tag::ModelTransformationTag)? {
+        if m.len() < 16 {
+            return Err(invalid("ModelTransformation needs 16 values"));
+        }
+        return Ok(GeoTransform::from_gdal([m[3], m[0], m[1], m[7], m[4], m[5]]));
+    }
+    match (doubles(Tag::ModelTiepointTag)?, doubles(Tag::ModelPixelScaleTag)?) {
+        (Some(tie), Some(scale)) if tie.len() >= 6 && scale.len() >= 2 => {
+            let (i, j, x, y) = (tie[0], tie[1], tie[3], tie[4]);
+            Ok(GeoTransform::north_up(x - i * scale[0], y + j * scale[1], scale[0], scale[1]))
+        }
+        _ => Err(invalid("TIFF has no ModelTiepoint/ModelPixelScale or ModelTransformation")),
+    }
+}
+
+/// Value of an inline (SHORT) GeoKey
+fn geokey(directory: &[u16], key: u16) -> Option<u16> {
+    directory
+        .get(4..)?
+        .chunks_exact(4)
+        .find(|entry| entry[0] == key && entry[1] == 0)
+        .map(|entry| entry[3])
+}
+
+fn crs_from_geokeys(directory: &[u16]) -> Result<CoordinateSystem> {
+    let code = geokey(directory, PROJECTED_CS_TYPE).or_else(|| geokey(directory, GEOGRAPHIC_TYPE));
+    match code {
+        Some(USER_DEFINED) => Ok(CoordinateSystem::LocalProjection),
+        Some(code) => CoordinateSystem::from_epsg(code as u32)
+            .ok_or_else(|| invalid(format!("unsupported EPSG:{code} (expected 4326 or a WGS84 UTM zone)"))),
+        None => Err(invalid("GeoKeys name no EPSG coordinate system")),
+    }
+}
+
+fn geokeys_for(crs: CoordinateSystem) -> Vec<u16> {
+    let (model_type, key) = match crs {
+        CoordinateSystem::WGS84 => (2, GEOGRAPHIC_TYPE),
+        _ => (1, PROJECTED_CS_TYPE),
+    };
+    let code = crs.epsg().map_or(USER_DEFINED, |c| c as u16);
+    vec![
+        1, 1, 0, 3, // Version 1.1.0, three keys
+        GT_MODEL_TYPE, 0, 1, model_type,
+        GT_RASTER_TYPE, 0, 1, 1, // PixelIsArea
+        key, 0, 1, code,
+    ]
+}
+
+fn gdal_metadata(bands: &[&RasterBand]) -> String {
+    let items: String = bands
+        .iter()
+        .enumerate()
+        .map(|(i, b)| format!(r#"<Item name="DESCRIPTION" sample="{i}" role="description">{}</Item>"#, b.band_name))
+        .collect();
+    format!("<GDALMetadata>{items}</GDALMetadata>")
+}
+
+/// Band names from GDAL_METADATA DESCRIPTION items
+fn band_descriptions(xml: &str, samples: usize) -> Vec<Option<String>> {
+    let mut names = vec![None; samples];
+    for item in xml.split("<Item ").skip(1) {
+        let Some((attrs, rest)) = item.split_once('>') else {
+            continue;
+        };
+        if !attrs.contains(r#"name="DESCRIPTION""#) {
+            continue;
+        }
+        let sample = attrs
+            .split_once(r#"sample=""#)
+            .and_then(|(_, s)| s.split('"').next())
+            .and_then(|s| s.parse::<usize>().ok());
+        if let (Some(sample), Some((name, _))) = (sample, rest.split_once("</Item>")) {
+            if sample < samples && !name.is_empty() {
+                names[sample] = Some(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cybersomething_core::models::LatLon;
+    use std::io::Cursor;
+
+    fn fixture(name: &str) -> RasterDataset {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+        RasterDataset::from_geotiff_file(path, 1).unwrap()
+    }
+
+    fn round_trip(dataset: &RasterDataset, options: &GeoTiffOptions) -> RasterDataset {
+        let mut buffer = Cursor::new(Vec::new());
+        write_geotiff(dataset, &mut buffer, options).unwrap();
+        buffer.set_position(0);
+        read_geotiff(buffer, dataset.dataset_id).unwrap()
+    }
+
+    #[test]
+    fn test_read_striped_deflate_f32() {
+        let dataset = fixture("dem_f32_deflate.tif");
+        assert_eq!(dataset.crs, CoordinateSystem::WGS84);
+        let dem = dataset.get_band("DEM").unwrap();
+        assert_eq!((dem.rows, dem.cols), (4, 5));
+        assert_eq!(dem.get_pixel(3, 4), Some(32.0));
+        assert!(dem.is_no_data(dem.get_pixel(1, 2).unwrap()));
+        assert_eq!(dataset.coord_to_pixel(dem, &LatLon::new(33.25, -111.65)), Some((1, 3)));
+    }
+
+    #[test]
+    fn test_read_tiled_lzw_multiband_utm() {
+        let dataset = fixture("landcover_u8_lzw_tiled.tif");
+        assert_eq!(dataset.crs, CoordinateSystem::UTM11N);
+        let names: Vec<&str> = dataset.bands.iter().map(|b| b.band_name.as_str()).collect();
+        assert_eq!(names, vec!["Red", "Green", "NIR"]);
+
+        let nir = dataset.get_band("NIR").unwrap();
+        assert_eq!((nir.rows, nir.cols), (18, 20));
+        assert_eq!(nir.get_pixel(17, 19), Some((100 + 17 * 3 + 19) as f32));
+        assert_eq!(dataset.geotransform.unwrap().to_gdal(), [400000.0, 30.0, 0.0, 3700000.0, 0.0, -30.0]);
+    }
+
+    #[test]
+    fn test_read_big_endian_i16_other_zone() {
+        let dataset = fixture("elevation_i16_be.tif");
+        assert_eq!(dataset.crs, CoordinateSystem::UTM { zone: 12, is_north: true });
+        let band = &dataset.bands[0];
+        assert_eq!(band.no_data_value, -32768.0);
+        assert_eq!(band.data, vec![-400.0, -300.0, -200.0, -100.0, -32768.0, 100.0, 200.0, 300.0, 400.0]);
+    }
+
+    #[test]
+    fn test_round_trip_layouts_and_compression() {
+        let source = fixture("landcover_u8_lzw_tiled.tif");
+        for (compression, layout) in [
+            (TiffCompression::None, TiffLayout::Striped { rows_per_strip: 5 }),
+            (TiffCompression::Deflate, TiffLayout::Tiled { tile_size: 16 }),
+            (TiffCompression::Lzw, TiffLayout::Striped { rows_per_strip: 64 }),
+        ] {
+            let options = GeoTiffOptions::default()
+                .with_sample_type(SampleType::U8)
+                .with_no_data(255.0)
+                .with_compression(compression)
+                .with_layout(layout);
+            let copy = round_trip(&source, &options);
+            assert_eq!(copy.crs, source.crs);
+            assert_eq!(copy.geotransform, source.geotransform);
+            for (a, b) in copy.bands.iter().zip(&source.bands) {
+                assert_eq!((a.band_name.as_str(), &a.data), (b.band_name.as_str(), &b.data));
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_in_memory_dataset() {
+        let mut dataset = RasterDataset::new(7, (LatLon::new(33.0, -112.0), LatLon::new(33.2, -111.7)));
+        let data = vec![1.25, -9999.0, 3.5, 4.0, 5.5, 6.0];
+        dataset.add_band(RasterBand::from_data(1, "NDVI".to_string(), 2, 3, data.clone(), -9999.0));
+
+        let copy = round_trip(&dataset, &GeoTiffOptions::default());
+        assert_eq!(copy.bands[0].data, data);
+        assert_eq!(copy.bands[0].band_name, "NDVI");
+        let (a, b) = (copy.pixel_center(&copy.bands[0], 1, 2), dataset.pixel_center(&dataset.bands[0], 1, 2));
+        assert!((a.latitude - b.latitude).abs() < 1e-9 && (a.longitude - b.longitude).abs() < 1e-9);
+
+        let i16_copy = round_trip(&dataset, &GeoTiffOptions::default().with_sample_type(SampleType::I16));
+        assert_eq!(i16_copy.bands[0].data, vec![1.0, -9999.0, 4.0, 4.0, 6.0, 6.0]);
+
+        let bad = GeoTiffOptions::default().with_layout(TiffLayout::Tiled { tile_size: 10 });
+        assert!(write_geotiff(&dataset, Cursor::new(Vec::new()), &bad).is_err());
+    }
+
+    #[test]
+    fn test_u8_nodata_kept_apart_from_zeros() {
+        let mut dataset = RasterDataset::new(3, (LatLon::new(33.0, -112.0), LatLon::new(33.1, -111.9)));
+        let data = vec![0.0, -9999.0, 12.0, 0.0];
+        dataset.add_band(RasterBand::from_data(1, "Class".to_string(), 2, 2, data, -9999.0));
+
+        // -9999 has no U8 encoding: refuse rather than silently store it as 0
+        let u8_options = GeoTiffOptions::default().with_sample_type(SampleType::U8);
+        assert!(write_geotiff(&dataset, Cursor::new(Vec::new()), &u8_options).is_err());
+        assert!(write_geotiff(&dataset, Cursor::new(Vec::new()), &u8_options.with_no_data(0.0)).is_err());
+
+        let copy = round_trip(&dataset, &u8_options.with_no_data(255.0));
+        let band = &copy.bands[0];
+        assert_eq!(band.no_data_value, 255.0);
+        assert_eq!(band.data, vec![0.0, 255.0, 12.0, 0.0]);
+        assert_eq!(band.statistics().count, 3);
+    }
+
+    #[test]
+    fn test_rejects_grid_outside_wgs84_range() {
+        let mut dataset = RasterDataset::new(4, (LatLon::new(33.0, -112.0), LatLon::new(33.1, -111.9)));
+        dataset.add_band(RasterBand::from_data(1, "DEM".to_string(), 2, 2, vec![1.0; 4], -9999.0));
+        for transform in [GeoTransform::north_up(179.5, 33.0, 1.0, 1.0), GeoTransform::north_up(f64::NAN, 33.0, 1.0, 1.0)] {
+            dataset.geotransform = Some(transform);
+            let mut buffer = Cursor::new(Vec::new());
+            write_geotiff(&dataset, &mut buffer, &GeoTiffOptions::default()).unwrap();
+            buffer.set_position(0);
+            assert!(matches!(read_geotiff(buffer, 4), Err(CybersomethingError::DataValidationError { .. })));
+        }
+    }
+}
@@ -9,13 +9,43 @@
 //! - `raster` — Raster datasets (UAV, satellite imagery)
 //! - `vector` — Vector geometries (polygons, points, lines)
 //! - `projection` — Coordinate system transformations
+//! - `priority` — Grid-wide priority index (P_i) rasters
+//! - `patch` — Connected patches and patch priorities (Q_j)
+//! - `terrain` — DEM slope, aspect and wind-aligned spread modifiers
+//! - `compliance` — Defensible-space grass-height compliance checks
+//! - `network` — Road/trail routing graphs built from line features
+//! - `geotiff` — GeoTIFF import/export for raster datasets
+//! - `tiled` — Tiled on-disk bands with block cache and overviews
+//! - `warp` — Resampling, reprojection and grid alignment of rasters
+//! - `algebra` — Per-pixel band expressions and spectral index presets
+//! - `burn` — Burn severity (dNBR/RdNBR) classes and burn perimeters
 
 pub mod grid;
 pub mod raster;
 pub mod vector;
 pub mod projection;
+pub mod priority;
+pub mod patch;
+pub mod terrain;
+pub mod compliance;
+pub mod network;
+pub mod geotiff;
+pub mod tiled;
+pub mod warp;
+pub mod algebra;
+pub mod burn;
 
 pub use grid::*;
 pub use raster::*;
 pub use vector::*;
 pub use projection::*;
+pub use priority::*;
+pub use patch::*;
+pub use terrain::*;
+pub use compliance::*;
+pub use network::*;
+pub use geotiff::*;
+pub use tiled::*;
+pub use warp::*;
+pub use algebra::*;
+pub use burn::*;
@@ -0,0 +1,293 @@
+//! Road/trail network construction from vector line features
+//! Endpoints and shared vertices within a snap tolerance become junction nodes;
+//! line features are split at junctions into network edges.
+//!
+//! Recognised feature properties:
+//! - `surface` — paved / gravel / dirt / trail
+//! - `oneway` — yes / true / 1 (digitised direction only)
+//! - `vehicles` — comma-separated allowed classes (truck, atv, foot)
+//! - `closed` — comma-separated day-of-year ranges, e.g. `335-60`
+
+use crate::vector::{Feature, FeatureCollection, Geometry};
+use cybersomething_core::math::road_network::{RoadEdge, RoadNetwork, SeasonalClosure, SurfaceType, VehicleClass};
+use cybersomething_core::models::LatLon;
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
+use std::collections::{BTreeSet, HashMap};
+
+/// Network build settings
+#[derive(Debug, Clone, Copy)]
+pub struct NetworkBuildConfig {
+    pub snap_tolerance_m: f64,         // Vertices closer than this share a node
+    pub default_surface: SurfaceType,  // When `surface` is missing
+}
+
+impl Default for NetworkBuildConfig {
+    fn default() -> Self {
+        Self {
+            snap_tolerance_m: 5.0,
+            default_surface: SurfaceType::Dirt,
+        }
+    }
+}
+
+/// Edge attributes parsed from feature properties
+struct EdgeAttributes {
+    surface: SurfaceType,
+    oneway: bool,
+    vehicles: Vec<VehicleClass>,
+    closures: Vec<SeasonalClosure>,
+}
+
+impl EdgeAttributes {
+    fn from_feature(feature: &Feature, config: &NetworkBuildConfig) -> Result<Self> {
+        let invalid = |key: &str, value: &str| CybersomethingError::DataValidationError {
+            reason: format!("feature {}: invalid {} '{}'", feature.feature_id, key, value),
+        };
+
+        let surface = match feature.get_property("surface") {
+            Some(tag) => SurfaceType::from_tag(tag).ok_or_else(|| invalid("surface", tag))?,
+            None => config.default_surface,
+        };
+        let oneway = feature
+            .get_property("oneway")
+            .is_some_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "yes" | "true" | "1"));
+
+        let mut vehicles = Vec::new();
+        if let Some(list) = feature.get_property("vehicles") {
+            for tag in list.split(',').filter(|t| !t.trim().is_empty()) {
+                vehicles.push(VehicleClass::from_tag(tag).ok_or_else(|| invalid("vehicles", tag))?);
+            }
+        }
+
+        let mut closures = Vec::new();
+        if let Some(list) = feature.get_property("closed") {
+            for range in list.split(',').filter(|r| !r.trim().is_empty()) {
+                let (start, end) = range.trim().split_once('-').ok_or_else(|| invalid("closed", range))?;
+                let day = |d: &str| d.trim().parse::<u16>().ok().filter(|d| (1..=366).contains(d));
+                match (day(start), day(end)) {
+                    (Some(s), Some(e)) => closures.push(SeasonalClosure::new(s, e)),
+                    _ => return Err(invalid("closed", range)),
+                }
+            }
+        }
+
+        Ok(Self { surface, oneway, vehicles, closures })
+    }
+
+    fn edge(&self, from: u32, to: u32, geometry: Vec<LatLon>) -> RoadEdge {
+        let length_m = geometry.windows(2).map(|w| w[0].distance_to(&w[1])).sum();
+        let mut edge = RoadEdge::new(from, to, length_m, self.surface)
+            .restricted_to(&self.vehicles)
+            .with_geometry(geometry);
+        edge.closures = self.closures.clone();
+        edge
+    }
+}
+
+/// Vertex cluster formed by snapping
+struct Junction {
+    location: LatLon,
+    features: BTreeSet<usize>,
+    endpoint: bool,
+    node_id: Option<u32>,
+}
+
+/// Hash grid over junction locations, keyed by coordinates quantised to the snap tolerance
+struct SnapGrid {
+    cell_deg: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl SnapGrid {
+    /// Meters per degree of latitude, rounded down so a cell spans at least the tolerance
+    const METERS_PER_DEGREE: f64 = 111_000.0;
+
+    fn new(tolerance_m: f64) -> Self {
+        Self {
+            cell_deg: tolerance_m.max(1e-3) / Self::METERS_PER_DEGREE,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn key(&self, point: &LatLon) -> (i64, i64) {
+        (
+            (point.latitude / self.cell_deg).floor() as i64,
+            (point.longitude / self.cell_deg).floor() as i64,
+        )
+    }
+
+    fn insert(&mut self, point: &LatLon, junction: usize) {
+        self.cells.entry(self.key(point)).or_default().push(junction);
+    }
+
+    /// Earliest-created junction within tolerance of a point
+    fn find(&self, point: &LatLon, junctions: &[Junction], tolerance_m: f64) -> Option<usize> {
+        let (row, col) = self.key(point);
+        // Longitude cells shrink with cos(lat); widen the search to cover the tolerance
+        let cos_lat = (point.latitude.abs() + 1.0).min(89.0).to_radians().cos();
+        let lon_reach = (1.0 / cos_lat).ceil() as i64;
+
+        let mut found: Option<usize> = None;
+        for dr in -1..=1 {
+            for dc in -lon_reach..=lon_reach {
+                for &ji in self.cells.get(&(row + dr, col + dc)).into_iter().flatten() {
+                    if found.is_none_or(|f| ji < f) && junctions[ji].location.distance_to(point) <= tolerance_m {
+                        found = Some(ji);
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Build a routable network from LineString features (other geometries are ignored)
+pub fn build_road_network(roads: &FeatureCollection, config: &NetworkBuildConfig) -> Result<RoadNetwork> {
+    let lines: Vec<(&Feature, &Vec<LatLon>)> = roads
+        .features
+        .iter()
+        .filter_map(|f| match &f.geometry {
+            Geometry::LineString(coords) if coords.len() >= 2 => Some((f, coords)),
+            _ => None,
+        })
+        .collect();
+
+    // Snap every vertex to a junction cluster
+    let mut junctions: Vec<Junction> = Vec::new();
+    let mut grid = SnapGrid::new(config.snap_tolerance_m);
+    let mut vertex_junction: Vec<Vec<usize>> = Vec::with_capacity(lines.len());
+    for (fi, (_, coords)) in lines.iter().enumerate() {
+        let mut ids = Vec::with_capacity(coords.len());
+        for (vi, point) in coords.iter().enumerate() {
+            let endpoint = vi == 0 || vi == coords.len() - 1;
+            let existing = grid.find(point, &junctions, config.snap_tolerance_m);
+            let ji = existing.unwrap_or_else(|| {
+                junctions.push(Junction { location: *point, features: BTreeSet::new(), endpoint: false, node_id: None });
+                grid.insert(point, junctions.len() - 1);
+                junctions.len() - 1
+            });
+            junctions[ji].features.insert(fi);
+            junctions[ji].endpoint |= endpoint;
+            ids.push(ji);
+        }
+        vertex_junction.push(ids);
+    }
+
+    // Endpoints and vertices shared between features become nodes
+    let mut network = RoadNetwork::new();
+    let mut next_id = 1;
+    for junction in junctions.iter_mut() {
+        if junction.endpoint || junction.features.len() > 1 {
+            junction.node_id = Some(next_id);
+            network.add_node(next_id, junction.location);
+            next_id += 1;
+        }
+    }
+
+    // Split each line at its nodes
+    for ((feature, coords), ids) in lines.iter().zip(&vertex_junction) {
+        let attrs = EdgeAttributes::from_feature(feature, config)?;
+        let mut start: Option<(u32, usize)> = None;
+
+        for (vi, ji) in ids.iter().enumerate() {
+            let Some(node_id) = junctions[*ji].node_id else {
+                continue;
+            };
+            if let Some((from, from_vi)) = start {
+                let mut geometry = coords[from_vi..=vi].to_vec();
+                geometry[0] = junctions[ids[from_vi]].location;
+                geometry[vi - from_vi] = junctions[*ji].location;
+
+                let edge = attrs.edge(from, node_id, geometry);
+                if from != node_id || edge.length_m > config.snap_tolerance_m {
+                    if attrs.oneway {
+                        network.add_edge(edge)?;
+                    } else {
+                        network.add_two_way(edge)?;
+                    }
+                }
+            }
+            start = Some((node_id, vi));
+        }
+    }
+
+    Ok(network)
+}
+
+impl FeatureCollection {
+    /// Build a road network from this collection's LineString features
+    pub fn to_road_network(&self, config: &NetworkBuildConfig) -> Result<RoadNetwork> {
+        build_road_network(self, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cybersomething_core::math::road_network::TravelConstraints;
+
+    fn line(id: u32, coords: &[(f64, f64)]) -> Feature {
+        Feature::new(id, Geometry::LineString(coords.iter().map(|&(lat, lon)| LatLon::new(lat, lon)).collect()))
+    }
+
+    /// Main road west-east, spur road meeting it mid-way (endpoint ~1 m off the vertex)
+    fn roads() -> FeatureCollection {
+        let mut roads = FeatureCollection::new(1, "Roads".to_string());
+        let mut main = line(1, &[(33.0, -112.0), (33.0, -111.99), (33.0, -111.98)]);
+        main.set_property("surface".to_string(), "paved".to_string());
+        roads.add_feature(main);
+        let mut spur = line(2, &[(33.01, -111.99), (33.00001, -111.99)]);
+        spur.set_property("vehicles".to_string(), "atv,foot".to_string());
+        roads.add_feature(spur);
+        roads
+    }
+
+    #[test]
+    fn test_snaps_and_splits_at_junctions() {
+        let network = roads().to_road_network(&NetworkBuildConfig::default()).unwrap();
+        assert_eq!(network.node_count(), 4); // Two main ends, spur end, junction
+        assert_eq!(network.edge_count(), 6); // Three two-way segments
+    }
+
+    #[test]
+    fn test_routes_through_junction() {
+        let network = roads().to_road_network(&NetworkBuildConfig::default()).unwrap();
+        let west = network.nearest_node(&LatLon::new(33.0, -112.0)).unwrap().0;
+        let spur_end = network.nearest_node(&LatLon::new(33.01, -111.99)).unwrap().0;
+
+        let atv = TravelConstraints { vehicle: Some(VehicleClass::Atv), day_of_year: None };
+        let path = network.astar(west, spur_end, &atv).unwrap();
+        assert_eq!(path.nodes.len(), 3);
+        assert!((path.length_m - 2045.0).abs() < 20.0); // ~934 m + ~1112 m
+
+        let truck = TravelConstraints { vehicle: Some(VehicleClass::Truck), day_of_year: None };
+        assert!(network.astar(west, spur_end, &truck).is_none());
+    }
+
+    #[test]
+    fn test_snapping_with_grid() {
+        // Endpoints ~3 m apart share a node; the road ~110 m north stays separate
+        let mut roads = FeatureCollection::new(1, "Roads".to_string());
+        roads.add_feature(line(1, &[(33.0, -112.01), (33.0, -112.0)]));
+        roads.add_feature(line(2, &[(33.0, -111.99997), (33.0, -111.99)]));
+        roads.add_feature(line(3, &[(33.001, -111.98), (33.001, -111.97)]));
+        let network = roads.to_road_network(&NetworkBuildConfig::default()).unwrap();
+        assert_eq!(network.node_count(), 5);
+        assert_eq!(network.edge_count(), 6);
+    }
+
+    #[test]
+    fn test_oneway_and_bad_tags() {
+        let mut roads = FeatureCollection::new(1, "Roads".to_string());
+        let mut road = line(1, &[(33.0, -112.0), (33.0, -111.99)]);
+        road.set_property("oneway".to_string(), "yes".to_string());
+        road.set_property("closed".to_string(), "335-60".to_string());
+        roads.add_feature(road);
+        let network = roads.to_road_network(&NetworkBuildConfig::default()).unwrap();
+        assert_eq!(network.edge_count(), 1);
+        assert_eq!(network.edges_from(1)[0].closures, vec![SeasonalClosure::new(335, 60)]);
+
+        roads.features[0].set_property("surface".to_string(), "lava".to_string());
+        assert!(roads.to_road_network(&NetworkBuildConfig::default()).is_err());
+    }
+}
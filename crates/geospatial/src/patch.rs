@@ -0,0 +1,248 @@
+//! Patch-level priority aggregation
+//! Groups high-P_i cells into connected patches and ranks them by
+//! Q_j = Σ_{i ∈ patch j} P_i for "do-first" treatment lists.
+
+use crate::grid::SpatialGrid;
+use crate::raster::{RasterBand, RasterDataset};
+use cybersomething_core::models::LatLon;
+use cybersomething_core::utils::constants::risk_coefficients::RISK_LOW_THRESHOLD;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Neighbourhood used when joining cells into patches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Connectivity {
+    Four,  // Edge neighbours only
+    Eight, // Edge and diagonal neighbours
+}
+
+impl Connectivity {
+    fn offsets(&self) -> &'static [(i64, i64)] {
+        match self {
+            Self::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Self::Eight => &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)],
+        }
+    }
+}
+
+/// Patch extraction settings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PatchConfig {
+    pub connectivity: Connectivity,
+    pub threshold: f64,   // Cells with P_i ≥ threshold join patches
+    pub min_cells: usize, // Smaller patches are discarded
+}
+
+impl Default for PatchConfig {
+    fn default() -> Self {
+        Self {
+            connectivity: Connectivity::Eight,
+            threshold: RISK_LOW_THRESHOLD, // Medium and High cells
+            min_cells: 1,
+        }
+    }
+}
+
+/// Connected patch of priority cells
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Patch {
+    pub patch_id: u32,             // 1 = highest Q_j
+    pub cells: Vec<(usize, usize)>, // (row, col)
+    pub priority_sum: f64,         // Q_j
+    pub priority_mean: f64,
+    pub area_ha: f64,
+    pub centroid: LatLon,
+}
+
+/// Label connected components of cells accepted by `include`
+pub fn label_components<F>(rows: usize, cols: usize, connectivity: Connectivity, include: F) -> Vec<Vec<(usize, usize)>>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    let mut visited = vec![false; rows * cols];
+    let mut components = Vec::new();
+
+    for start in 0..rows * cols {
+        if visited[start] || !include(start / cols, start % cols) {
+            continue;
+        }
+
+        let mut component = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        visited[start] = true;
+
+        while let Some(idx) = queue.pop_front() {
+            let (row, col) = (idx / cols, idx % cols);
+            component.push((row, col));
+
+            for (dr, dc) in connectivity.offsets() {
+                let (nr, nc) = (row as i64 + dr, col as i64 + dc);
+                if nr < 0 || nc < 0 || nr >= rows as i64 || nc >= cols as i64 {
+                    continue;
+                }
+                let (nr, nc) = (nr as usize, nc as usize);
+                let nidx = nr * cols + nc;
+                if !visited[nidx] && include(nr, nc) {
+                    visited[nidx] = true;
+                    queue.push_back(nidx);
+                }
+            }
+        }
+
+        components.push(component);
+    }
+
+    components
+}
+
+/// Build patch records from components, ranked by descending Q_j
+fn rank_patches<V, C>(
+    components: Vec<Vec<(usize, usize)>>,
+    config: &PatchConfig,
+    cell_area_ha: f64,
+    value: V,
+    center: C,
+) -> Vec<Patch>
+where
+    V: Fn(usize, usize) -> f64,
+    C: Fn(usize, usize) -> LatLon,
+{
+    let mut patches: Vec<Patch> = components
+        .into_iter()
+        .filter(|cells| cells.len() >= config.min_cells)
+        .map(|cells| {
+            let n = cells.len() as f64;
+            let priority_sum: f64 = cells.iter().map(|&(r, c)| value(r, c)).sum();
+            let (lat_sum, lon_sum) = cells.iter().fold((0.0, 0.0), |(lat, lon), &(r, c)| {
+                let p = center(r, c);
+                (lat + p.latitude, lon + p.longitude)
+            });
+
+            Patch {
+                patch_id: 0,
+                priority_sum,
+                priority_mean: priority_sum / n,
+                area_ha: n * cell_area_ha,
+                centroid: LatLon::new(lat_sum / n, lon_sum / n),
+                cells,
+            }
+        })
+        .collect();
+
+    patches.sort_by(|a, b| {
+        b.priority_sum
+            .partial_cmp(&a.priority_sum)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    for (rank, patch) in patches.iter_mut().enumerate() {
+        patch.patch_id = rank as u32 + 1;
+    }
+
+    patches
+}
+
+impl RasterDataset {
+    /// Extract ranked patches from a P_i band georeferenced by this dataset
+    pub fn find_patches(&self, priority: &RasterBand, config: &PatchConfig) -> Vec<Patch> {
+        let value = |r: usize, c: usize| priority.data[r * priority.cols + c];
+        let components = label_components(priority.rows, priority.cols, config.connectivity, |r, c| {
+            let v = value(r, c);
+            !priority.is_no_data(v) && v as f64 >= config.threshold
+        });
+
+        let (width_m, height_m) = self.pixel_size_m(priority);
+        rank_patches(
+            components,
+            config,
+            width_m * height_m / 10_000.0,
+            |r, c| value(r, c) as f64,
+            |r, c| self.pixel_center(priority, r, c),
+        )
+    }
+}
+
+impl SpatialGrid {
+    /// Extract ranked patches from a per-cell priority value stored under `key`
+    pub fn find_patches(&self, key: &str, config: &PatchConfig) -> Vec<Patch> {
+        let value = |r: usize, c: usize| {
+            self.get_cell(r as u32, c as u32)
+                .and_then(|cell| cell.get_value(key))
+        };
+        let components = label_components(self.rows as usize, self.cols as usize, config.connectivity, |r, c| {
+            value(r, c).is_some_and(|v| v >= config.threshold)
+        });
+
+        rank_patches(
+            components,
+            config,
+            self.cell_size_km * self.cell_size_km * 100.0,
+            |r, c| value(r, c).unwrap_or(0.0),
+            |r, c| self.get_cell(r as u32, c as u32).map(|cell| cell.center).unwrap_or(LatLon::new(0.0, 0.0)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn priority_band(values: &[f32], rows: usize, cols: usize) -> RasterBand {
+        RasterBand::from_data(1, "Priority".to_string(), rows, cols, values.to_vec(), -9999.0)
+    }
+
+    #[test]
+    fn test_connectivity_changes_patch_count() {
+        // Diagonal pair only joins under 8-connectivity
+        let band = priority_band(&[0.9, 0.0, 0.0, 0.8], 2, 2);
+        let dataset = RasterDataset::new(1, (LatLon::new(33.0, -112.0), LatLon::new(33.001, -111.999)));
+
+        let four = PatchConfig { connectivity: Connectivity::Four, threshold: 0.5, min_cells: 1 };
+        let eight = PatchConfig { connectivity: Connectivity::Eight, ..four };
+        assert_eq!(dataset.find_patches(&band, &four).len(), 2);
+        assert_eq!(dataset.find_patches(&band, &eight).len(), 1);
+    }
+
+    #[test]
+    fn test_patches_ranked_by_sum() {
+        #[rustfmt::skip]
+        let band = priority_band(&[
+            0.9, 0.0, 0.5,
+            0.0, 0.0, 0.5,
+            0.0, 0.0, 0.5,
+        ], 3, 3);
+        let dataset = RasterDataset::new(1, (LatLon::new(33.0, -112.0), LatLon::new(33.003, -111.997)));
+        let config = PatchConfig { connectivity: Connectivity::Four, threshold: 0.4, min_cells: 1 };
+
+        let patches = dataset.find_patches(&band, &config);
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].patch_id, 1);
+        assert_eq!(patches[0].cells.len(), 3);
+        assert!((patches[0].priority_sum - 1.5).abs() < 1e-6);
+        assert!((patches[0].priority_mean - 0.5).abs() < 1e-6);
+        assert!(patches[0].area_ha > 0.0);
+        assert!(patches[0].centroid.longitude > patches[1].centroid.longitude);
+    }
+
+    #[test]
+    fn test_min_patch_size_and_no_data() {
+        let band = priority_band(&[0.9, -9999.0, 0.9, 0.9], 2, 2);
+        let dataset = RasterDataset::new(1, (LatLon::new(33.0, -112.0), LatLon::new(33.001, -111.999)));
+        let config = PatchConfig { connectivity: Connectivity::Four, threshold: 0.5, min_cells: 4 };
+
+        assert!(dataset.find_patches(&band, &config).is_empty());
+    }
+
+    #[test]
+    fn test_grid_patches() {
+        let mut grid = SpatialGrid::new(1, 3, 3, 0.01); // 10 m cells
+        grid.initialize(33.0, -112.0);
+        for (row, col) in [(0, 0), (0, 1), (2, 2)] {
+            grid.get_cell_mut(row, col).unwrap().set_value("priority", 0.8);
+        }
+
+        let patches = grid.find_patches("priority", &PatchConfig::default());
+        assert_eq!(patches.len(), 2);
+        assert!((patches[0].priority_sum - 1.6).abs() < 1e-9);
+        assert!((patches[0].area_ha - 0.02).abs() < 1e-9);
+    }
+}
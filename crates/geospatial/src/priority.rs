@@ -0,0 +1,198 @@
+//! Grid-wide priority index (P_i) rasters
+//! Runs the core risk formula per pixel over vegetation, invasive-grass and
+//! slope bands, producing a P_i band and a Low/Medium/High zone band.
+
+use crate::raster::{RasterBand, RasterDataset};
+use crate::tiled::{map_blocks, BandSource, TiledBand};
+use cybersomething_core::math::risk_index::RiskCalculator;
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
+use rayon::prelude::*;
+
+/// Band id assigned to computed P_i rasters
+pub const PRIORITY_BAND_ID: u32 = 1001;
+/// Band id assigned to classified risk-zone rasters
+pub const RISK_ZONE_BAND_ID: u32 = 1002;
+/// Nodata of P_i and zone rasters (outside both [0, 1] and the zone classes)
+pub const PRIORITY_NO_DATA: f32 = -9999.0;
+
+/// P_i raster with its zone classification
+#[derive(Debug, Clone)]
+pub struct PriorityRaster {
+    pub priority: RasterBand, // P_i in [0, 1]
+    pub zones: RasterBand,    // RiskZone class values (0/1/2)
+}
+
+/// Compute P_i and risk zones per pixel (parallel over rows)
+pub fn compute_priority_raster(
+    vegetation: &RasterBand,
+    grass: &RasterBand,
+    slope: &RasterBand,
+    calculator: &RiskCalculator,
+) -> Result<PriorityRaster> {
+    for band in [grass, slope] {
+        if band.rows != vegetation.rows || band.cols != vegetation.cols {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!(
+                    "band '{}' is {}x{}, expected {}x{}",
+                    band.band_name, band.rows, band.cols, vegetation.rows, vegetation.cols
+                ),
+            });
+        }
+    }
+
+    let no_data = PRIORITY_NO_DATA;
+    let (priority, zones): (Vec<f32>, Vec<f32>) = vegetation
+        .data
+        .par_iter()
+        .zip(grass.data.par_iter())
+        .zip(slope.data.par_iter())
+        .map(|((&v, &g), &s)| {
+            if vegetation.is_no_data(v) || grass.is_no_data(g) || slope.is_no_data(s) {
+                return (no_data, no_data);
+            }
+            let pi = calculator.compute_risk(v as f64, g as f64, s as f64);
+            (pi as f32, calculator.classify(pi).class_value() as f32)
+        })
+        .unzip();
+
+    let (rows, cols) = (vegetation.rows, vegetation.cols);
+    Ok(PriorityRaster {
+        priority: RasterBand::from_data(PRIORITY_BAND_ID, "Priority".to_string(), rows, cols, priority, no_data),
+        zones: RasterBand::from_data(RISK_ZONE_BAND_ID, "RiskZone".to_string(), rows, cols, zones, no_data),
+    })
+}
+
+/// Compute P_i block by block into tiled priority and zone bands (for rasters larger than memory)
+pub fn compute_priority_tiled(
+    vegetation: &dyn BandSource,
+    grass: &dyn BandSource,
+    slope: &dyn BandSource,
+    calculator: &RiskCalculator,
+    priority: &TiledBand,
+    zones: &TiledBand,
+) -> Result<()> {
+    map_blocks(&[vegetation, grass, slope], &[priority, zones], |w| {
+        let out = compute_priority_raster(&w[0], &w[1], &w[2], calculator)?;
+        Ok(vec![out.priority, out.zones])
+    })
+}
+
+impl RasterDataset {
+    /// Compute P_i from named vegetation, grass and slope bands
+    pub fn compute_priority(
+        &self,
+        calculator: &RiskCalculator,
+        vegetation_band: &str,
+        grass_band: &str,
+        slope_band: &str,
+    ) -> Result<PriorityRaster> {
+        let band = |name: &str| {
+            self.get_band(name).ok_or_else(|| CybersomethingError::DataValidationError {
+                reason: format!("dataset {} has no band '{}'", self.dataset_id, name),
+            })
+        };
+
+        compute_priority_raster(band(vegetation_band)?, band(grass_band)?, band(slope_band)?, calculator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiled::tests::assert_blockwise_matches;
+    use cybersomething_core::math::risk_index::{RiskThresholds, RiskWeights, RiskZone};
+    use cybersomething_core::models::LatLon;
+
+    fn filled(name: &str, value: f32) -> RasterBand {
+        RasterBand::from_data(1, name.to_string(), 2, 2, vec![value; 4], -9999.0)
+    }
+
+    #[test]
+    fn test_priority_matches_calculator() {
+        let calc = RiskCalculator::new(RiskWeights::default());
+        let out = compute_priority_raster(
+            &filled("Vegetation", 500.0),
+            &filled("Grass", 80.0),
+            &filled("Slope", 15.0),
+            &calc,
+        )
+        .unwrap();
+
+        let expected = calc.compute_risk(500.0, 80.0, 15.0) as f32;
+        assert!((out.priority.get_pixel(1, 1).unwrap() - expected).abs() < 1e-6);
+        assert_eq!(out.zones.get_pixel(0, 0), Some(RiskZone::Medium.class_value() as f32));
+    }
+
+    #[test]
+    fn test_priority_zones_use_calculator_thresholds() {
+        let strict = RiskCalculator::new(RiskWeights::default())
+            .with_thresholds(RiskThresholds { low: 0.05, medium: 0.1 });
+        let out = compute_priority_raster(
+            &filled("Vegetation", 500.0),
+            &filled("Grass", 80.0),
+            &filled("Slope", 15.0),
+            &strict,
+        )
+        .unwrap();
+        assert_eq!(out.zones.get_pixel(0, 0), Some(RiskZone::High.class_value() as f32));
+    }
+
+    #[test]
+    fn test_priority_honours_no_data() {
+        let calc = RiskCalculator::new(RiskWeights::default());
+        let mut grass = filled("Grass", 80.0);
+        grass.data[3] = grass.no_data_value;
+
+        let out = compute_priority_raster(&filled("Vegetation", 500.0), &grass, &filled("Slope", 15.0), &calc).unwrap();
+        assert_eq!(out.priority.get_pixel(1, 1), Some(PRIORITY_NO_DATA));
+        assert_eq!(out.zones.get_pixel(1, 1), Some(PRIORITY_NO_DATA));
+        assert_eq!(out.priority.statistics().count, 3);
+    }
+
+    #[test]
+    fn test_zero_input_no_data_keeps_low_scores() {
+        // Inputs flagging nodata as 0 must not mask P_i = 0 or the Low class
+        let calc = RiskCalculator::new(RiskWeights::default());
+        let band = |name: &str, value: f32| RasterBand::from_data(1, name.to_string(), 1, 2, vec![value, 0.0], 0.0);
+
+        let out = compute_priority_raster(&band("Vegetation", 1.0), &band("Grass", 1.0), &band("Slope", 1.0), &calc).unwrap();
+        assert_eq!(out.priority.no_data_value, PRIORITY_NO_DATA);
+        assert_eq!(out.zones.data, vec![0.0, PRIORITY_NO_DATA]);
+        assert_eq!(out.zones.statistics().count, 1);
+    }
+
+    #[test]
+    fn test_priority_rejects_shape_mismatch() {
+        let calc = RiskCalculator::new(RiskWeights::default());
+        let slope = RasterBand::new(3, "Slope".to_string(), 3, 3);
+        assert!(compute_priority_raster(&filled("Vegetation", 1.0), &filled("Grass", 1.0), &slope, &calc).is_err());
+    }
+
+    #[test]
+    fn test_tiled_priority_matches_in_memory() {
+        let ramp = |name: &str, scale: f32| {
+            let data = (0..30).map(|i| i as f32 * scale).collect();
+            RasterBand::from_data(1, name.to_string(), 5, 6, data, -9999.0)
+        };
+        let (vegetation, grass, slope) = (ramp("Vegetation", 40.0), ramp("Grass", 4.0), ramp("Slope", 2.0));
+        let calc = RiskCalculator::new(RiskWeights::default());
+        let expected = compute_priority_raster(&vegetation, &grass, &slope, &calc).unwrap();
+
+        assert_blockwise_matches("tiled-priority", &[&vegetation, &grass, &slope], &[&expected.priority, &expected.zones], |i, o| {
+            compute_priority_tiled(i[0], i[1], i[2], &calc, o[0], o[1])
+        });
+    }
+
+    #[test]
+    fn test_dataset_priority_by_band_name() {
+        let mut dataset = RasterDataset::new(1, (LatLon::new(33.0, -112.0), LatLon::new(33.1, -111.9)));
+        dataset.add_band(filled("Vegetation", 1000.0));
+        dataset.add_band(filled("Grass", 100.0));
+        dataset.add_band(filled("Slope", 60.0));
+
+        let calc = RiskCalculator::new(RiskWeights::default());
+        let out = dataset.compute_priority(&calc, "Vegetation", "Grass", "Slope").unwrap();
+        assert_eq!(out.zones.get_pixel(0, 1), Some(RiskZone::High.class_value() as f32));
+        assert!(dataset.compute_priority(&calc, "Vegetation", "Grass", "DEM").is_err());
+    }
+}
@@ -3,6 +3,22 @@
 use cybersomething_core::models::{LatLon, UTM};
 use serde::{Deserialize, Serialize};
 
+const WGS84_A: f64 = 6378137.0;
+const WGS84_E2: f64 = 0.00669438; // Eccentricity squared
+const UTM_K0: f64 = 0.9996;
+const UTM_FALSE_EASTING: f64 = 500000.0;
+pub(crate) const UTM_FALSE_NORTHING_SOUTH: f64 = 10000000.0;
+
+/// Distance along the meridian from the equator (m)
+fn meridian_arc(lat: f64) -> f64 {
+    let e2 = WGS84_E2;
+    WGS84_A
+        * ((1.0 - e2 / 4.0 - 3.0 * e2.powi(2) / 64.0 - 5.0 * e2.powi(3) / 256.0) * lat
+            - (3.0 * e2 / 8.0 + 3.0 * e2.powi(2) / 32.0 + 45.0 * e2.powi(3) / 1024.0) * (2.0 * lat).sin()
+            + (15.0 * e2.powi(2) / 256.0 + 45.0 * e2.powi(3) / 1024.0) * (4.0 * lat).sin()
+            - (35.0 * e2.powi(3) / 3072.0) * (6.0 * lat).sin())
+}
+
 /// Projection type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProjectionType {
@@ -27,47 +43,85 @@ impl CoordinateTransformer {
         }
     }
 
+    /// Target a different UTM zone (default 11)
+    pub fn with_utm_zone(mut self, zone: u8) -> Self {
+        self.utm_zone = zone;
+        self
+    }
+
     /// Transform WGS84 to UTM11N (Sonoran Desert)
+    /// Snyder (1987) transverse Mercator series, sub-meter within the zone
     pub fn latlon_to_utm(&self, latlon: &LatLon) -> UTM {
-        let lon_origin = (self.utm_zone as f64 - 0.5) * 6.0 - 180.0;
-        let dlon = (latlon.longitude - lon_origin).to_radians();
         let lat = latlon.latitude.to_radians();
+        let lon_origin = (self.utm_zone as f64 - 0.5) * 6.0 - 180.0;
+        let ep2 = WGS84_E2 / (1.0 - WGS84_E2);
 
-        const K0: f64 = 0.9996;
-        const E2: f64 = 0.00669438; // WGS84 eccentricity squared
-        const A: f64 = 6378137.0;
-
-        let n = A / (1.0 - E2 * lat.sin().powi(2)).sqrt();
+        let n = WGS84_A / (1.0 - WGS84_E2 * lat.sin().powi(2)).sqrt();
         let t = lat.tan().powi(2);
-        let c = lat.cos().powi(2) * E2 / (1.0 - E2);
+        let c = ep2 * lat.cos().powi(2);
+        let a = lat.cos() * (latlon.longitude - lon_origin).to_radians();
+        let m = meridian_arc(lat);
 
-        let easting = K0
+        let easting = UTM_K0
             * n
-            * (dlon
-                + dlon.powi(3) / 6.0 * lat.cos().powi(2) * (1.0 - t + c)
-                + dlon.powi(5) / 120.0
-                    * lat.cos().powi(4)
-                    * (5.0 - 18.0 * t + t.powi(2) + 72.0 * c - 58.0 * E2))
-            + 500000.0;
-
-        // Meridian arc
-        let a = A
-            / (1.0 + ((1.0 - E2) / (1.0 - E2 * lat.sin().powi(2))).sqrt())
-            * (1.0 - E2 / 4.0 - 3.0 * E2.powi(2) / 64.0 - 5.0 * E2.powi(3) / 256.0);
-        let alpha1 = 3.0 / 2.0 * E2 - 27.0 / 32.0 * E2.powi(3);
-        let alpha2 = 21.0 / 16.0 * E2.powi(2) - 55.0 / 32.0 * E2.powi(4);
-
-        let m = a * (lat - alpha1 * (2.0 * lat).sin() + alpha2 * (4.0 * lat).sin());
-        let northing = K0 * m;
+            * (a + (1.0 - t + c) * a.powi(3) / 6.0
+                + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+            + UTM_FALSE_EASTING;
+        let northing = UTM_K0
+            * (m + n
+                * lat.tan()
+                * (a * a / 2.0
+                    + (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+                    + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0));
 
         UTM::new(
             easting,
-            if latlon.latitude >= 0.0 { northing } else { northing + 10000000.0 },
+            if latlon.latitude >= 0.0 { northing } else { northing + UTM_FALSE_NORTHING_SOUTH },
             self.utm_zone,
             latlon.latitude >= 0.0,
         )
     }
 
+    /// Transform UTM (any zone, from the coordinate itself) back to WGS84
+    pub fn utm_to_latlon(&self, utm: &UTM) -> LatLon {
+        let (lat, lon) = self.utm_to_degrees(utm);
+        LatLon::new(lat, lon)
+    }
+
+    /// Unchecked (latitude, longitude) of a UTM coordinate
+    /// Snyder footpoint-latitude inverse
+    pub(crate) fn utm_to_degrees(&self, utm: &UTM) -> (f64, f64) {
+        let ep2 = WGS84_E2 / (1.0 - WGS84_E2);
+        let e1 = (1.0 - (1.0 - WGS84_E2).sqrt()) / (1.0 + (1.0 - WGS84_E2).sqrt());
+        let northing = if utm.is_north { utm.northing } else { utm.northing - UTM_FALSE_NORTHING_SOUTH };
+
+        let m = northing / UTM_K0;
+        let mu = m / (WGS84_A * (1.0 - WGS84_E2 / 4.0 - 3.0 * WGS84_E2.powi(2) / 64.0 - 5.0 * WGS84_E2.powi(3) / 256.0));
+        let phi1 = mu
+            + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+            + (21.0 * e1 * e1 / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+            + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+            + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+        let n1 = WGS84_A / (1.0 - WGS84_E2 * phi1.sin().powi(2)).sqrt();
+        let t1 = phi1.tan().powi(2);
+        let c1 = ep2 * phi1.cos().powi(2);
+        let r1 = WGS84_A * (1.0 - WGS84_E2) / (1.0 - WGS84_E2 * phi1.sin().powi(2)).powf(1.5);
+        let d = (utm.easting - UTM_FALSE_EASTING) / (n1 * UTM_K0);
+
+        let lat = phi1
+            - n1 * phi1.tan() / r1
+                * (d * d / 2.0
+                    - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                    + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1 - 252.0 * ep2 - 3.0 * c1 * c1) * d.powi(6) / 720.0);
+        let lon = (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+            + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1) * d.powi(5) / 120.0)
+            / phi1.cos();
+
+        let lon_origin = (utm.zone as f64 - 0.5) * 6.0 - 180.0;
+        (lat.to_degrees(), lon_origin + lon.to_degrees())
+    }
+
     /// Transform coordinate based on projection types
     pub fn transform(&self, source_coord: &str) -> Option<String> {
         match (self.source_proj, self.target_proj) {
@@ -154,6 +208,22 @@ mod tests {
         assert!(utm.easting > 200000.0 && utm.easting < 800000.0);
     }
 
+    #[test]
+    fn test_utm_round_trip() {
+        let transformer = CoordinateTransformer::new(ProjectionType::WGS84, ProjectionType::UTM);
+        let yuma = LatLon::new(32.6927, -114.6277);
+        let utm = transformer.latlon_to_utm(&yuma);
+        assert!((utm.easting - 722_402.0).abs() < 2.0 && (utm.northing - 3_619_708.0).abs() < 2.0);
+
+        let back = transformer.utm_to_latlon(&utm);
+        assert!((back.latitude - yuma.latitude).abs() < 1e-7);
+        assert!((back.longitude - yuma.longitude).abs() < 1e-7);
+
+        let zone12 = transformer.with_utm_zone(12);
+        let phoenix = zone12.utm_to_latlon(&zone12.latlon_to_utm(&LatLon::new(33.4484, -112.0742)));
+        assert!((phoenix.longitude + 112.0742).abs() < 1e-7);
+    }
+
     #[test]
     fn test_local_coordinate_distance() {
         let c1 = LocalCoordinate::new(0.0, 0.0, 0.0);
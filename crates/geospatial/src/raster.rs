@@ -1,6 +1,9 @@
 //! Raster data handling for satellite and UAV imagery
 
-use cybersomething_core::models::LatLon;
+use crate::projection::{CoordinateTransformer, ProjectionType, UTM_FALSE_NORTHING_SOUTH};
+use crate::tiled::{map_blocks, BandSource, TiledBand};
+use cybersomething_core::models::{LatLon, UTM};
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
 use ndarray::{Array2, ArrayView2};
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +34,39 @@ impl RasterBand {
         }
     }
 
+    /// Build a band from row-major data, computing min/max over valid pixels
+    pub fn from_data(
+        band_id: u32,
+        band_name: String,
+        rows: usize,
+        cols: usize,
+        data: Vec<f32>,
+        no_data_value: f32,
+    ) -> Self {
+        assert_eq!(data.len(), rows * cols, "Data length does not match raster shape");
+
+        let (min_value, max_value) = data
+            .iter()
+            .filter(|&&v| v != no_data_value && !v.is_nan())
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+
+        Self {
+            band_id,
+            band_name,
+            data,
+            rows,
+            cols,
+            no_data_value,
+            min_value,
+            max_value,
+        }
+    }
+
+    /// Whether a value is the no-data sentinel (or NaN)
+    pub fn is_no_data(&self, value: f32) -> bool {
+        value == self.no_data_value || value.is_nan()
+    }
+
     /// Set pixel value
     pub fn set_pixel(&mut self, row: usize, col: usize, value: f32) {
         if row < self.rows && col < self.cols {
@@ -52,6 +88,28 @@ impl RasterBand {
         }
     }
 
+    /// Copy a pixel window into a new band
+    pub fn read_window(&self, window: &PixelWindow) -> Option<RasterBand> {
+        if window.row_off + window.rows > self.rows || window.col_off + window.cols > self.cols {
+            return None;
+        }
+
+        let data = (window.row_off..window.row_off + window.rows)
+            .flat_map(|r| {
+                let start = r * self.cols + window.col_off;
+                self.data[start..start + window.cols].iter().copied()
+            })
+            .collect();
+        Some(RasterBand::from_data(
+            self.band_id,
+            self.band_name.clone(),
+            window.rows,
+            window.cols,
+            data,
+            self.no_data_value,
+        ))
+    }
+
     /// Normalize band to [0, 1]
     pub fn normalize(&self) -> Vec<f32> {
         let range = self.max_value - self.min_value;
@@ -104,6 +162,94 @@ pub struct RasterStats {
     pub std_dev: f32,
 }
 
+/// Affine pixel -> CRS mapping (GDAL order), row 0 / col 0 at the top-left corner
+/// x = origin_x + col·pixel_width + row·rotation_x
+/// y = origin_y + col·rotation_y + row·pixel_height
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoTransform {
+    pub origin_x: f64,
+    pub pixel_width: f64,
+    pub rotation_x: f64,
+    pub origin_y: f64,
+    pub rotation_y: f64,
+    pub pixel_height: f64, // Negative for north-up rasters
+}
+
+impl GeoTransform {
+    /// North-up transform from the top-left corner and positive pixel sizes
+    pub fn north_up(origin_x: f64, origin_y: f64, pixel_width: f64, pixel_height: f64) -> Self {
+        Self {
+            origin_x,
+            pixel_width,
+            rotation_x: 0.0,
+            origin_y,
+            rotation_y: 0.0,
+            pixel_height: -pixel_height.abs(),
+        }
+    }
+
+    /// North-up transform spanning (min_x, min_y)-(max_x, max_y) with the given shape
+    pub fn from_bounds(min: (f64, f64), max: (f64, f64), rows: usize, cols: usize) -> Self {
+        Self::north_up(min.0, max.1, (max.0 - min.0) / cols as f64, (max.1 - min.1) / rows as f64)
+    }
+
+    pub fn from_gdal(coefficients: [f64; 6]) -> Self {
+        let [origin_x, pixel_width, rotation_x, origin_y, rotation_y, pixel_height] = coefficients;
+        Self { origin_x, pixel_width, rotation_x, origin_y, rotation_y, pixel_height }
+    }
+
+    pub fn to_gdal(&self) -> [f64; 6] {
+        [self.origin_x, self.pixel_width, self.rotation_x, self.origin_y, self.rotation_y, self.pixel_height]
+    }
+
+    pub fn is_north_up(&self) -> bool {
+        self.rotation_x == 0.0 && self.rotation_y == 0.0 && self.pixel_height < 0.0
+    }
+
+    /// CRS coordinates of a fractional pixel position (row + 0.5 = pixel center)
+    pub fn apply(&self, row: f64, col: f64) -> (f64, f64) {
+        (
+            self.origin_x + col * self.pixel_width + row * self.rotation_x,
+            self.origin_y + col * self.rotation_y + row * self.pixel_height,
+        )
+    }
+
+    /// Fractional (row, col) of CRS coordinates; None for a degenerate transform
+    pub fn invert(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        let det = self.pixel_width * self.pixel_height - self.rotation_x * self.rotation_y;
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+        let (dx, dy) = (x - self.origin_x, y - self.origin_y);
+        let col = (self.pixel_height * dx - self.rotation_x * dy) / det;
+        let row = (self.pixel_width * dy - self.rotation_y * dx) / det;
+        Some((row, col))
+    }
+
+    /// Transform of a window starting at (row_off, col_off)
+    pub fn offset(&self, row_off: usize, col_off: usize) -> Self {
+        let (origin_x, origin_y) = self.apply(row_off as f64, col_off as f64);
+        Self { origin_x, origin_y, ..*self }
+    }
+
+    /// Same grid spacing, rotation and origin (to within `tolerance` CRS units)
+    pub fn is_aligned_with(&self, other: &GeoTransform, tolerance: f64) -> bool {
+        self.to_gdal()
+            .iter()
+            .zip(other.to_gdal())
+            .all(|(a, b)| (a - b).abs() <= tolerance)
+    }
+}
+
+/// Rectangular pixel window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PixelWindow {
+    pub row_off: usize,
+    pub col_off: usize,
+    pub rows: usize,
+    pub cols: usize,
+}
+
 /// Multi-band raster dataset (e.g., satellite image)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RasterDataset {
@@ -111,15 +257,106 @@ pub struct RasterDataset {
     pub bands: Vec<RasterBand>,
     pub extent: (LatLon, LatLon), // (sw, ne)
     pub crs: CoordinateSystem,
+    #[serde(default)]
+    pub geotransform: Option<GeoTransform>, // None = derived from extent per band shape
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum CoordinateSystem {
     WGS84,
     UTM11N,
+    UTM { zone: u8, is_north: bool }, // Any other WGS84 UTM zone
     LocalProjection,
 }
 
+/// UTM systems compare by (zone, hemisphere), so `UTM { zone: 11, is_north: true } == UTM11N`
+impl PartialEq for CoordinateSystem {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.utm_zone(), other.utm_zone()) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => std::mem::discriminant(self) == std::mem::discriminant(other),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for CoordinateSystem {}
+
+impl CoordinateSystem {
+    /// CRS for an EPSG code (4326, 326zz / 327zz UTM)
+    pub fn from_epsg(code: u32) -> Option<Self> {
+        match code {
+            4326 => Some(CoordinateSystem::WGS84),
+            32611 => Some(CoordinateSystem::UTM11N),
+            32601..=32660 => Some(CoordinateSystem::UTM { zone: (code - 32600) as u8, is_north: true }),
+            32701..=32760 => Some(CoordinateSystem::UTM { zone: (code - 32700) as u8, is_north: false }),
+            _ => None,
+        }
+    }
+
+    /// EPSG code, None for local projections
+    pub fn epsg(&self) -> Option<u32> {
+        match self.utm_zone() {
+            Some((zone, true)) => Some(32600 + zone as u32),
+            Some((zone, false)) => Some(32700 + zone as u32),
+            None if *self == CoordinateSystem::WGS84 => Some(4326),
+            None => None,
+        }
+    }
+
+    /// (zone, is_north) for UTM systems
+    pub fn utm_zone(&self) -> Option<(u8, bool)> {
+        match *self {
+            CoordinateSystem::UTM11N => Some((11, true)),
+            CoordinateSystem::UTM { zone, is_north } => Some((zone, is_north)),
+            CoordinateSystem::WGS84 | CoordinateSystem::LocalProjection => None,
+        }
+    }
+
+    /// CRS (x, y) of a geographic point (WGS84: lon/lat; local: passed through)
+    pub fn from_latlon(&self, latlon: &LatLon) -> (f64, f64) {
+        match self.utm_zone() {
+            Some((zone, is_north)) => {
+                let utm = CoordinateTransformer::new(ProjectionType::WGS84, ProjectionType::UTM)
+                    .with_utm_zone(zone)
+                    .latlon_to_utm(latlon);
+                // Northing against the CRS hemisphere's false northing, not the point's
+                let northing = match (is_north, utm.is_north) {
+                    (true, false) => utm.northing - UTM_FALSE_NORTHING_SOUTH,
+                    (false, true) => utm.northing + UTM_FALSE_NORTHING_SOUTH,
+                    _ => utm.northing,
+                };
+                (utm.easting, northing)
+            }
+            None => (latlon.longitude, latlon.latitude),
+        }
+    }
+
+    /// Geographic point of CRS (x, y)
+    pub fn to_latlon(&self, x: f64, y: f64) -> LatLon {
+        match self.utm_zone() {
+            Some((zone, is_north)) => CoordinateTransformer::new(ProjectionType::UTM, ProjectionType::WGS84)
+                .utm_to_latlon(&UTM::new(x, y, zone, is_north)),
+            None => LatLon::new(y, x),
+        }
+    }
+
+    /// Geographic point of CRS (x, y), None when it falls outside WGS84 range
+    pub fn try_to_latlon(&self, x: f64, y: f64) -> Option<LatLon> {
+        let (lat, lon) = match self.utm_zone() {
+            Some((zone, is_north)) => CoordinateTransformer::new(ProjectionType::UTM, ProjectionType::WGS84)
+                .utm_to_degrees(&UTM::new(x, y, zone, is_north)),
+            None => (y, x),
+        };
+        LatLon::try_new(lat, lon)
+    }
+
+    /// Whether CRS units are meters
+    pub fn is_metric(&self) -> bool {
+        !matches!(self, CoordinateSystem::WGS84)
+    }
+}
+
 impl RasterDataset {
     pub fn new(dataset_id: u32, extent: (LatLon, LatLon)) -> Self {
         Self {
@@ -127,6 +364,61 @@ impl RasterDataset {
             bands: Vec::new(),
             extent,
             crs: CoordinateSystem::WGS84,
+            geotransform: None,
+        }
+    }
+
+    /// Dataset on an explicit grid; the extent is taken from the grid's corners
+    /// (panics if a corner is outside WGS84 range; see `try_from_geotransform`)
+    pub fn from_geotransform(
+        dataset_id: u32,
+        crs: CoordinateSystem,
+        geotransform: GeoTransform,
+        rows: usize,
+        cols: usize,
+    ) -> Self {
+        let corners = [(0, 0), (0, cols), (rows, 0), (rows, cols)]
+            .map(|(r, c)| {
+                let (x, y) = geotransform.apply(r as f64, c as f64);
+                crs.to_latlon(x, y)
+            });
+        Self::from_corners(dataset_id, crs, geotransform, &corners)
+    }
+
+    /// `from_geotransform` for untrusted transforms: error when a corner is outside WGS84 range
+    pub fn try_from_geotransform(
+        dataset_id: u32,
+        crs: CoordinateSystem,
+        geotransform: GeoTransform,
+        rows: usize,
+        cols: usize,
+    ) -> Result<Self> {
+        let mut corners = Vec::with_capacity(4);
+        for (r, c) in [(0, 0), (0, cols), (rows, 0), (rows, cols)] {
+            let (x, y) = geotransform.apply(r as f64, c as f64);
+            corners.push(crs.try_to_latlon(x, y).ok_or_else(|| CybersomethingError::DataValidationError {
+                reason: format!("dataset {} corner ({}, {}) is outside WGS84 range in {:?}", dataset_id, x, y, crs),
+            })?);
+        }
+        Ok(Self::from_corners(dataset_id, crs, geotransform, &corners))
+    }
+
+    fn from_corners(dataset_id: u32, crs: CoordinateSystem, geotransform: GeoTransform, corners: &[LatLon]) -> Self {
+        let fold = |f: fn(f64, f64) -> f64, init: f64, lat: bool| {
+            corners
+                .iter()
+                .map(|p| if lat { p.latitude } else { p.longitude })
+                .fold(init, f)
+        };
+        let sw = LatLon::new(fold(f64::min, f64::INFINITY, true), fold(f64::min, f64::INFINITY, false));
+        let ne = LatLon::new(fold(f64::max, f64::NEG_INFINITY, true), fold(f64::max, f64::NEG_INFINITY, false));
+
+        Self {
+            dataset_id,
+            bands: Vec::new(),
+            extent: (sw, ne),
+            crs,
+            geotransform: Some(geotransform),
         }
     }
 
@@ -140,26 +432,133 @@ impl RasterDataset {
         self.bands.iter().find(|b| b.band_name == band_name)
     }
 
-    /// Compute NDVI (Normalized Difference Vegetation Index)
-    /// NDVI = (NIR - Red) / (NIR + Red)
-    pub fn compute_ndvi(&self) -> Option<RasterBand> {
-        let nir = self.get_band("NIR")?;
-        let red = self.get_band("Red")?;
-
-        let mut ndvi = RasterBand::new(999, "NDVI".to_string(), nir.rows, nir.cols);
-
-        for row in 0..nir.rows {
-            for col in 0..nir.cols {
-                if let (Some(nir_val), Some(red_val)) = (nir.get_pixel(row, col), red.get_pixel(row, col)) {
-                    if nir_val + red_val > 0.001 {
-                        let value = (nir_val - red_val) / (nir_val + red_val);
-                        ndvi.set_pixel(row, col, value);
-                    }
-                }
-            }
+    /// Pixel grid of a band: the explicit geotransform, or the extent divided by the band's shape
+    pub fn geotransform_for(&self, band: &RasterBand) -> GeoTransform {
+        self.geotransform.unwrap_or_else(|| {
+            let (sw, ne) = self.extent;
+            GeoTransform::from_bounds(self.crs.from_latlon(&sw), self.crs.from_latlon(&ne), band.rows, band.cols)
+        })
+    }
+
+    /// Geographic location of a fractional pixel position (integers = pixel corners)
+    pub fn pixel_to_coord(&self, band: &RasterBand, row: f64, col: f64) -> LatLon {
+        let (x, y) = self.geotransform_for(band).apply(row, col);
+        self.crs.to_latlon(x, y)
+    }
+
+    /// Pixel (row, col) containing a location, if inside the band
+    pub fn coord_to_pixel(&self, band: &RasterBand, location: &LatLon) -> Option<(usize, usize)> {
+        let (x, y) = self.crs.from_latlon(location);
+        let (row, col) = self.geotransform_for(band).invert(x, y)?;
+        if row < 0.0 || col < 0.0 || row >= band.rows as f64 || col >= band.cols as f64 {
+            return None;
         }
+        Some((row as usize, col as usize))
+    }
+
+    /// Center of a pixel
+    pub fn pixel_center(&self, band: &RasterBand, row: usize, col: usize) -> LatLon {
+        self.pixel_to_coord(band, row as f64 + 0.5, col as f64 + 0.5)
+    }
 
-        Some(ndvi)
+    /// Pixel (width, height) in meters at the raster's center
+    pub fn pixel_size_m(&self, band: &RasterBand) -> (f64, f64) {
+        let gt = self.geotransform_for(band);
+        if self.crs.is_metric() {
+            return (gt.pixel_width.hypot(gt.rotation_y), gt.rotation_x.hypot(gt.pixel_height));
+        }
+
+        let (rows, cols) = (band.rows as f64, band.cols as f64);
+        let at = |r: f64, c: f64| self.pixel_to_coord(band, r, c);
+        let width = at(rows / 2.0, 0.0).distance_to(&at(rows / 2.0, cols));
+        let height = at(0.0, 0.0).distance_to(&at(rows, 0.0));
+        (width / cols, height / rows)
+    }
+
+    /// Pixel window of a band covering a (sw, ne) box, clipped to the band
+    pub fn window_for(&self, band: &RasterBand, bounds: (LatLon, LatLon)) -> Option<PixelWindow> {
+        let gt = self.geotransform_for(band);
+        let (sw, ne) = bounds;
+        let corners = [
+            (sw.latitude, sw.longitude),
+            (sw.latitude, ne.longitude),
+            (ne.latitude, sw.longitude),
+            (ne.latitude, ne.longitude),
+        ]
+        .map(|(lat, lon)| {
+            let (x, y) = self.crs.from_latlon(&LatLon::new(lat, lon));
+            gt.invert(x, y)
+        });
+
+        let (mut r0, mut c0, mut r1, mut c1) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for (r, c) in corners.into_iter().collect::<Option<Vec<_>>>()? {
+            (r0, c0, r1, c1) = (r0.min(r), c0.min(c), r1.max(r), c1.max(c));
+        }
+
+        let row_off = r0.floor().max(0.0) as usize;
+        let col_off = c0.floor().max(0.0) as usize;
+        let row_end = (r1.ceil().max(0.0) as usize).min(band.rows);
+        let col_end = (c1.ceil().max(0.0) as usize).min(band.cols);
+        (row_end > row_off && col_end > col_off).then(|| PixelWindow {
+            row_off,
+            col_off,
+            rows: row_end - row_off,
+            cols: col_end - col_off,
+        })
+    }
+
+    /// Read the pixels of a named band covering a (sw, ne) box as a georeferenced dataset
+    pub fn read_window(&self, band_name: &str, bounds: (LatLon, LatLon)) -> Result<RasterDataset> {
+        let band = self.band_or_err(band_name)?;
+        let window = self.window_for(band, bounds).ok_or_else(|| CybersomethingError::DataValidationError {
+            reason: format!("window does not overlap band '{}' of dataset {}", band_name, self.dataset_id),
+        })?;
+
+        let transform = self.geotransform_for(band).offset(window.row_off, window.col_off);
+        let mut out = RasterDataset::from_geotransform(self.dataset_id, self.crs, transform, window.rows, window.cols);
+        out.bands.extend(band.read_window(&window));
+        Ok(out)
+    }
+
+    /// Error unless all bands share one shape (and so one pixel grid)
+    pub fn ensure_aligned(&self, bands: &[&RasterBand]) -> Result<()> {
+        let Some(first) = bands.first() else {
+            return Ok(());
+        };
+        match bands.iter().find(|b| b.rows != first.rows || b.cols != first.cols) {
+            Some(other) => Err(CybersomethingError::DataValidationError {
+                reason: format!(
+                    "band '{}' is {}x{} but band '{}' is {}x{}",
+                    other.band_name, other.rows, other.cols, first.band_name, first.rows, first.cols
+                ),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether a band of another dataset lies on the same pixel grid as one of ours
+    pub fn is_aligned_with(&self, band: &RasterBand, other: &RasterDataset, other_band: &RasterBand) -> bool {
+        self.crs == other.crs
+            && band.rows == other_band.rows
+            && band.cols == other_band.cols
+            && self
+                .geotransform_for(band)
+                .is_aligned_with(&other.geotransform_for(other_band), 1e-9)
+    }
+
+    pub(crate) fn band_or_err(&self, band_name: &str) -> Result<&RasterBand> {
+        self.get_band(band_name).ok_or_else(|| CybersomethingError::DataValidationError {
+            reason: format!("dataset {} has no band '{}'", self.dataset_id, band_name),
+        })
+    }
+
+    /// Compute NDVI (Normalized Difference Vegetation Index)
+    /// NDVI = (NIR - Red) / (NIR + Red)
+    pub fn compute_ndvi(&self) -> Result<RasterBand> {
+        let nir = self.band_or_err("NIR")?;
+        let red = self.band_or_err("Red")?;
+        self.ensure_aligned(&[nir, red])?;
+        Ok(ndvi_band(nir, red))
     }
 
     /// Classify pixels by value thresholds
@@ -183,6 +582,31 @@ impl RasterDataset {
     }
 }
 
+/// NDVI of same-shape NIR and Red bands (nodata where either input is)
+pub fn ndvi_band(nir: &RasterBand, red: &RasterBand) -> RasterBand {
+    let mut ndvi = RasterBand::new(999, "NDVI".to_string(), nir.rows, nir.cols);
+    ndvi.no_data_value = nir.no_data_value;
+
+    for row in 0..nir.rows {
+        for col in 0..nir.cols {
+            if let (Some(nir_val), Some(red_val)) = (nir.get_pixel(row, col), red.get_pixel(row, col)) {
+                if nir.is_no_data(nir_val) || red.is_no_data(red_val) {
+                    ndvi.data[row * nir.cols + col] = ndvi.no_data_value;
+                } else if nir_val + red_val > 0.001 {
+                    let value = (nir_val - red_val) / (nir_val + red_val);
+                    ndvi.set_pixel(row, col, value);
+                }
+            }
+        }
+    }
+    ndvi
+}
+
+/// Compute NDVI block by block into a tiled band (for rasters larger than memory)
+pub fn compute_ndvi_tiled(nir: &dyn BandSource, red: &dyn BandSource, output: &TiledBand) -> Result<()> {
+    map_blocks(&[nir, red], &[output], |w| Ok(vec![ndvi_band(&w[0], &w[1])]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,4 +644,136 @@ mod tests {
 
         assert_eq!(dataset.bands.len(), 0);
     }
+
+    fn sonoran_dataset() -> RasterDataset {
+        RasterDataset::new(1, (LatLon::new(33.0, -112.0), LatLon::new(33.4, -111.5)))
+    }
+
+    #[test]
+    fn test_geotransform_round_trip() {
+        let gt = GeoTransform::from_gdal([400_000.0, 10.0, 2.0, 3_700_000.0, 1.0, -10.0]);
+        let (x, y) = gt.apply(3.5, 7.25);
+        let (row, col) = gt.invert(x, y).unwrap();
+        assert!((row - 3.5).abs() < 1e-9 && (col - 7.25).abs() < 1e-9);
+        assert!(!gt.is_north_up());
+
+        let window = gt.offset(2, 3);
+        assert_eq!(window.apply(0.0, 0.0), gt.apply(2.0, 3.0));
+    }
+
+    #[test]
+    fn test_pixel_coord_mapping() {
+        let dataset = sonoran_dataset();
+        let band = RasterBand::new(1, "DEM".to_string(), 4, 5);
+
+        let corner = dataset.pixel_to_coord(&band, 0.0, 0.0);
+        assert_eq!((corner.latitude, corner.longitude), (33.4, -112.0));
+        let center = dataset.pixel_center(&band, 3, 4);
+        assert!((center.latitude - 33.05).abs() < 1e-9 && (center.longitude + 111.55).abs() < 1e-9);
+
+        assert_eq!(dataset.coord_to_pixel(&band, &center), Some((3, 4)));
+        assert_eq!(dataset.coord_to_pixel(&band, &LatLon::new(33.5, -111.9)), None);
+    }
+
+    #[test]
+    fn test_utm_pixel_coord_round_trip() {
+        // 30 m grid in UTM 12N around Phoenix
+        let crs = CoordinateSystem::UTM { zone: 12, is_north: true };
+        let gt = GeoTransform::from_gdal([400_000.0, 30.0, 0.0, 3_705_000.0, 0.0, -30.0]);
+        let dataset = RasterDataset::from_geotransform(1, crs, gt, 100, 100);
+        let band = RasterBand::new(1, "DEM".to_string(), 100, 100);
+
+        let corner = dataset.pixel_to_coord(&band, 0.0, 0.0);
+        assert!((corner.latitude - 33.4798).abs() < 1e-4 && (corner.longitude + 112.0763).abs() < 1e-4);
+        assert!(dataset.extent.0.latitude < corner.latitude && dataset.extent.1.longitude > corner.longitude);
+
+        for (row, col) in [(0, 0), (17, 42), (99, 99)] {
+            let center = dataset.pixel_center(&band, row, col);
+            assert_eq!(dataset.coord_to_pixel(&band, &center), Some((row, col)));
+            let (x, y) = crs.from_latlon(&center);
+            assert!((x - (400_015.0 + 30.0 * col as f64)).abs() < 1e-3);
+            assert!((y - (3_704_985.0 - 30.0 * row as f64)).abs() < 1e-3);
+        }
+
+        let window = dataset.window_for(&band, (dataset.pixel_center(&band, 20, 10), dataset.pixel_center(&band, 10, 30)));
+        assert_eq!(window.map(|w| (w.row_off, w.col_off, w.rows, w.cols)), Some((10, 10, 11, 21)));
+    }
+
+    #[test]
+    fn test_southern_utm_keeps_its_hemisphere() {
+        // UTM 37S across the equator (Kenya): northings stay on the southern false northing
+        let south = CoordinateSystem::UTM { zone: 37, is_north: false };
+        for latitude in [-1.5, 0.5] {
+            let point = LatLon::new(latitude, 37.0);
+            let (x, y) = south.from_latlon(&point);
+            assert_eq!(y > 10_000_000.0, latitude > 0.0);
+            let back = south.to_latlon(x, y);
+            assert!((back.latitude - latitude).abs() < 1e-6 && (back.longitude - 37.0).abs() < 1e-6);
+        }
+        let (_, y) = CoordinateSystem::UTM { zone: 37, is_north: true }.from_latlon(&LatLon::new(-1.5, 37.0));
+        assert!(y < 0.0);
+    }
+
+    #[test]
+    fn test_utm_variants_compare_by_zone() {
+        let general = CoordinateSystem::UTM { zone: 11, is_north: true };
+        assert_eq!(general, CoordinateSystem::UTM11N);
+        assert_ne!(CoordinateSystem::UTM { zone: 11, is_north: false }, CoordinateSystem::UTM11N);
+        assert_ne!(CoordinateSystem::WGS84, CoordinateSystem::LocalProjection);
+        assert_eq!(CoordinateSystem::from_epsg(32611).unwrap().epsg(), general.epsg());
+
+        let band = RasterBand::new(1, "DEM".to_string(), 4, 4);
+        let gt = GeoTransform::north_up(400_000.0, 3_700_000.0, 30.0, 30.0);
+        let a = RasterDataset::from_geotransform(1, CoordinateSystem::UTM11N, gt, 4, 4);
+        let b = RasterDataset::from_geotransform(2, general, gt, 4, 4);
+        assert!(a.is_aligned_with(&band, &b, &band));
+    }
+
+    #[test]
+    fn test_read_window() {
+        let mut dataset = sonoran_dataset();
+        let data = (0..20).map(|v| v as f32).collect();
+        dataset.add_band(RasterBand::from_data(1, "DEM".to_string(), 4, 5, data, -9999.0));
+
+        let window = dataset
+            .read_window("DEM", (LatLon::new(33.15, -111.85), LatLon::new(33.25, -111.75)))
+            .unwrap();
+        let band = &window.bands[0];
+        assert_eq!((band.rows, band.cols), (2, 2));
+        assert_eq!(band.data, vec![6.0, 7.0, 11.0, 12.0]);
+        assert!((window.extent.1.latitude - 33.3).abs() < 1e-9);
+
+        let outside = (LatLon::new(34.0, -110.0), LatLon::new(34.1, -109.9));
+        assert!(dataset.read_window("DEM", outside).is_err());
+    }
+
+    #[test]
+    fn test_ndvi_rejects_mismatched_bands() {
+        let mut dataset = sonoran_dataset();
+        dataset.add_band(RasterBand::from_data(1, "NIR".to_string(), 1, 2, vec![0.6, -9999.0], -9999.0));
+        dataset.add_band(RasterBand::from_data(2, "Red".to_string(), 1, 2, vec![0.2, 0.1], -9999.0));
+        let ndvi = dataset.compute_ndvi().unwrap();
+        assert!((ndvi.data[0] - 0.5).abs() < 1e-6);
+        assert_eq!(ndvi.data[1], -9999.0);
+
+        dataset.bands[1] = RasterBand::new(2, "Red".to_string(), 2, 2);
+        assert!(dataset.compute_ndvi().is_err());
+
+        let other = sonoran_dataset();
+        let nir = &dataset.bands[0];
+        assert!(dataset.is_aligned_with(nir, &other, nir));
+        assert!(!dataset.is_aligned_with(nir, &other, &dataset.bands[1]));
+    }
+
+    #[test]
+    fn test_tiled_ndvi_matches_in_memory() {
+        let band = |name: &str, offset: f32| {
+            let data = (0..30).map(|i| if i == 4 { -9999.0 } else { (i as f32 + offset) / 40.0 }).collect();
+            RasterBand::from_data(1, name.to_string(), 5, 6, data, -9999.0)
+        };
+        let (nir, red) = (band("NIR", 10.0), band("Red", 0.0));
+        crate::tiled::tests::assert_blockwise_matches("tiled-ndvi", &[&nir, &red], &[&ndvi_band(&nir, &red)], |i, o| {
+            compute_ndvi_tiled(i[0], i[1], o[0])
+        });
+    }
 }
@@ -0,0 +1,225 @@
+//! Terrain derivatives from DEM rasters
+//! Slope and aspect via Horn (3×3 Sobel-weighted) or Zevenbergen-Thorne
+//! (central difference) kernels, plus a wind-alignment spread modifier for S_i.
+
+use crate::raster::{RasterBand, RasterDataset};
+use cybersomething_core::math::flight_energy::ElevationSource;
+use cybersomething_core::models::LatLon;
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Band id assigned to derived slope rasters
+pub const SLOPE_BAND_ID: u32 = 1010;
+/// Band id assigned to derived aspect rasters
+pub const ASPECT_BAND_ID: u32 = 1011;
+/// Aspect value for flat cells (no downslope direction)
+pub const FLAT_ASPECT: f32 = -1.0;
+
+/// Finite-difference kernel for surface gradients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlopeMethod {
+    Horn,              // Smoother, preferred for rough terrain
+    ZevenbergenThorne, // Central differences, preferred for smooth terrain
+}
+
+/// Slope and aspect bands derived from a DEM
+#[derive(Debug, Clone)]
+pub struct TerrainDerivatives {
+    pub slope: RasterBand,  // Degrees from horizontal
+    pub aspect: RasterBand, // Degrees clockwise from north the slope faces, -1 = flat
+}
+
+/// Compute slope and aspect with real cell spacing in meters
+pub fn slope_aspect(dem: &RasterBand, cell_width_m: f64, cell_height_m: f64, method: SlopeMethod) -> TerrainDerivatives {
+    let (rows, cols) = (dem.rows, dem.cols);
+    let no_data = dem.no_data_value;
+
+    let (slope, aspect): (Vec<f32>, Vec<f32>) = (0..rows * cols)
+        .into_par_iter()
+        .map(|idx| {
+            let (row, col) = (idx / cols, idx % cols);
+            let center = dem.data[idx];
+            if dem.is_no_data(center) {
+                return (no_data, no_data);
+            }
+
+            // Neighbour elevation with edge replication and no-data fill
+            let z = |dr: i64, dc: i64| -> f64 {
+                let r = (row as i64 + dr).clamp(0, rows as i64 - 1) as usize;
+                let c = (col as i64 + dc).clamp(0, cols as i64 - 1) as usize;
+                let v = dem.data[r * cols + c];
+                if dem.is_no_data(v) { center as f64 } else { v as f64 }
+            };
+
+            // dz/dx positive eastward, dz/dy positive southward (row order)
+            let (dzdx, dzdy) = match method {
+                SlopeMethod::Horn => (
+                    ((z(-1, 1) + 2.0 * z(0, 1) + z(1, 1)) - (z(-1, -1) + 2.0 * z(0, -1) + z(1, -1)))
+                        / (8.0 * cell_width_m),
+                    ((z(1, -1) + 2.0 * z(1, 0) + z(1, 1)) - (z(-1, -1) + 2.0 * z(-1, 0) + z(-1, 1)))
+                        / (8.0 * cell_height_m),
+                ),
+                SlopeMethod::ZevenbergenThorne => (
+                    (z(0, 1) - z(0, -1)) / (2.0 * cell_width_m),
+                    (z(1, 0) - z(-1, 0)) / (2.0 * cell_height_m),
+                ),
+            };
+
+            let gradient = (dzdx * dzdx + dzdy * dzdy).sqrt();
+            let slope_deg = gradient.atan().to_degrees();
+            let aspect_deg = if gradient < 1e-9 {
+                FLAT_ASPECT as f64
+            } else {
+                // Downslope vector is (-dz/dx east, +dz/dy north)
+                ((-dzdx).atan2(dzdy).to_degrees() + 360.0) % 360.0
+            };
+
+            (slope_deg as f32, aspect_deg as f32)
+        })
+        .unzip();
+
+    TerrainDerivatives {
+        slope: RasterBand::from_data(SLOPE_BAND_ID, "Slope".to_string(), rows, cols, slope, no_data),
+        aspect: RasterBand::from_data(ASPECT_BAND_ID, "Aspect".to_string(), rows, cols, aspect, no_data),
+    }
+}
+
+/// Spread multiplier in [1, 1 + gain]; peaks when wind blows from the direction the slope faces (upslope)
+pub fn wind_alignment_factor(aspect_deg: f64, wind_from_deg: f64, gain: f64) -> f64 {
+    if aspect_deg < 0.0 {
+        return 1.0; // Flat
+    }
+    let alignment = (aspect_deg - wind_from_deg).to_radians().cos().max(0.0);
+    1.0 + gain * alignment
+}
+
+/// Effective slope (degrees, capped at 90°) raised where aspect lines up with the prevailing wind
+pub fn wind_adjusted_slope(slope: &RasterBand, aspect: &RasterBand, wind_from_deg: f64, gain: f64) -> Result<RasterBand> {
+    if slope.rows != aspect.rows || slope.cols != aspect.cols {
+        return Err(CybersomethingError::DataValidationError {
+            reason: format!(
+                "slope is {}x{} but aspect is {}x{}",
+                slope.rows, slope.cols, aspect.rows, aspect.cols
+            ),
+        });
+    }
+
+    let adjusted = slope
+        .data
+        .par_iter()
+        .zip(aspect.data.par_iter())
+        .map(|(&s, &a)| {
+            if slope.is_no_data(s) || aspect.is_no_data(a) {
+                slope.no_data_value
+            } else {
+                ((s as f64) * wind_alignment_factor(a as f64, wind_from_deg, gain)).min(90.0) as f32
+            }
+        })
+        .collect();
+
+    Ok(RasterBand::from_data(
+        SLOPE_BAND_ID,
+        "WindAdjustedSlope".to_string(),
+        slope.rows,
+        slope.cols,
+        adjusted,
+        slope.no_data_value,
+    ))
+}
+
+impl RasterDataset {
+    /// Derive slope and aspect from a named DEM band using the dataset's pixel spacing
+    pub fn derive_terrain(&self, dem_band: &str, method: SlopeMethod) -> Result<TerrainDerivatives> {
+        let dem = self.get_band(dem_band).ok_or_else(|| CybersomethingError::DataValidationError {
+            reason: format!("dataset {} has no band '{}'", self.dataset_id, dem_band),
+        })?;
+
+        let (width_m, height_m) = self.pixel_size_m(dem);
+        Ok(slope_aspect(dem, width_m, height_m, method))
+    }
+}
+
+/// DEM band as an elevation source for flight energy (nearest pixel)
+pub struct DemElevation<'a> {
+    pub dataset: &'a RasterDataset,
+    pub band: &'a RasterBand,
+}
+
+impl<'a> DemElevation<'a> {
+    pub fn new(dataset: &'a RasterDataset, band: &'a RasterBand) -> Self {
+        Self { dataset, band }
+    }
+}
+
+impl ElevationSource for DemElevation<'_> {
+    fn elevation_m(&self, location: &LatLon) -> Option<f64> {
+        let (row, col) = self.dataset.coord_to_pixel(self.band, location)?;
+        let value = self.band.get_pixel(row, col)?;
+        (!self.band.is_no_data(value)).then_some(value as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3×3 DEM rising `rise_per_col` meters per column eastward
+    fn eastward_ramp(rise_per_col: f32) -> RasterBand {
+        let data = (0..9).map(|i| (i % 3) as f32 * rise_per_col).collect();
+        RasterBand::from_data(1, "DEM".to_string(), 3, 3, data, -9999.0)
+    }
+
+    #[test]
+    fn test_ramp_slope_and_aspect() {
+        // 10 m rise over 10 m cells = 45°, facing west
+        let dem = eastward_ramp(10.0);
+        for method in [SlopeMethod::Horn, SlopeMethod::ZevenbergenThorne] {
+            let terrain = slope_aspect(&dem, 10.0, 10.0, method);
+            assert!((terrain.slope.get_pixel(1, 1).unwrap() - 45.0).abs() < 1e-4);
+            assert!((terrain.aspect.get_pixel(1, 1).unwrap() - 270.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_north_rising_faces_south() {
+        let data = (0..9).map(|i| (2 - i / 3) as f32 * 5.0).collect();
+        let dem = RasterBand::from_data(1, "DEM".to_string(), 3, 3, data, -9999.0);
+        let terrain = slope_aspect(&dem, 10.0, 10.0, SlopeMethod::Horn);
+        assert!((terrain.aspect.get_pixel(1, 1).unwrap() - 180.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_dem_elevation_source() {
+        let dataset = RasterDataset::new(1, (LatLon::new(33.0, -112.0), LatLon::new(33.03, -111.97)));
+        let dem = eastward_ramp(10.0);
+        let source = DemElevation::new(&dataset, &dem);
+        assert_eq!(source.elevation_m(&LatLon::new(33.015, -111.975)), Some(20.0));
+        assert_eq!(source.elevation_m(&LatLon::new(33.015, -111.995)), Some(0.0));
+        assert_eq!(source.elevation_m(&LatLon::new(33.015, -111.90)), None);
+    }
+
+    #[test]
+    fn test_flat_and_no_data() {
+        let mut dem = RasterBand::from_data(1, "DEM".to_string(), 3, 3, vec![100.0; 9], -9999.0);
+        dem.data[0] = -9999.0;
+        let terrain = slope_aspect(&dem, 10.0, 10.0, SlopeMethod::Horn);
+
+        assert_eq!(terrain.slope.get_pixel(1, 1), Some(0.0));
+        assert_eq!(terrain.aspect.get_pixel(1, 1), Some(FLAT_ASPECT));
+        assert_eq!(terrain.slope.get_pixel(0, 0), Some(-9999.0));
+    }
+
+    #[test]
+    fn test_wind_alignment_raises_slope() {
+        // West-facing slope: wind from the west drives fire upslope
+        assert!((wind_alignment_factor(270.0, 270.0, 0.5) - 1.5).abs() < 1e-9);
+        assert_eq!(wind_alignment_factor(270.0, 90.0, 0.5), 1.0);
+        assert_eq!(wind_alignment_factor(FLAT_ASPECT as f64, 270.0, 0.5), 1.0);
+
+        let terrain = slope_aspect(&eastward_ramp(5.0), 10.0, 10.0, SlopeMethod::Horn);
+        let adjusted = wind_adjusted_slope(&terrain.slope, &terrain.aspect, 270.0, 0.5).unwrap();
+        let base = terrain.slope.get_pixel(1, 1).unwrap();
+        assert!((adjusted.get_pixel(1, 1).unwrap() - base * 1.5).abs() < 1e-4);
+    }
+}
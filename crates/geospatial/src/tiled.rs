@@ -0,0 +1,763 @@
+//! Tiled on-disk raster bands with an LRU block cache and overview pyramids
+//! Bands are split into fixed-size square blocks stored as little-endian f32
+//! files and loaded lazily, so regional rasters need not fit in memory.
+//! Missing block files read as nodata (sparse coverage).
+//! Consumers read through `BandSource` and run block by block via `map_blocks`.
+
+use crate::raster::{PixelWindow, RasterBand};
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Default block edge (pixels)
+pub const DEFAULT_BLOCK_SIZE: usize = 256;
+/// Default number of blocks held in memory
+pub const DEFAULT_CACHE_BLOCKS: usize = 64;
+
+const META_FILE: &str = "band.json";
+
+/// Read access shared by in-memory and tiled bands
+pub trait BandSource {
+    fn band_name(&self) -> &str;
+    /// (rows, cols)
+    fn shape(&self) -> (usize, usize);
+    fn no_data_value(&self) -> f32;
+    /// Copy a window into an in-memory band
+    fn window(&self, window: &PixelWindow) -> Result<RasterBand>;
+
+    fn pixel(&self, row: usize, col: usize) -> Result<Option<f32>> {
+        let (rows, cols) = self.shape();
+        if row >= rows || col >= cols {
+            return Ok(None);
+        }
+        let window = PixelWindow { row_off: row, col_off: col, rows: 1, cols: 1 };
+        Ok(self.window(&window)?.data.first().copied())
+    }
+}
+
+fn window_error(band_name: &str, window: &PixelWindow) -> CybersomethingError {
+    CybersomethingError::DataValidationError {
+        reason: format!(
+            "window {}x{} at ({}, {}) is outside band '{}'",
+            window.rows, window.cols, window.row_off, window.col_off, band_name
+        ),
+    }
+}
+
+impl BandSource for RasterBand {
+    fn band_name(&self) -> &str {
+        &self.band_name
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn no_data_value(&self) -> f32 {
+        self.no_data_value
+    }
+
+    fn window(&self, window: &PixelWindow) -> Result<RasterBand> {
+        self.read_window(window).ok_or_else(|| window_error(&self.band_name, window))
+    }
+}
+
+/// Overview downsampling rule (2×2 -> 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverviewResampling {
+    Average, // Continuous data (NDVI, elevation, P_i)
+    Mode,    // Classes (land cover, risk zones)
+}
+
+impl OverviewResampling {
+    /// Combine valid source pixels; None when all are nodata
+    fn reduce(&self, values: &[f32]) -> Option<f32> {
+        if values.is_empty() {
+            return None;
+        }
+        match self {
+            OverviewResampling::Average => Some(values.iter().sum::<f32>() / values.len() as f32),
+            OverviewResampling::Mode => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(f32::total_cmp);
+                let (mut best, mut best_run) = (sorted[0], 0);
+                for run in sorted.chunk_by(|a, b| a == b) {
+                    if run.len() > best_run {
+                        (best, best_run) = (run[0], run.len()); // Ties keep the smallest value
+                    }
+                }
+                Some(best)
+            }
+        }
+    }
+}
+
+/// Persisted description of a tiled band
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TiledBandMeta {
+    pub band_id: u32,
+    pub band_name: String,
+    pub rows: usize,
+    pub cols: usize,
+    pub block_size: usize,
+    pub no_data_value: f32,
+    pub overviews: Vec<(usize, usize)>, // (rows, cols) of levels 1..
+    pub resampling: Option<OverviewResampling>,
+}
+
+type BlockKey = (usize, usize, usize); // (level, block_row, block_col)
+
+/// Least-recently-used block cache
+#[derive(Debug, Default)]
+struct BlockCache {
+    capacity: usize,
+    tick: u64,
+    blocks: HashMap<BlockKey, (u64, Arc<Vec<f32>>)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl BlockCache {
+    fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), ..Default::default() }
+    }
+
+    fn get(&mut self, key: &BlockKey) -> Option<Arc<Vec<f32>>> {
+        self.tick += 1;
+        let tick = self.tick;
+        match self.blocks.get_mut(key) {
+            Some((used, block)) => {
+                *used = tick;
+                self.hits += 1;
+                Some(block.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Lookup that refreshes recency without counting a hit or miss
+    fn peek(&mut self, key: &BlockKey) -> Option<Arc<Vec<f32>>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.blocks.get_mut(key).map(|(used, block)| {
+            *used = tick;
+            block.clone()
+        })
+    }
+
+    fn insert(&mut self, key: BlockKey, block: Arc<Vec<f32>>) {
+        if !self.blocks.contains_key(&key) && self.blocks.len() >= self.capacity {
+            if let Some(oldest) = self.blocks.iter().min_by_key(|(_, (used, _))| *used).map(|(k, _)| *k) {
+                self.blocks.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.blocks.insert(key, (self.tick, block));
+    }
+}
+
+/// Cache counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub resident_blocks: usize,
+}
+
+/// Raster band stored as block files under a directory
+#[derive(Debug)]
+pub struct TiledBand {
+    root: PathBuf,
+    meta: TiledBandMeta,
+    cache: Mutex<BlockCache>,
+    writes: Mutex<()>, // Held across block stores and cache-miss loads so no update is lost or overwritten by a stale read
+}
+
+impl TiledBand {
+    /// Create an empty (all-nodata) band
+    pub fn create(
+        root: impl AsRef<Path>,
+        band_id: u32,
+        band_name: &str,
+        rows: usize,
+        cols: usize,
+        block_size: usize,
+        no_data_value: f32,
+    ) -> Result<Self> {
+        if block_size == 0 {
+            return Err(CybersomethingError::DataValidationError {
+                reason: "block size must be positive".to_string(),
+            });
+        }
+        let band = Self {
+            root: root.as_ref().to_path_buf(),
+            meta: TiledBandMeta {
+                band_id,
+                band_name: band_name.to_string(),
+                rows,
+                cols,
+                block_size,
+                no_data_value,
+                overviews: Vec::new(),
+                resampling: None,
+            },
+            cache: Mutex::new(BlockCache::new(DEFAULT_CACHE_BLOCKS)),
+            writes: Mutex::new(()),
+        };
+        fs::create_dir_all(&band.root)?;
+        band.save_meta()?;
+        Ok(band)
+    }
+
+    /// Open a band written earlier
+    pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let text = fs::read_to_string(root.join(META_FILE))?;
+        let meta = serde_json::from_str(&text).map_err(|e| CybersomethingError::SerializationError(e.to_string()))?;
+        Ok(Self { root, meta, cache: Mutex::new(BlockCache::new(DEFAULT_CACHE_BLOCKS)), writes: Mutex::new(()) })
+    }
+
+    /// Tile an in-memory band to disk
+    pub fn from_band(root: impl AsRef<Path>, band: &RasterBand, block_size: usize) -> Result<Self> {
+        let tiled = Self::create(root, band.band_id, &band.band_name, band.rows, band.cols, block_size, band.no_data_value)?;
+        tiled.write_window(0, 0, band)?;
+        Ok(tiled)
+    }
+
+    pub fn with_cache_capacity(self, blocks: usize) -> Self {
+        Self { cache: Mutex::new(BlockCache::new(blocks)), ..self }
+    }
+
+    pub fn meta(&self) -> &TiledBandMeta {
+        &self.meta
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        let cache = self.cache.lock().unwrap();
+        CacheStats { hits: cache.hits, misses: cache.misses, resident_blocks: cache.blocks.len() }
+    }
+
+    /// Number of pyramid levels above full resolution
+    pub fn overview_count(&self) -> usize {
+        self.meta.overviews.len()
+    }
+
+    /// (rows, cols) at a level (0 = full resolution)
+    pub fn level_shape(&self, level: usize) -> Option<(usize, usize)> {
+        match level {
+            0 => Some((self.meta.rows, self.meta.cols)),
+            _ => self.meta.overviews.get(level - 1).copied(),
+        }
+    }
+
+    /// Coarsest level whose pixels are no larger than `pixels_per_screen_pixel` full-resolution pixels
+    pub fn level_for_scale(&self, pixels_per_screen_pixel: f64) -> usize {
+        let mut level = 0;
+        while level < self.overview_count() && 2f64.powi(level as i32 + 1) <= pixels_per_screen_pixel {
+            level += 1;
+        }
+        level
+    }
+
+    /// (blocks down, blocks across) at a level
+    pub fn block_grid(&self, level: usize) -> Option<(usize, usize)> {
+        let (rows, cols) = self.level_shape(level)?;
+        let size = self.meta.block_size;
+        Some((rows.div_ceil(size), cols.div_ceil(size)))
+    }
+
+    /// Full-resolution window of every block, row-major (edge blocks clipped to the band)
+    pub fn block_windows(&self) -> Vec<PixelWindow> {
+        let (rows, cols, size) = (self.meta.rows, self.meta.cols, self.meta.block_size);
+        let (blocks_down, blocks_across) = self.block_grid(0).unwrap_or((0, 0));
+        (0..blocks_down)
+            .flat_map(|block_row| {
+                (0..blocks_across).map(move |block_col| PixelWindow {
+                    row_off: block_row * size,
+                    col_off: block_col * size,
+                    rows: size.min(rows - block_row * size),
+                    cols: size.min(cols - block_col * size),
+                })
+            })
+            .collect()
+    }
+
+    fn save_meta(&self) -> Result<()> {
+        let text = serde_json::to_string_pretty(&self.meta).map_err(|e| CybersomethingError::SerializationError(e.to_string()))?;
+        fs::write(self.root.join(META_FILE), text)?;
+        Ok(())
+    }
+
+    fn block_path(&self, (level, block_row, block_col): BlockKey) -> PathBuf {
+        self.root.join(format!("L{level}")).join(format!("r{block_row}_c{block_col}.blk"))
+    }
+
+    /// Block contents (block_size² values, padded with nodata past the band edge)
+    pub fn read_block(&self, level: usize, block_row: usize, block_col: usize) -> Result<Arc<Vec<f32>>> {
+        let key = (level, block_row, block_col);
+        let cached = self.cache.lock().unwrap().get(&key);
+        if let Some(block) = cached {
+            return Ok(block);
+        }
+        let _writing = self.writes.lock().unwrap();
+        self.load_block(key)
+    }
+
+    /// Cached block, else the block from disk; callers hold `writes`
+    fn load_block(&self, key: BlockKey) -> Result<Arc<Vec<f32>>> {
+        // A writer may have stored the block since the caller missed
+        if let Some(block) = self.cache.lock().unwrap().peek(&key) {
+            return Ok(block);
+        }
+
+        let cells = self.meta.block_size * self.meta.block_size;
+        let block = match fs::read(self.block_path(key)) {
+            Ok(bytes) if bytes.len() == cells * 4 => bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            Ok(_) => {
+                return Err(CybersomethingError::DataValidationError {
+                    reason: format!("block {:?} of band '{}' is truncated", key, self.meta.band_name),
+                })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![self.meta.no_data_value; cells],
+            Err(e) => return Err(e.into()),
+        };
+
+        let block = Arc::new(block);
+        self.cache.lock().unwrap().insert(key, block.clone());
+        Ok(block)
+    }
+
+    /// Replace a whole block (write-through to disk and cache)
+    pub fn write_block(&self, level: usize, block_row: usize, block_col: usize, data: Vec<f32>) -> Result<()> {
+        let _writing = self.writes.lock().unwrap();
+        self.store_block(level, block_row, block_col, data)
+    }
+
+    /// Write a block; callers hold `writes`
+    fn store_block(&self, level: usize, block_row: usize, block_col: usize, data: Vec<f32>) -> Result<()> {
+        let size = self.meta.block_size;
+        if data.len() != size * size {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("block has {} values, expected {}", data.len(), size * size),
+            });
+        }
+
+        let key = (level, block_row, block_col);
+        let path = self.block_path(key);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let bytes: Vec<u8> = data.iter().flat_map(|v| v.to_le_bytes()).collect();
+        fs::write(path, bytes)?;
+        self.cache.lock().unwrap().insert(key, Arc::new(data));
+        Ok(())
+    }
+
+    /// Read a window at any pyramid level
+    pub fn read_level_window(&self, level: usize, window: &PixelWindow) -> Result<RasterBand> {
+        self.level_window(level, window, false)
+    }
+
+    /// `read_level_window`, loading through `load_block` when the caller already holds `writes`
+    fn level_window(&self, level: usize, window: &PixelWindow, writes_held: bool) -> Result<RasterBand> {
+        let (rows, cols) = self.level_shape(level).ok_or_else(|| CybersomethingError::DataValidationError {
+            reason: format!("band '{}' has no overview level {}", self.meta.band_name, level),
+        })?;
+        if window.row_off + window.rows > rows || window.col_off + window.cols > cols {
+            return Err(window_error(&self.meta.band_name, window));
+        }
+
+        let size = self.meta.block_size;
+        let mut data = vec![self.meta.no_data_value; window.rows * window.cols];
+        for block_row in window.row_off / size..(window.row_off + window.rows).div_ceil(size) {
+            for block_col in window.col_off / size..(window.col_off + window.cols).div_ceil(size) {
+                let block = if writes_held {
+                    self.load_block((level, block_row, block_col))?
+                } else {
+                    self.read_block(level, block_row, block_col)?
+                };
+                let r0 = (block_row * size).max(window.row_off);
+                let r1 = ((block_row + 1) * size).min(window.row_off + window.rows);
+                let c0 = (block_col * size).max(window.col_off);
+                let c1 = ((block_col + 1) * size).min(window.col_off + window.cols);
+                for r in r0..r1 {
+                    let src = (r - block_row * size) * size + (c0 - block_col * size);
+                    let dst = (r - window.row_off) * window.cols + (c0 - window.col_off);
+                    data[dst..dst + (c1 - c0)].copy_from_slice(&block[src..src + (c1 - c0)]);
+                }
+            }
+        }
+
+        Ok(RasterBand::from_data(
+            self.meta.band_id,
+            self.meta.band_name.clone(),
+            window.rows,
+            window.cols,
+            data,
+            self.meta.no_data_value,
+        ))
+    }
+
+    /// Write an in-memory band at (row_off, col_off) of full resolution;
+    /// its nodata pixels are stored as this band's nodata and overviews over it are rebuilt
+    pub fn write_window(&self, row_off: usize, col_off: usize, band: &RasterBand) -> Result<()> {
+        if row_off + band.rows > self.meta.rows || col_off + band.cols > self.meta.cols {
+            let window = PixelWindow { row_off, col_off, rows: band.rows, cols: band.cols };
+            return Err(window_error(&self.meta.band_name, &window));
+        }
+
+        let no_data = self.meta.no_data_value;
+        let size = self.meta.block_size;
+        let _writing = self.writes.lock().unwrap();
+        for block_row in row_off / size..(row_off + band.rows).div_ceil(size) {
+            for block_col in col_off / size..(col_off + band.cols).div_ceil(size) {
+                let mut block = self.load_block((0, block_row, block_col))?.as_ref().clone();
+                let r0 = (block_row * size).max(row_off);
+                let r1 = ((block_row + 1) * size).min(row_off + band.rows);
+                let c0 = (block_col * size).max(col_off);
+                let c1 = ((block_col + 1) * size).min(col_off + band.cols);
+                for r in r0..r1 {
+                    let dst = (r - block_row * size) * size + (c0 - block_col * size);
+                    let src = (r - row_off) * band.cols + (c0 - col_off);
+                    for (out, &v) in block[dst..dst + (c1 - c0)].iter_mut().zip(&band.data[src..src + (c1 - c0)]) {
+                        *out = if band.is_no_data(v) { no_data } else { v };
+                    }
+                }
+                self.store_block(0, block_row, block_col, block)?;
+            }
+        }
+        self.refresh_overviews(&PixelWindow { row_off, col_off, rows: band.rows, cols: band.cols })
+    }
+
+    /// Rebuild the overview blocks above a changed full-resolution window; callers hold `writes`
+    fn refresh_overviews(&self, window: &PixelWindow) -> Result<()> {
+        let Some(resampling) = self.meta.resampling else {
+            return Ok(());
+        };
+        let size = self.meta.block_size;
+        let (mut r0, mut c0) = (window.row_off, window.col_off);
+        let (mut r1, mut c1) = (window.row_off + window.rows, window.col_off + window.cols);
+        for level in 1..=self.overview_count() {
+            (r0, c0, r1, c1) = (r0 / 2, c0 / 2, r1.div_ceil(2), c1.div_ceil(2));
+            for block_row in r0 / size..r1.div_ceil(size) {
+                for block_col in c0 / size..c1.div_ceil(size) {
+                    self.build_overview_block(level, block_row, block_col, resampling)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Build `levels` overviews, each halving the previous level, block by block
+    pub fn build_overviews(&mut self, levels: usize, resampling: OverviewResampling) -> Result<()> {
+        self.meta.overviews.clear();
+        self.meta.resampling = Some(resampling);
+
+        for level in 1..=levels {
+            let (src_rows, src_cols) = self.level_shape(level - 1).unwrap_or((0, 0));
+            if src_rows <= 1 && src_cols <= 1 {
+                break;
+            }
+            self.meta.overviews.push((src_rows.div_ceil(2), src_cols.div_ceil(2)));
+            let (blocks_down, blocks_across) = self.block_grid(level).unwrap_or((0, 0));
+            for block_row in 0..blocks_down {
+                for block_col in 0..blocks_across {
+                    self.build_overview_block(level, block_row, block_col, resampling)?;
+                }
+            }
+        }
+        self.save_meta()
+    }
+
+    /// Resample one overview block from the level below; callers hold `writes` (or `&mut self`)
+    fn build_overview_block(&self, level: usize, block_row: usize, block_col: usize, resampling: OverviewResampling) -> Result<()> {
+        let size = self.meta.block_size;
+        let no_data = self.meta.no_data_value;
+        let (src_rows, src_cols) = self.level_shape(level - 1).unwrap_or((0, 0));
+
+        // Source window covering this block (2× footprint, clipped)
+        let row_off = block_row * size * 2;
+        let col_off = block_col * size * 2;
+        let window = PixelWindow {
+            row_off,
+            col_off,
+            rows: (size * 2).min(src_rows - row_off),
+            cols: (size * 2).min(src_cols - col_off),
+        };
+        let source = self.level_window(level - 1, &window, true)?;
+
+        let mut block = vec![no_data; size * size];
+        let mut values = Vec::with_capacity(4);
+        for r in 0..window.rows.div_ceil(2) {
+            for c in 0..window.cols.div_ceil(2) {
+                values.clear();
+                for (sr, sc) in [(2 * r, 2 * c), (2 * r, 2 * c + 1), (2 * r + 1, 2 * c), (2 * r + 1, 2 * c + 1)] {
+                    if let Some(v) = source.get_pixel(sr, sc).filter(|&v| !source.is_no_data(v)) {
+                        values.push(v);
+                    }
+                }
+                if let Some(v) = resampling.reduce(&values) {
+                    block[r * size + c] = v;
+                }
+            }
+        }
+        self.store_block(level, block_row, block_col, block)
+    }
+}
+
+impl BandSource for TiledBand {
+    fn band_name(&self) -> &str {
+        &self.meta.band_name
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        (self.meta.rows, self.meta.cols)
+    }
+
+    fn no_data_value(&self) -> f32 {
+        self.meta.no_data_value
+    }
+
+    fn window(&self, window: &PixelWindow) -> Result<RasterBand> {
+        self.read_level_window(0, window)
+    }
+}
+
+/// Run a per-window computation block by block over bands larger than memory.
+///
+/// The same window is read from every input and `compute` returns one band per
+/// output, written back at that window. Inputs and outputs must share a shape.
+pub fn map_blocks<F>(inputs: &[&dyn BandSource], outputs: &[&TiledBand], compute: F) -> Result<()>
+where
+    F: Fn(&[RasterBand]) -> Result<Vec<RasterBand>>,
+{
+    let Some(first) = outputs.first() else {
+        return Ok(());
+    };
+    let shape = first.shape();
+    for band in inputs.iter().copied().chain(outputs.iter().map(|o| *o as &dyn BandSource)) {
+        if band.shape() != shape {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!(
+                    "band '{}' is {}x{}, expected {}x{}",
+                    band.band_name(), band.shape().0, band.shape().1, shape.0, shape.1
+                ),
+            });
+        }
+    }
+
+    for window in first.block_windows() {
+        let windows = inputs.iter().map(|band| band.window(&window)).collect::<Result<Vec<_>>>()?;
+        let results = compute(&windows)?;
+        if results.len() != outputs.len() {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("block computation returned {} bands for {} outputs", results.len(), outputs.len()),
+            });
+        }
+        for (output, band) in outputs.iter().zip(&results) {
+            output.write_window(window.row_off, window.col_off, band)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Fresh scratch directory under the system temp dir
+    pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cybersomething-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Run a block-wise computation over tiled copies of `inputs` (4-pixel blocks)
+    /// and check each tiled output against the in-memory band it should reproduce
+    pub(crate) fn assert_blockwise_matches<F>(name: &str, inputs: &[&RasterBand], expected: &[&RasterBand], run: F)
+    where
+        F: FnOnce(&[&dyn BandSource], &[&TiledBand]) -> Result<()>,
+    {
+        let dir = scratch_dir(name);
+        let tiled: Vec<TiledBand> = inputs
+            .iter()
+            .enumerate()
+            .map(|(i, band)| TiledBand::from_band(dir.join(format!("in{i}")), band, 4).unwrap())
+            .collect();
+        let outputs: Vec<TiledBand> = expected
+            .iter()
+            .enumerate()
+            .map(|(i, b)| TiledBand::create(dir.join(format!("out{i}")), b.band_id, &b.band_name, b.rows, b.cols, 4, b.no_data_value).unwrap())
+            .collect();
+
+        let sources: Vec<&dyn BandSource> = tiled.iter().map(|t| t as &dyn BandSource).collect();
+        run(&sources, &outputs.iter().collect::<Vec<_>>()).unwrap();
+        for (output, band) in outputs.iter().zip(expected) {
+            let all = PixelWindow { row_off: 0, col_off: 0, rows: band.rows, cols: band.cols };
+            assert_eq!(output.window(&all).unwrap().data, band.data, "{}", band.band_name);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn ramp(rows: usize, cols: usize) -> RasterBand {
+        let data = (0..rows * cols).map(|i| i as f32).collect();
+        RasterBand::from_data(1, "Ramp".to_string(), rows, cols, data, -9999.0)
+    }
+
+    #[test]
+    fn test_windows_span_blocks_and_reopen() {
+        let dir = scratch_dir("tiled-windows");
+        let band = ramp(10, 7);
+        TiledBand::from_band(&dir, &band, 4).unwrap();
+
+        let tiled = TiledBand::open(&dir).unwrap();
+        assert_eq!(tiled.block_grid(0), Some((3, 2)));
+        let window = PixelWindow { row_off: 3, col_off: 2, rows: 4, cols: 4 };
+        assert_eq!(tiled.window(&window).unwrap().data, band.read_window(&window).unwrap().data);
+        assert_eq!(tiled.pixel(9, 6).unwrap(), Some(69.0));
+        assert_eq!(tiled.pixel(10, 0).unwrap(), None);
+
+        let sparse = TiledBand::create(dir.join("sparse"), 2, "Sparse", 8, 8, 4, -1.0).unwrap();
+        assert_eq!(sparse.pixel(5, 5).unwrap(), Some(-1.0));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lru_evicts_least_recent_block() {
+        let dir = scratch_dir("tiled-lru");
+        TiledBand::from_band(&dir, &ramp(4, 12), 4).unwrap();
+        let tiled = TiledBand::open(&dir).unwrap().with_cache_capacity(2);
+
+        tiled.read_block(0, 0, 0).unwrap();
+        tiled.read_block(0, 0, 1).unwrap();
+        tiled.read_block(0, 0, 0).unwrap(); // Hit, block 1 is now oldest
+        tiled.read_block(0, 0, 2).unwrap(); // Evicts block 1
+        tiled.read_block(0, 0, 0).unwrap(); // Still resident
+        tiled.read_block(0, 0, 1).unwrap(); // Reloaded from disk
+
+        let stats = tiled.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.resident_blocks), (2, 4, 2));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_overviews_average_and_mode() {
+        let dir = scratch_dir("tiled-overviews");
+        let mut band = ramp(5, 5);
+        band.data[0] = -9999.0;
+        let mut tiled = TiledBand::from_band(dir.join("avg"), &band, 2).unwrap();
+        tiled.build_overviews(4, OverviewResampling::Average).unwrap();
+
+        assert_eq!(tiled.meta().overviews, vec![(3, 3), (2, 2), (1, 1)]);
+        let level1 = tiled.read_level_window(1, &PixelWindow { row_off: 0, col_off: 0, rows: 3, cols: 3 }).unwrap();
+        assert_eq!(level1.get_pixel(0, 0), Some((1.0 + 5.0 + 6.0) / 3.0)); // Nodata skipped
+        assert_eq!(level1.get_pixel(2, 2), Some(24.0));                      // Edge pixel alone
+        assert_eq!(TiledBand::open(dir.join("avg")).unwrap().overview_count(), 3);
+        assert_eq!(tiled.level_for_scale(5.0), 2);
+
+        let classes = RasterBand::from_data(1, "Classes".to_string(), 2, 4, vec![1.0, 2.0, 3.0, 3.0, 2.0, 2.0, 3.0, 1.0], -9999.0);
+        let mut tiled = TiledBand::from_band(dir.join("mode"), &classes, 4).unwrap();
+        tiled.build_overviews(1, OverviewResampling::Mode).unwrap();
+        let level1 = tiled.read_level_window(1, &PixelWindow { row_off: 0, col_off: 0, rows: 1, cols: 2 }).unwrap();
+        assert_eq!(level1.data, vec![2.0, 3.0]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_window_writes_share_blocks() {
+        // Every thread writes its own column of the same blocks
+        let dir = scratch_dir("tiled-concurrent");
+        let tiled = TiledBand::create(&dir, 1, "Columns", 8, 8, 8, -9999.0).unwrap();
+        std::thread::scope(|scope| {
+            for col in 0..8 {
+                let tiled = &tiled;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        let column = RasterBand::from_data(1, "c".to_string(), 8, 1, vec![col as f32; 8], -9999.0);
+                        tiled.write_window(0, col, &column).unwrap();
+                    }
+                });
+            }
+        });
+        let all = tiled.window(&PixelWindow { row_off: 0, col_off: 0, rows: 8, cols: 8 }).unwrap();
+        assert!(all.data.iter().enumerate().all(|(i, &v)| v == (i % 8) as f32));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_window_writes_refresh_overviews() {
+        let dir = scratch_dir("tiled-refresh");
+        let mut band = ramp(9, 7);
+        let mut tiled = TiledBand::from_band(dir.join("edited"), &band, 2).unwrap();
+        tiled.build_overviews(3, OverviewResampling::Average).unwrap();
+
+        let patch = RasterBand::from_data(1, "Patch".to_string(), 3, 2, vec![100.0, -9999.0, 102.0, 103.0, 104.0, 105.0], -9999.0);
+        tiled.write_window(4, 3, &patch).unwrap();
+        for (i, &v) in patch.data.iter().enumerate() {
+            band.data[(4 + i / 2) * 7 + 3 + i % 2] = v;
+        }
+
+        let mut rebuilt = TiledBand::from_band(dir.join("rebuilt"), &band, 2).unwrap();
+        rebuilt.build_overviews(3, OverviewResampling::Average).unwrap();
+        for level in 1..=tiled.overview_count() {
+            let (rows, cols) = tiled.level_shape(level).unwrap();
+            let all = PixelWindow { row_off: 0, col_off: 0, rows, cols };
+            assert_eq!(tiled.read_level_window(level, &all).unwrap().data, rebuilt.read_level_window(level, &all).unwrap().data);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_misses_never_restore_stale_blocks() {
+        // One cache slot for two blocks: readers keep missing while writers store
+        let dir = scratch_dir("tiled-stale");
+        let tiled = TiledBand::create(&dir, 1, "Columns", 4, 8, 4, -9999.0).unwrap().with_cache_capacity(1);
+        std::thread::scope(|scope| {
+            for col in 0..8 {
+                let tiled = &tiled;
+                scope.spawn(move || {
+                    for round in 0..20 {
+                        let column = RasterBand::from_data(1, "c".to_string(), 4, 1, vec![(round * 8 + col) as f32; 4], -9999.0);
+                        tiled.write_window(0, col, &column).unwrap();
+                    }
+                });
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        tiled.read_block(0, 0, col % 2).unwrap();
+                    }
+                });
+            }
+        });
+        let all = tiled.window(&PixelWindow { row_off: 0, col_off: 0, rows: 4, cols: 8 }).unwrap();
+        assert!(all.data.iter().enumerate().all(|(i, &v)| v == (19 * 8 + i % 8) as f32));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_map_blocks_remaps_nodata() {
+        let dir = scratch_dir("tiled-map");
+        let mut band = ramp(5, 6);
+        band.data[7] = -9999.0;
+        let doubled = TiledBand::create(&dir, 2, "Doubled", 5, 6, 4, -1.0).unwrap();
+        map_blocks(&[&band], &[&doubled], |w| {
+            let data = w[0].data.iter().map(|&v| if w[0].is_no_data(v) { v } else { v * 2.0 }).collect();
+            Ok(vec![RasterBand::from_data(2, "Doubled".to_string(), w[0].rows, w[0].cols, data, w[0].no_data_value)])
+        })
+        .unwrap();
+
+        assert_eq!(doubled.block_windows().len(), 4);
+        assert_eq!(doubled.pixel(1, 1).unwrap(), Some(-1.0));
+        assert_eq!(doubled.pixel(4, 5).unwrap(), Some(58.0));
+        assert!(map_blocks(&[&ramp(5, 5)], &[&doubled], |w| Ok(w.to_vec())).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -95,33 +95,98 @@ impl Geometry {
     /// Point-in-polygon test (ray casting algorithm)
     pub fn contains_point(&self, point: &LatLon) -> bool {
         match self {
+            Self::Polygon(coords) => ring_contains(coords, point),
+            Self::MultiPolygon(polys) => polys.iter().any(|ring| ring_contains(ring, point)),
+            Self::Point(p) => point == p,
+            _ => false,
+        }
+    }
+
+    /// Distance from a point to this geometry in meters (0 inside polygons)
+    pub fn distance_to_point_m(&self, point: &LatLon) -> f64 {
+        match self {
+            Self::Point(p) => p.distance_to(point),
+            Self::LineString(coords) => path_distance_m(coords, point, false),
             Self::Polygon(coords) => {
-                if coords.len() < 3 {
-                    return false;
+                if ring_contains(coords, point) {
+                    0.0
+                } else {
+                    path_distance_m(coords, point, true)
                 }
-
-                let mut inside = false;
-                let mut j = coords.len() - 1;
-
-                for i in 0..coords.len() {
-                    if (coords[i].latitude > point.latitude) != (coords[j].latitude > point.latitude)
-                        && point.longitude
-                            < (coords[j].longitude - coords[i].longitude)
-                                * (point.latitude - coords[i].latitude)
-                                / (coords[j].latitude - coords[i].latitude)
-                                + coords[i].longitude
-                    {
-                        inside = !inside;
+            }
+            Self::MultiPolygon(polys) => polys
+                .iter()
+                .map(|ring| {
+                    if ring_contains(ring, point) {
+                        0.0
+                    } else {
+                        path_distance_m(ring, point, true)
                     }
-                    j = i;
-                }
+                })
+                .fold(f64::INFINITY, f64::min),
+        }
+    }
+}
 
-                inside
-            }
-            Self::Point(p) => point == p,
-            _ => false,
+/// Ray-casting test against a single ring
+fn ring_contains(coords: &[LatLon], point: &LatLon) -> bool {
+    if coords.len() < 3 {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut j = coords.len() - 1;
+
+    for i in 0..coords.len() {
+        if (coords[i].latitude > point.latitude) != (coords[j].latitude > point.latitude)
+            && point.longitude
+                < (coords[j].longitude - coords[i].longitude)
+                    * (point.latitude - coords[i].latitude)
+                    / (coords[j].latitude - coords[i].latitude)
+                    + coords[i].longitude
+        {
+            inside = !inside;
         }
+        j = i;
     }
+
+    inside
+}
+
+/// Minimum distance (m) from a point to a vertex path, using a local
+/// equirectangular projection centred on the point (accurate at parcel scale)
+fn path_distance_m(coords: &[LatLon], point: &LatLon, closed: bool) -> f64 {
+    const R: f64 = 6371000.0;
+    let cos_lat = point.latitude.to_radians().cos();
+    let project = |c: &LatLon| {
+        (
+            (c.longitude - point.longitude).to_radians() * R * cos_lat,
+            (c.latitude - point.latitude).to_radians() * R,
+        )
+    };
+
+    match coords.len() {
+        0 => return f64::INFINITY,
+        1 => return coords[0].distance_to(point),
+        _ => {}
+    }
+
+    let segment_count = if closed { coords.len() } else { coords.len() - 1 };
+    (0..segment_count)
+        .map(|i| {
+            let (ax, ay) = project(&coords[i]);
+            let (bx, by) = project(&coords[(i + 1) % coords.len()]);
+            let (dx, dy) = (bx - ax, by - ay);
+            let len2 = dx * dx + dy * dy;
+            let t = if len2 > 0.0 {
+                (-(ax * dx + ay * dy) / len2).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let (px, py) = (ax + t * dx, ay + t * dy);
+            (px * px + py * py).sqrt()
+        })
+        .fold(f64::INFINITY, f64::min)
 }
 
 /// Vector feature (geometry + attributes)
@@ -241,6 +306,21 @@ mod tests {
         assert!(poly.contains_point(&inside));
     }
 
+    #[test]
+    fn test_distance_to_polygon() {
+        let poly = Geometry::Polygon(vec![
+            LatLon::new(33.0, -112.0),
+            LatLon::new(33.001, -112.0),
+            LatLon::new(33.001, -111.999),
+            LatLon::new(33.0, -111.999),
+        ]);
+
+        assert_eq!(poly.distance_to_point_m(&LatLon::new(33.0005, -111.9995)), 0.0);
+        // ~0.0001° of latitude south of the southern edge ≈ 11 m
+        let south = poly.distance_to_point_m(&LatLon::new(32.9999, -111.9995));
+        assert!((south - 11.1).abs() < 0.5);
+    }
+
     #[test]
     fn test_feature_creation() {
         let geom = Geometry::Point(LatLon::new(33.0, -112.0));
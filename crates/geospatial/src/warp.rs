@@ -0,0 +1,401 @@
+//! Raster resampling and reprojection (WGS84 <-> UTM)
+//! Each target pixel center is mapped back into the source grid and sampled
+//! with nearest, bilinear or cubic (Keys, a = -0.5) interpolation; several
+//! inputs can be warped onto one common grid for pixel-for-pixel stacking.
+
+use crate::raster::{CoordinateSystem, GeoTransform, PixelWindow, RasterBand, RasterDataset};
+use crate::tiled::{BandSource, TiledBand};
+use cybersomething_core::utils::errors::{CybersomethingError, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Points sampled along each edge when projecting a raster outline
+const OUTLINE_SAMPLES: usize = 16;
+
+/// Interpolation kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resampling {
+    Nearest,  // Classes (land cover, risk zones, grass presence)
+    Bilinear, // Continuous indices (NDVI, P_i)
+    Cubic,    // Smooth surfaces (DEM); falls back to bilinear next to nodata
+}
+
+/// Output grid of a warp
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TargetGrid {
+    pub crs: CoordinateSystem,
+    pub geotransform: GeoTransform,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl TargetGrid {
+    pub fn new(crs: CoordinateSystem, geotransform: GeoTransform, rows: usize, cols: usize) -> Self {
+        Self { crs, geotransform, rows, cols }
+    }
+
+    /// North-up grid of `resolution` (target CRS units) anchored at the upper-left corner of the bounds
+    pub fn from_bounds(crs: CoordinateSystem, min: (f64, f64), max: (f64, f64), resolution: f64) -> Result<Self> {
+        if resolution <= 0.0 || max.0 <= min.0 || max.1 <= min.1 {
+            return Err(CybersomethingError::DataValidationError {
+                reason: format!("empty target grid {min:?}-{max:?} at resolution {resolution}"),
+            });
+        }
+        // Tolerance keeps exact multiples from gaining a sliver column
+        let count = |extent: f64| ((extent / resolution - 1e-6).ceil() as usize).max(1);
+        let (rows, cols) = (count(max.1 - min.1), count(max.0 - min.0));
+        Ok(Self::new(crs, GeoTransform::north_up(min.0, max.1, resolution, resolution), rows, cols))
+    }
+
+    /// Grid covering one band of a dataset once projected into `crs`
+    pub fn covering(dataset: &RasterDataset, band: &RasterBand, crs: CoordinateSystem, resolution: f64) -> Result<Self> {
+        let (min, max) = projected_bounds(dataset, band, crs)?;
+        Self::from_bounds(crs, min, max, resolution)
+    }
+
+    /// Grid over the area shared by every dataset (first band of each)
+    pub fn common(datasets: &[&RasterDataset], crs: CoordinateSystem, resolution: f64) -> Result<Self> {
+        let mut bounds: Option<((f64, f64), (f64, f64))> = None;
+        for dataset in datasets {
+            let band = first_band(dataset)?;
+            let (min, max) = projected_bounds(dataset, band, crs)?;
+            bounds = Some(match bounds {
+                Some((lo, hi)) => ((lo.0.max(min.0), lo.1.max(min.1)), (hi.0.min(max.0), hi.1.min(max.1))),
+                None => (min, max),
+            });
+        }
+        let (min, max) = bounds.ok_or_else(|| CybersomethingError::DataValidationError {
+            reason: "no rasters to align".to_string(),
+        })?;
+        Self::from_bounds(crs, min, max, resolution)
+    }
+
+    /// Empty dataset on this grid
+    pub fn dataset(&self, dataset_id: u32) -> RasterDataset {
+        RasterDataset::from_geotransform(dataset_id, self.crs, self.geotransform, self.rows, self.cols)
+    }
+}
+
+fn first_band(dataset: &RasterDataset) -> Result<&RasterBand> {
+    dataset.bands.first().ok_or_else(|| CybersomethingError::DataValidationError {
+        reason: format!("dataset {} has no bands", dataset.dataset_id),
+    })
+}
+
+fn check_convertible(from: CoordinateSystem, to: CoordinateSystem) -> Result<()> {
+    let local = CoordinateSystem::LocalProjection;
+    if from != to && (from == local || to == local) {
+        return Err(CybersomethingError::DataValidationError {
+            reason: format!("cannot reproject between {from:?} and {to:?}"),
+        });
+    }
+    Ok(())
+}
+
+/// Map (x, y) in one CRS into another, None when it has no geographic position
+fn convert(from: CoordinateSystem, to: CoordinateSystem, x: f64, y: f64) -> Option<(f64, f64)> {
+    if from == to {
+        return Some((x, y));
+    }
+    Some(to.from_latlon(&from.try_to_latlon(x, y)?))
+}
+
+/// Bounding box of a band's outline projected into `crs`
+fn projected_bounds(dataset: &RasterDataset, band: &RasterBand, crs: CoordinateSystem) -> Result<((f64, f64), (f64, f64))> {
+    check_convertible(dataset.crs, crs)?;
+    let gt = dataset.geotransform_for(band);
+    let (rows, cols) = (band.rows as f64, band.cols as f64);
+
+    let (mut min, mut max) = ((f64::INFINITY, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY));
+    for i in 0..=OUTLINE_SAMPLES {
+        let f = i as f64 / OUTLINE_SAMPLES as f64;
+        for (row, col) in [(0.0, f * cols), (rows, f * cols), (f * rows, 0.0), (f * rows, cols)] {
+            let (x, y) = gt.apply(row, col);
+            if let Some((x, y)) = convert(dataset.crs, crs, x, y) {
+                (min, max) = ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)));
+            }
+        }
+    }
+    if min.0 > max.0 {
+        return Err(CybersomethingError::DataValidationError {
+            reason: format!("band '{}' of dataset {} has no geographic position in {:?}", band.band_name, dataset.dataset_id, dataset.crs),
+        });
+    }
+    Ok((min, max))
+}
+
+/// Keys cubic convolution weight (a = -0.5)
+fn cubic_weight(t: f64) -> f64 {
+    let t = t.abs();
+    if t <= 1.0 {
+        1.5 * t.powi(3) - 2.5 * t.powi(2) + 1.0
+    } else if t < 2.0 {
+        -0.5 * t.powi(3) + 2.5 * t.powi(2) - 4.0 * t + 2.0
+    } else {
+        0.0
+    }
+}
+
+/// Interpolate a band at a fractional pixel position (integers = pixel corners)
+pub fn sample(band: &RasterBand, row: f64, col: f64, resampling: Resampling) -> Option<f32> {
+    if row < 0.0 || col < 0.0 || row >= band.rows as f64 || col >= band.cols as f64 {
+        return None;
+    }
+    let valid = |r: i64, c: i64| {
+        let r = r.clamp(0, band.rows as i64 - 1) as usize;
+        let c = c.clamp(0, band.cols as i64 - 1) as usize;
+        band.get_pixel(r, c).filter(|&v| !band.is_no_data(v)).map(|v| v as f64)
+    };
+
+    // Position relative to pixel centers
+    let (y, x) = (row - 0.5, col - 0.5);
+    let (r0, c0) = (y.floor() as i64, x.floor() as i64);
+    let (dy, dx) = (y - r0 as f64, x - c0 as f64);
+
+    match resampling {
+        Resampling::Nearest => valid(row as i64, col as i64).map(|v| v as f32),
+        Resampling::Bilinear => {
+            let (mut sum, mut weight) = (0.0, 0.0);
+            for (r, c, w) in [
+                (r0, c0, (1.0 - dy) * (1.0 - dx)),
+                (r0, c0 + 1, (1.0 - dy) * dx),
+                (r0 + 1, c0, dy * (1.0 - dx)),
+                (r0 + 1, c0 + 1, dy * dx),
+            ] {
+                if let Some(v) = valid(r, c) {
+                    sum += v * w;
+                    weight += w;
+                }
+            }
+            // Renormalize over valid neighbours; the pixel itself must be valid
+            valid(row as i64, col as i64)?;
+            (weight > 0.0).then(|| (sum / weight) as f32)
+        }
+        Resampling::Cubic => {
+            let mut sum = 0.0;
+            for i in -1..=2 {
+                for j in -1..=2 {
+                    match valid(r0 + i, c0 + j) {
+                        Some(v) => sum += v * cubic_weight(dy - i as f64) * cubic_weight(dx - j as f64),
+                        None => return sample(band, row, col, Resampling::Bilinear),
+                    }
+                }
+            }
+            Some(sum as f32)
+        }
+    }
+}
+
+/// Warp one band of a dataset onto a target grid
+pub fn warp_band(dataset: &RasterDataset, band: &RasterBand, grid: &TargetGrid, resampling: Resampling) -> Result<RasterBand> {
+    check_convertible(dataset.crs, grid.crs)?;
+    let source = dataset.geotransform_for(band);
+    let no_data = band.no_data_value;
+
+    let data = (0..grid.rows * grid.cols)
+        .into_par_iter()
+        .map(|idx| {
+            let (x, y) = grid.geotransform.apply((idx / grid.cols) as f64 + 0.5, (idx % grid.cols) as f64 + 0.5);
+            convert(grid.crs, dataset.crs, x, y)
+                .and_then(|(x, y)| source.invert(x, y))
+                .and_then(|(row, col)| sample(band, row, col, resampling))
+                .unwrap_or(no_data)
+        })
+        .collect();
+
+    Ok(RasterBand::from_data(band.band_id, band.band_name.clone(), grid.rows, grid.cols, data, no_data))
+}
+
+/// Warp an in-memory or tiled band on a source grid into a tiled band, block by block;
+/// only the source window under each target block (plus the kernel's reach) is read
+pub fn warp_tiled(
+    source: &dyn BandSource,
+    source_crs: CoordinateSystem,
+    source_transform: GeoTransform,
+    grid: &TargetGrid,
+    resampling: Resampling,
+    output: &TiledBand,
+) -> Result<()> {
+    check_convertible(source_crs, grid.crs)?;
+    if output.shape() != (grid.rows, grid.cols) {
+        return Err(CybersomethingError::DataValidationError {
+            reason: format!(
+                "band '{}' is {}x{}, expected {}x{}",
+                output.band_name(), output.shape().0, output.shape().1, grid.rows, grid.cols
+            ),
+        });
+    }
+    let (src_rows, src_cols) = source.shape();
+    let no_data = output.no_data_value();
+
+    for window in output.block_windows() {
+        // Source position of each target pixel center
+        let positions: Vec<Option<(f64, f64)>> = (0..window.rows * window.cols)
+            .map(|i| {
+                let (row, col) = (window.row_off + i / window.cols, window.col_off + i % window.cols);
+                let (x, y) = grid.geotransform.apply(row as f64 + 0.5, col as f64 + 0.5);
+                convert(grid.crs, source_crs, x, y)
+                    .and_then(|(x, y)| source_transform.invert(x, y))
+                    .filter(|&(r, c)| r >= 0.0 && c >= 0.0 && r < src_rows as f64 && c < src_cols as f64)
+            })
+            .collect();
+
+        let mut data = vec![no_data; positions.len()];
+        let footprint = positions.iter().flatten().fold(None, |acc: Option<(usize, usize, usize, usize)>, &(r, c)| {
+            let (r, c) = (r as usize, c as usize);
+            Some(acc.map_or((r, r, c, c), |(r0, r1, c0, c1)| (r0.min(r), r1.max(r), c0.min(c), c1.max(c))))
+        });
+        if let Some((r0, r1, c0, c1)) = footprint {
+            // Cubic reads two pixels either side
+            let (row_off, col_off) = (r0.saturating_sub(2), c0.saturating_sub(2));
+            let read = PixelWindow {
+                row_off,
+                col_off,
+                rows: (r1 + 3).min(src_rows) - row_off,
+                cols: (c1 + 3).min(src_cols) - col_off,
+            };
+            let patch = source.window(&read)?;
+            for (value, position) in data.iter_mut().zip(&positions) {
+                if let Some(v) = position.and_then(|(r, c)| sample(&patch, r - row_off as f64, c - col_off as f64, resampling)) {
+                    *value = v;
+                }
+            }
+        }
+        let block = RasterBand::from_data(output.meta().band_id, output.band_name().to_string(), window.rows, window.cols, data, no_data);
+        output.write_window(window.row_off, window.col_off, &block)?;
+    }
+    Ok(())
+}
+
+impl RasterDataset {
+    /// Warp every band onto a target grid
+    pub fn reproject(&self, grid: &TargetGrid, resampling: Resampling) -> Result<RasterDataset> {
+        let mut out = grid.dataset(self.dataset_id);
+        for band in &self.bands {
+            out.add_band(warp_band(self, band, grid, resampling)?);
+        }
+        Ok(out)
+    }
+
+    /// Reproject to a CRS at a resolution (target CRS units), covering the first band
+    pub fn warp_to(&self, crs: CoordinateSystem, resolution: f64, resampling: Resampling) -> Result<RasterDataset> {
+        let grid = TargetGrid::covering(self, first_band(self)?, crs, resolution)?;
+        self.reproject(&grid, resampling)
+    }
+}
+
+/// Warp several datasets (each with its own kernel) onto one grid as a single band stack
+pub fn align_rasters(inputs: &[(&RasterDataset, Resampling)], grid: &TargetGrid, dataset_id: u32) -> Result<RasterDataset> {
+    let mut stack = grid.dataset(dataset_id);
+    for (dataset, resampling) in inputs {
+        for band in &dataset.bands {
+            if stack.get_band(&band.band_name).is_some() {
+                return Err(CybersomethingError::DataValidationError {
+                    reason: format!("band '{}' appears in more than one input", band.band_name),
+                });
+            }
+            stack.add_band(warp_band(dataset, band, grid, *resampling)?);
+        }
+    }
+    Ok(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UTM 12N dataset with a plane z = 2·col + 3·row (exact under bilinear)
+    fn plane_utm(rows: usize, cols: usize) -> RasterDataset {
+        let crs = CoordinateSystem::UTM { zone: 12, is_north: true };
+        let gt = GeoTransform::north_up(400_000.0, 3_700_000.0, 30.0, 30.0);
+        let mut dataset = RasterDataset::from_geotransform(1, crs, gt, rows, cols);
+        let data = (0..rows * cols).map(|i| (2 * (i % cols) + 3 * (i / cols)) as f32).collect();
+        dataset.add_band(RasterBand::from_data(1, "DEM".to_string(), rows, cols, data, -9999.0));
+        dataset
+    }
+
+    #[test]
+    fn test_kernels_on_plane() {
+        let band = &plane_utm(6, 6).bands[0];
+        assert_eq!(sample(band, 2.5, 3.5, Resampling::Nearest), Some(12.0));
+        assert!((sample(band, 2.75, 3.0, Resampling::Bilinear).unwrap() - 11.75).abs() < 1e-5);
+        assert!((sample(band, 2.75, 3.0, Resampling::Cubic).unwrap() - 11.75).abs() < 1e-4);
+        assert_eq!(sample(band, 6.0, 0.0, Resampling::Nearest), None);
+    }
+
+    #[test]
+    fn test_nodata_is_respected() {
+        let mut dataset = plane_utm(4, 4);
+        dataset.bands[0].data[5] = -9999.0; // (1, 1)
+        let band = &dataset.bands[0];
+        assert_eq!(sample(band, 1.5, 1.5, Resampling::Bilinear), None);
+        let cubic = sample(band, 2.6, 2.6, Resampling::Cubic).unwrap();
+        assert_eq!(cubic, sample(band, 2.6, 2.6, Resampling::Bilinear).unwrap());
+    }
+
+    #[test]
+    fn test_utm_to_wgs84_and_back() {
+        let source = plane_utm(20, 20);
+        let geographic = source.warp_to(CoordinateSystem::WGS84, 0.0002, Resampling::Bilinear).unwrap();
+        assert_eq!(geographic.crs, CoordinateSystem::WGS84);
+        let warped = &geographic.bands[0];
+
+        // A point well inside the source keeps its value
+        let point = source.pixel_center(&source.bands[0], 10, 10);
+        let (row, col) = geographic.coord_to_pixel(warped, &point).unwrap();
+        let value = warped.get_pixel(row, col).unwrap();
+        assert!((value - 50.0).abs() < 2.0, "got {value}");
+        assert!(warped.data.iter().any(|&v| v == -9999.0)); // Rotated footprint leaves corners empty
+
+        let back = geographic.warp_to(source.crs, 30.0, Resampling::Nearest).unwrap();
+        let (row, col) = back.coord_to_pixel(&back.bands[0], &point).unwrap();
+        assert!((back.bands[0].get_pixel(row, col).unwrap() - 50.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn test_pixels_off_the_globe_are_nodata() {
+        let source = plane_utm(4, 4).warp_to(CoordinateSystem::WGS84, 0.001, Resampling::Nearest).unwrap();
+        // Eastings far outside any UTM zone have no latitude/longitude
+        let grid = TargetGrid::new(CoordinateSystem::UTM { zone: 12, is_north: true }, GeoTransform::north_up(5.0e7, 3.7e6, 1.0e5, 1.0e5), 2, 2);
+        let warped = warp_band(&source, &source.bands[0], &grid, Resampling::Bilinear).unwrap();
+        assert!(warped.data.iter().all(|&v| v == -9999.0));
+    }
+
+    #[test]
+    fn test_tiled_warp_matches_in_memory() {
+        let mut source = plane_utm(20, 20);
+        source.bands[0].data[45] = -9999.0;
+        let band = &source.bands[0];
+        let grid = TargetGrid::covering(&source, band, CoordinateSystem::WGS84, 0.0002).unwrap();
+
+        for resampling in [Resampling::Nearest, Resampling::Bilinear, Resampling::Cubic] {
+            let expected = warp_band(&source, band, &grid, resampling).unwrap();
+            crate::tiled::tests::assert_blockwise_matches("tiled-warp", &[band], &[&expected], |i, o| {
+                warp_tiled(i[0], source.crs, source.geotransform_for(band), &grid, resampling, o[0])
+            });
+        }
+    }
+
+    #[test]
+    fn test_align_stack_to_common_grid() {
+        let dem = plane_utm(10, 10);
+        let mut ndvi = RasterDataset::from_geotransform(
+            2,
+            dem.crs,
+            GeoTransform::north_up(400_150.0, 3_699_850.0, 10.0, 10.0),
+            30,
+            30,
+        );
+        ndvi.add_band(RasterBand::from_data(1, "NDVI".to_string(), 30, 30, vec![0.4; 900], -9999.0));
+
+        let grid = TargetGrid::common(&[&dem, &ndvi], dem.crs, 30.0).unwrap();
+        assert_eq!((grid.rows, grid.cols), (5, 5));
+        assert_eq!(grid.geotransform.origin_x, 400_150.0);
+
+        let stack = align_rasters(&[(&dem, Resampling::Cubic), (&ndvi, Resampling::Bilinear)], &grid, 9).unwrap();
+        stack.ensure_aligned(&stack.bands.iter().collect::<Vec<_>>()).unwrap();
+        assert_eq!(stack.get_band("DEM").unwrap().get_pixel(0, 0), Some(2.0 * 5.0 + 3.0 * 5.0));
+        assert!((stack.get_band("NDVI").unwrap().get_pixel(4, 4).unwrap() - 0.4).abs() < 1e-6);
+        assert!(align_rasters(&[(&dem, Resampling::Nearest), (&dem, Resampling::Nearest)], &grid, 9).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+#!/usr/bin/env python3
+"""Generate the small GeoTIFF fixtures used by `geotiff` tests (stdlib only).
+
+    python3 make_geotiff_fixtures.py   # writes *.tif next to this script
+
+Pixel values are deterministic so the Rust tests can check them exactly:
+  dem_f32_deflate.tif       4x5 f32, 2-row strips, deflate, EPSG:4326, nodata -9999, band "DEM"
+  landcover_u8_lzw_tiled.tif 18x20x3 u8, 16x16 tiles, LZW, EPSG:32611, bands "Red", "Green", "NIR"
+  elevation_i16_be.tif      3x3 i16, one strip, uncompressed, big-endian, EPSG:32612, nodata -32768
+"""
+
+import os
+import struct
+import zlib
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+SHORT, LONG, ASCII, DOUBLE = 3, 4, 2, 12
+TYPE_SIZE = {SHORT: 2, LONG: 4, ASCII: 1, DOUBLE: 8}
+TYPE_CHAR = {SHORT: "H", LONG: "I", DOUBLE: "d"}
+
+
+def lzw_encode(data):
+    """TIFF-flavoured LZW (MSB-first, codes 256 = clear, 257 = end of information)."""
+    out, acc, nbits = bytearray(), 0, 0
+
+    def put(code, width):
+        nonlocal acc, nbits
+        acc = (acc << width) | code
+        nbits += width
+        while nbits >= 8:
+            nbits -= 8
+            out.append((acc >> nbits) & 0xFF)
+
+    def reset():
+        return {bytes([i]): i for i in range(256)}, 258, 9
+
+    table, next_code, width = reset()
+    put(256, width)
+    w = b""
+    for byte in data:
+        wc = w + bytes([byte])
+        if wc in table:
+            w = wc
+            continue
+        put(table[w], width)
+        table[wc] = next_code
+        next_code += 1
+        if next_code == 4094:
+            put(256, width)
+            table, next_code, width = reset()
+        elif next_code == 1 << width:
+            width += 1
+        w = bytes([byte])
+    if w:
+        put(table[w], width)
+        next_code += 1
+        if next_code == 1 << width:
+            width += 1
+    put(257, width)
+    if nbits:
+        out.append((acc << (8 - nbits)) & 0xFF)
+    return bytes(out)
+
+
+def compress(raw, method):
+    if method == 1:
+        return raw
+    if method == 5:
+        return lzw_encode(raw)
+    if method == 8:
+        return zlib.compress(raw)
+    raise ValueError(method)
+
+
+def gdal_metadata(names):
+    items = "".join(
+        '<Item name="DESCRIPTION" sample="{}" role="description">{}</Item>'.format(i, n)
+        for i, n in enumerate(names)
+    )
+    return "<GDALMetadata>{}</GDALMetadata>".format(items)
+
+
+def geokeys(epsg):
+    geographic = epsg == 4326
+    keys = [
+        (1024, 0, 1, 2 if geographic else 1),  # GTModelTypeGeoKey
+        (1025, 0, 1, 1),                        # GTRasterTypeGeoKey = PixelIsArea
+        (2048 if geographic else 3072, 0, 1, epsg),
+    ]
+    flat = [1, 1, 0, len(keys)]
+    for key in keys:
+        flat.extend(key)
+    return flat
+
+
+def write_geotiff(path, *, order, rows, cols, fmt, sample_format, pixels, compression,
+                  epsg, origin, pixel_size, names, nodata=None, rows_per_strip=None, tile=None):
+    """pixels: row-major list of per-pixel tuples (one value per band)."""
+    bands = len(pixels[0])
+    bits = struct.calcsize(fmt) * 8
+
+    def pack_rows(r0, r1, c0, c1, fill_cols=None):
+        buf = bytearray()
+        for r in range(r0, r1):
+            for c in range(c0, c0 + (fill_cols or (c1 - c0))):
+                values = pixels[r * cols + c] if r < rows and c < cols else (0,) * bands
+                buf += struct.pack(order + fmt * bands, *values)
+        return bytes(buf)
+
+    chunks = []
+    if tile:
+        for tr in range(0, rows, tile):
+            for tc in range(0, cols, tile):
+                chunks.append(pack_rows(tr, tr + tile, tc, tc + tile, fill_cols=tile))
+    else:
+        for r0 in range(0, rows, rows_per_strip):
+            chunks.append(pack_rows(r0, min(r0 + rows_per_strip, rows), 0, cols))
+    chunks = [compress(c, compression) for c in chunks]
+
+    entries = {
+        256: (LONG, [cols]),
+        257: (LONG, [rows]),
+        258: (SHORT, [bits] * bands),
+        259: (SHORT, [compression]),
+        262: (SHORT, [1]),
+        277: (SHORT, [bands]),
+        284: (SHORT, [1]),
+        339: (SHORT, [sample_format] * bands),
+        33550: (DOUBLE, [pixel_size[0], pixel_size[1], 0.0]),
+        33922: (DOUBLE, [0.0, 0.0, 0.0, origin[0], origin[1], 0.0]),
+        34735: (SHORT, geokeys(epsg)),
+        42112: (ASCII, gdal_metadata(names)),
+    }
+    if bands > 1:
+        entries[338] = (SHORT, [0] * (bands - 1))
+    if nodata is not None:
+        entries[42113] = (ASCII, nodata)
+
+    data = bytearray(b"II*\x00" if order == "<" else b"MM\x00*")
+    data += b"\x00\x00\x00\x00"  # IFD offset patched below
+    offsets = []
+    for chunk in chunks:
+        offsets.append(len(data))
+        data += chunk
+        if len(data) % 2:
+            data += b"\x00"
+    counts = [len(c) for c in chunks]
+    if tile:
+        entries.update({322: (LONG, [tile]), 323: (LONG, [tile]), 324: (LONG, offsets), 325: (LONG, counts)})
+    else:
+        entries.update({273: (LONG, offsets), 278: (LONG, [rows_per_strip]), 279: (LONG, counts)})
+
+    # Out-of-line values, then the IFD itself
+    inline = {}
+    for tag, (typ, values) in sorted(entries.items()):
+        if typ == ASCII:
+            raw = values.encode("ascii") + b"\x00"
+        else:
+            raw = struct.pack(order + TYPE_CHAR[typ] * len(values), *values)
+        if len(raw) <= 4:
+            inline[tag] = (typ, len(raw) // TYPE_SIZE[typ], raw.ljust(4, b"\x00"))
+        else:
+            offset = len(data)
+            data += raw
+            if len(data) % 2:
+                data += b"\x00"
+            inline[tag] = (typ, len(raw) // TYPE_SIZE[typ], struct.pack(order + "I", offset))
+
+    ifd_offset = len(data)
+    data[4:8] = struct.pack(order + "I", ifd_offset)
+    data += struct.pack(order + "H", len(inline))
+    for tag, (typ, count, value) in sorted(inline.items()):
+        data += struct.pack(order + "HHI", tag, typ, count) + value
+    data += struct.pack(order + "I", 0)
+
+    with open(path, "wb") as f:
+        f.write(data)
+
+
+def main():
+    dem = [(r * 10.0 + c * 0.5,) for r in range(4) for c in range(5)]
+    dem[7] = (-9999.0,)
+    write_geotiff(
+        os.path.join(HERE, "dem_f32_deflate.tif"),
+        order="<", rows=4, cols=5, fmt="f", sample_format=3, pixels=dem, compression=8,
+        epsg=4326, origin=(-112.0, 33.4), pixel_size=(0.1, 0.1), names=["DEM"],
+        nodata="-9999", rows_per_strip=2,
+    )
+
+    cover = [tuple((b * 50 + r * 3 + c) % 256 for b in range(3)) for r in range(18) for c in range(20)]
+    write_geotiff(
+        os.path.join(HERE, "landcover_u8_lzw_tiled.tif"),
+        order="<", rows=18, cols=20, fmt="B", sample_format=1, pixels=cover, compression=5,
+        epsg=32611, origin=(400000.0, 3700000.0), pixel_size=(30.0, 30.0),
+        names=["Red", "Green", "NIR"], tile=16,
+    )
+
+    elevation = [((r * 3 + c) * 100 - 400,) for r in range(3) for c in range(3)]
+    elevation[4] = (-32768,)
+    write_geotiff(
+        os.path.join(HERE, "elevation_i16_be.tif"),
+        order=">", rows=3, cols=3, fmt="h", sample_format=2, pixels=elevation, compression=1,
+        epsg=32612, origin=(300000.0, 3800000.0), pixel_size=(10.0, 10.0), names=["Elevation"],
+        nodata="-32768", rows_per_strip=3,
+    )
+
+
+if __name__ == "__main__":
+    main()
@@ -2,6 +2,8 @@
 
 use super::agent::SwarmAgent;
 use super::collective::SwarmCollective;
+use cybersomething_core::math::vehicle_routing::{Stop, TimeWindow};
+use cybersomething_core::models::LatLon;
 use serde::{Deserialize, Serialize};
 
 /// Mission objective
@@ -15,6 +17,14 @@ pub struct MissionObjective {
     pub deadline_seconds: u32,
 }
 
+impl MissionObjective {
+    /// Routing stop for the target zone, due by the objective deadline
+    pub fn to_stop(&self, location: LatLon, demand: f64, service_s: f64) -> Stop {
+        Stop::new(self.target_zone_id, location, demand, service_s)
+            .with_window(TimeWindow::deadline(self.deadline_seconds as f64))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ObjectiveType {
     Survey,
@@ -155,6 +165,10 @@ mod tests {
             deadline_seconds: 3600,
         };
         assert_eq!(mission.urgency, 0.8);
+
+        let stop = mission.to_stop(LatLon::new(33.4, -112.0), 0.0, 120.0);
+        assert_eq!(stop.stop_id, 100);
+        assert_eq!(stop.window.unwrap().latest_s, 3600.0);
     }
 
     #[test]
@@ -1,5 +1,6 @@
 //! Discrete-event simulator for ecological recovery scenarios
 
+use cybersomething_core::math::flight_energy::{ElevationSource, FlatTerrain, FlightConditions, FlightEnergyModel, FlightLeg};
 use std::collections::VecDeque;
 
 #[derive(Debug, Clone)]
@@ -11,6 +12,8 @@ pub struct SimEvent {
 #[derive(Debug, Clone)]
 pub enum EventType {
     AgentMoves { agent_id: u64, distance_m: f64 },
+    DroneFlight { drone_id: u64, leg: FlightLeg },
+    DroneFlightFailed { drone_id: u64, reason: String },
     WaterApplied { zone_id: u32, liters: f64 },
     TreeSprout { zone_id: u32, count: u32 },
     Wildfire { zone_id: u32, severity: f64 },
@@ -21,6 +24,10 @@ pub struct SimulationEngine {
     current_time: f64,
     event_queue: VecDeque<SimEvent>,
     zone_states: std::collections::HashMap<u32, ZoneState>,
+    flight: Option<(FlightEnergyModel, FlightConditions)>,
+    terrain: Box<dyn ElevationSource>,
+    drone_energy_wh: std::collections::HashMap<u64, f64>, // Cumulative flight energy
+    failed_legs: std::collections::HashMap<u64, u32>,     // Legs the energy model rejected
 }
 
 #[derive(Debug, Clone)]
@@ -38,9 +45,40 @@ impl SimulationEngine {
             current_time: 0.0,
             event_queue: VecDeque::new(),
             zone_states: std::collections::HashMap::new(),
+            flight: None,
+            terrain: Box::new(FlatTerrain(0.0)),
+            drone_energy_wh: std::collections::HashMap::new(),
+            failed_legs: std::collections::HashMap::new(),
         }
     }
 
+    /// Cost drone flights with the terrain/wind-aware energy model
+    pub fn with_flight_model(mut self, model: FlightEnergyModel, conditions: FlightConditions) -> Self {
+        self.flight = Some((model, conditions));
+        self
+    }
+
+    /// Take climb and descent energy from a DEM instead of flat ground
+    pub fn with_terrain(mut self, terrain: Box<dyn ElevationSource>) -> Self {
+        self.terrain = terrain;
+        self
+    }
+
+    /// Flight energy consumed by a drone so far (Wh)
+    pub fn energy_used_wh(&self, drone_id: u64) -> f64 {
+        self.drone_energy_wh.get(&drone_id).copied().unwrap_or(0.0)
+    }
+
+    /// Legs a drone could not fly (e.g. headwind above airspeed)
+    pub fn failed_legs(&self, drone_id: u64) -> u32 {
+        self.failed_legs.get(&drone_id).copied().unwrap_or(0)
+    }
+
+    /// Whether any of a drone's legs could not be flown
+    pub fn mission_failed(&self, drone_id: u64) -> bool {
+        self.failed_legs(drone_id) > 0
+    }
+
     pub fn enqueue_event(&mut self, event: SimEvent) {
         self.event_queue.push_back(event);
     }
@@ -62,6 +100,26 @@ impl SimulationEngine {
                         zone.tree_density += *count as f64 / 100.0;
                     }
                 }
+                EventType::DroneFlight { drone_id, leg } => {
+                    if let Some((model, conditions)) = &self.flight {
+                        match model.leg_energy(leg, conditions, self.terrain.as_ref()) {
+                            Ok(energy) => {
+                                *self.drone_energy_wh.entry(*drone_id).or_insert(0.0) += energy.total_wh;
+                            }
+                            Err(err) => {
+                                // Unflyable leg: count it and report the failure as the next event
+                                *self.failed_legs.entry(*drone_id).or_insert(0) += 1;
+                                self.event_queue.push_front(SimEvent {
+                                    time: event.time,
+                                    event_type: EventType::DroneFlightFailed {
+                                        drone_id: *drone_id,
+                                        reason: err.to_string(),
+                                    },
+                                });
+                            }
+                        }
+                    }
+                }
                 EventType::Wildfire { zone_id, severity } => {
                     if let Some(zone) = self.zone_states.get_mut(zone_id) {
                         zone.tree_density *= (1.0 - severity).max(0.0);
@@ -108,4 +166,44 @@ mod tests {
         let result = sim.step();
         assert!(result.is_some());
     }
+
+    #[test]
+    fn test_drone_flight_energy() {
+        use cybersomething_core::models::{DroneType, LatLon};
+
+        let model = FlightEnergyModel::for_drone_type(DroneType::Quadcopter);
+        let mut sim = SimulationEngine::new().with_flight_model(model, FlightConditions::default());
+        let leg = FlightLeg::new(LatLon::new(33.40, -112.00), LatLon::new(33.45, -112.00));
+        sim.enqueue_event(SimEvent {
+            time: 0.0,
+            event_type: EventType::DroneFlight { drone_id: 7, leg },
+        });
+        sim.step();
+        assert!(sim.energy_used_wh(7) > 0.0);
+        assert_eq!(sim.energy_used_wh(8), 0.0);
+        assert!(!sim.mission_failed(7));
+    }
+
+    #[test]
+    fn test_unflyable_leg_fails_mission() {
+        use cybersomething_core::math::flight_energy::Wind;
+        use cybersomething_core::models::{DroneType, LatLon};
+
+        // 40 m/s wind from the north against a northbound leg
+        let model = FlightEnergyModel::for_drone_type(DroneType::Quadcopter);
+        let conditions = FlightConditions { wind: Wind::new(40.0, 0.0), ..Default::default() };
+        let mut sim = SimulationEngine::new().with_flight_model(model, conditions);
+        let leg = FlightLeg::new(LatLon::new(33.40, -112.00), LatLon::new(33.45, -112.00));
+        sim.enqueue_event(SimEvent {
+            time: 0.0,
+            event_type: EventType::DroneFlight { drone_id: 7, leg },
+        });
+
+        sim.step();
+        assert_eq!(sim.energy_used_wh(7), 0.0);
+        assert_eq!(sim.failed_legs(7), 1);
+        assert!(sim.mission_failed(7));
+        let failure = sim.step().unwrap();
+        assert!(matches!(failure.event_type, EventType::DroneFlightFailed { drone_id: 7, .. }));
+    }
 }